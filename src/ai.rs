@@ -0,0 +1,41 @@
+use crate::parser::parse_message;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::thread;
+
+// What the user's summarizer command proposed
+#[derive(Debug, Clone)]
+pub struct AiSuggestion {
+    pub subject: String,
+    pub body: String,
+}
+
+// Run the configured command with the staged diff on stdin. The first line of its
+// output is the subject (a full conventional header is accepted too), the rest the body.
+pub fn run_summarizer(command: &str, diff: &str) -> Result<AiSuggestion, String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run '{}': {}", command, e))?;
+
+    // Feed stdin from another thread so a chatty command can't deadlock us
+    let mut stdin = child.stdin.take().ok_or("Could not open the command's stdin")?;
+    let diff = diff.to_string();
+    let writer = thread::spawn(move || stdin.write_all(diff.as_bytes()));
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    let _ = writer.join();
+    if !output.status.success() {
+        return Err(format!("'{}' failed: {}", command, String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    let parsed = parse_message(&String::from_utf8_lossy(&output.stdout));
+    if parsed.subject.is_empty() {
+        return Err(format!("'{}' printed no suggestion", command));
+    }
+    Ok(AiSuggestion { subject: parsed.subject, body: parsed.body })
+}
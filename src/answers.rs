@@ -0,0 +1,48 @@
+use crate::state::Prefill;
+use serde::Deserialize;
+use std::io::Read;
+use std::{fs, path::Path};
+
+// Pre-supplied wizard answers, e.g.
+//   type = "feat"
+//   scope = "api"
+//   subject = "add rate limiting"
+//   footers = ["Refs: #42"]
+// A key that is present counts as answered, even if empty (`scope = ""` means no scope).
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Answers {
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub breaking: Option<String>,
+    pub footers: Option<Vec<String>>,
+}
+
+impl Answers {
+    // `-` reads the answers from stdin
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let content = if path == Path::new("-") {
+            let mut content = String::new();
+            std::io::stdin().read_to_string(&mut content)?;
+            content
+        } else {
+            fs::read_to_string(path)?
+        };
+        Ok(toml::from_str(&content)?)
+    }
+
+    pub fn to_prefill(&self) -> Prefill {
+        Prefill {
+            commit_type: self.commit_type.clone(),
+            scope: self.scope.clone(),
+            subject: self.subject.clone(),
+            body: self.body.clone(),
+            breaking: self.breaking.clone(),
+            issue: self.footers.as_ref().map(|f| f.join("\n")),
+            bang: false,
+        }
+    }
+}
@@ -0,0 +1,1106 @@
+use commitui::ai::run_summarizer;
+use commitui::config::{self, Config, PanePosition, WizardLayout};
+use commitui::git::{
+    has_unstaged_changes, identity, remote_branches_containing, staged_changes, staged_patch, CommitOptions, Identity,
+    LogEntry, StagedChange,
+};
+use commitui::i18n::{t, tf};
+use commitui::validation::{subject_warning, validate_subject};
+use crate::jobs::{Job, JobResult, JobStatus, RepoFacts};
+use crate::manage::ManagedList;
+use crate::render::View;
+use crate::reword::count_violations;
+use crate::review::Review;
+use commitui::state::{AppState, FormField, Step};
+use crate::steps;
+use crate::tui::{build_message, centered_rect};
+use ratatui::{
+    Frame,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    layout::{Layout, Constraint, Direction, Rect},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use std::cell::{Cell, RefCell};
+use std::time::{Duration, Instant};
+
+pub const STEPS: [Step; 6] = [Step::Type, Step::Scope, Step::Subject, Step::Body, Step::Breaking, Step::Preview];
+
+// Keys `handle_key` takes on every step, before the step's own ones, with the
+// locale key describing each (for `commiTUI help`)
+pub const GLOBAL_KEYS: &[(&str, &str)] = &[
+    ("Esc / Ctrl+C", "help.quit"),
+    ("Ctrl+P", "help.live_preview"),
+    ("Ctrl+R", "help.reload"),
+    ("Ctrl+Z", "help.suspend"),
+    ("Ctrl+S", "help.skip"),
+    ("Ctrl+G", "help.suggestions"),
+    ("Ctrl+A", "help.summarizer"),
+    ("Ctrl+F", "help.auto_fix"),
+    ("Ctrl+K", "help.remember_scope"),
+    ("Ctrl+L", "help.bullet"),
+    ("Ctrl+W", "help.reflow"),
+];
+
+// How long `step_transition` highlights a new step
+const TRANSITION: Duration = Duration::from_millis(300);
+
+// What the event loop should do after a key press
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    Continue,
+    Confirm,
+    Quit,
+    // Needs the terminal and event source, so the loop runs the screen itself
+    OpenHistory(Vec<LogEntry>),
+    // Re-read the config files; the loop owns the config the App borrows
+    ReloadConfig,
+    // Review the branch's commits since the base (a range like `origin/main..HEAD`)
+    Reword(String),
+    // Edit the type or scope list and save it to the local config
+    Manage(ManagedList),
+}
+
+// Everything the wizard screens read and write, without the terminal
+pub struct App<'a> {
+    pub config: &'a Config,
+    pub state: &'a mut AppState,
+    // None in editor mode, where git itself owns the commit invocation
+    pub options: Option<&'a mut CommitOptions>,
+    // The commented status lines git wrote into COMMIT_EDITMSG
+    pub git_comments: &'a [String],
+    // Background work in flight (suggestions, summarizer, history)
+    pub job: Option<Job>,
+    // The repository facts below, still being read; the preview shows a
+    // placeholder for them and won't confirm until they're in
+    pub loading: Option<Job>,
+    // Remote branch HEAD is already on when `--amend` would rewrite it; only `y` confirms then
+    pub published: Option<String>,
+    // Author and signing key the commit will get; read when we run `git commit` ourselves
+    pub identity: Option<Identity>,
+    // What the commit will contain, listed next to the message on the preview
+    pub staged: Vec<StagedChange>,
+    // Commits on the branch since `base_branch` that break the rules (R rewords them)
+    pub branch_violations: usize,
+    // Unstaged changes exist next to the staged ones, so stashing them is worth offering
+    pub unstaged: bool,
+    // One-off message in the corner (e.g. after a config reload), gone on the next key
+    pub notice: Option<String>,
+    // `show <rev>`: the preview is someone's commit, read-only, with its rule violations
+    pub review: Option<Review>,
+    // Last (type, subject) validated and its (error, warning), reused until either changes
+    subject_cache: RefCell<Option<(SubjectKey, SubjectCheck)>>,
+    // The last frame had fewer rows than `compact_height`; keys follow what was drawn
+    compact: Cell<bool>,
+    // When the step last changed, while `step_transition` highlights it
+    transition: Option<Instant>,
+}
+
+type SubjectKey = (Option<String>, String);
+type SubjectCheck = (Option<String>, Option<String>);
+
+// The job's result once it's done; a finished or failed job leaves its slot
+fn finished(slot: &mut Option<Job>) -> Option<JobResult> {
+    match slot.as_ref()?.status() {
+        JobStatus::Running => None,
+        JobStatus::Failed => slot.take().and(None),
+        JobStatus::Done(result) => {
+            *slot = None;
+            Some(result)
+        }
+    }
+}
+
+// `base_branch..HEAD` commits breaking the rules; 0 when the check is off or the base is missing
+pub fn count_branch_violations(config: &Config) -> usize {
+    let base = config.base_branch.clone().unwrap_or_else(config::default_base_branch);
+    if base.is_empty() {
+        return 0;
+    }
+    count_violations(&base, config).unwrap_or(0)
+}
+
+pub fn step_title(step: &Step) -> &'static str {
+    match step {
+        Step::Type => t("steps.type"),
+        Step::Scope => t("steps.scope"),
+        Step::Subject => t("steps.subject"),
+        Step::Body => t("steps.body"),
+        Step::Breaking => t("steps.breaking"),
+        Step::Preview => t("steps.preview"),
+    }
+}
+
+fn step_number(step: &Step) -> usize {
+    match step {
+        Step::Type => 1,
+        Step::Scope => 2,
+        Step::Subject => 3,
+        Step::Body => 4,
+        Step::Breaking => 5,
+        Step::Preview => 6,
+    }
+}
+
+impl<'a> App<'a> {
+    pub fn new(
+        config: &'a Config,
+        state: &'a mut AppState,
+        options: Option<&'a mut CommitOptions>,
+        git_comments: &'a [String],
+    ) -> Self {
+        let loading = options.as_deref().map(|options| {
+            let (amends, config) = (options.amends(), config.clone());
+            Job::spawn(t("jobs.repo"), move || JobResult::Repo(Box::new(RepoFacts {
+                published: amends.then(|| remote_branches_containing("HEAD").into_iter().next()).flatten(),
+                identity: identity(),
+                staged: staged_changes().unwrap_or_default(),
+                unstaged: has_unstaged_changes(),
+                branch_violations: count_branch_violations(&config),
+            })))
+        });
+        let mut app = App {
+            config,
+            state,
+            options,
+            git_comments,
+            job: None,
+            loading,
+            published: None,
+            identity: None,
+            staged: Vec::new(),
+            unstaged: false,
+            branch_violations: 0,
+            notice: None,
+            review: None,
+            subject_cache: RefCell::new(None),
+            compact: Cell::new(false),
+            transition: None,
+        };
+        if app.in_form() {
+            steps::form::enter(&mut app);
+        }
+        app
+    }
+
+    // With `layout = "form"`, every step but the preview is on one screen
+    pub fn in_form(&self) -> bool {
+        self.config.layout == Some(WizardLayout::Form) && self.state.step != Step::Preview
+    }
+
+    // A step transition is still being drawn, so the loop has to keep redrawing
+    pub fn animating(&self) -> bool {
+        self.animating_at(Instant::now())
+    }
+
+    // `animating` as of `now`, so the fade can be checked without waiting for it
+    fn animating_at(&self, now: Instant) -> bool {
+        self.transition.is_some_and(|start| now.duration_since(start) < TRANSITION)
+    }
+
+    // Short terminal: the steps drop their borders and lists get the `/` filter
+    pub fn compact(&self) -> bool {
+        self.compact.get()
+    }
+
+    pub fn draw(&self, f: &mut Frame) {
+        let compact_height = self.config.compact_height.unwrap_or_else(config::default_compact_height);
+        self.compact.set(f.size().height < compact_height);
+        let view = View::of(self);
+        let progress_paragraph = Paragraph::new(view.header(self));
+        let chunks_outer = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // For the breadcrumb bar
+                Constraint::Min(1),    // For the main content
+            ])
+            .split(f.size());
+        f.render_widget(progress_paragraph, chunks_outer[0]);
+
+        let mut area = chunks_outer[1]; // Main drawing area
+
+        // The preview step shows the message anyway
+        if self.state.live_preview && self.state.step != Step::Preview {
+            let (step_area, pane_area) = self.split_for_pane(area);
+            area = step_area;
+            let message = build_message(self.state, self.config);
+            let mut lines = message.lines();
+            let header = lines.next().map(|header| steps::preview::header_line(header, self.config));
+            let paragraph = Paragraph::new(header.into_iter().chain(lines.map(Line::from)).collect::<Vec<_>>())
+                .block(Block::default()
+                    .title(t("titles.live_preview"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::DarkGray)))
+                .style(Style::default().fg(Color::Yellow))
+                .wrap(Wrap { trim: false });
+            f.render_widget(paragraph, pane_area);
+        }
+
+        view.draw(self, f, area);
+
+        // `step_transition`: the new step's title row lights up, then fades to bold
+        if let Some(elapsed) = self.transition.map(|start| start.elapsed()).filter(|e| *e < TRANSITION) {
+            let style = if elapsed < TRANSITION / 2 {
+                Style::default().bg(Color::DarkGray).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().add_modifier(Modifier::BOLD)
+            };
+            f.buffer_mut().set_style(Rect { height: 1.min(area.height), ..area }, style);
+        }
+
+        if let Some(text) = self.active_input() {
+            steps::draw_snippet_hints(self, f, area, text);
+        }
+
+        if let Some(job) = &self.job {
+            let text = tf("text.job", &[("spinner", &job.spinner().to_string()), ("label", job.label)]);
+            let width = (text.chars().count() as u16 + 2).min(area.width);
+            let popup = Rect { x: area.x + area.width - width, y: area.y, width, height: 3.min(area.height) };
+            let paragraph = Paragraph::new(text)
+                .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+            f.render_widget(Clear, popup);
+            f.render_widget(paragraph, popup);
+        }
+
+        if let (None, Some(notice)) = (&self.job, &self.notice) {
+            let width = (notice.chars().count() as u16 + 2).min(area.width);
+            let popup = Rect { x: area.x + area.width - width, y: area.y, width, height: 3.min(area.height) };
+            let paragraph = Paragraph::new(notice.as_str())
+                .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Green)));
+            f.render_widget(Clear, popup);
+            f.render_widget(paragraph, popup);
+        }
+
+        // Summarizer overlay on top of whichever step asked for it
+        if let Some(suggestion) = &self.state.ai_suggestion {
+            let (title, text, color) = match suggestion {
+                Ok(s) if self.state.step == Step::Subject => (t("titles.suggested_subject"), s.subject.clone(), Color::Cyan),
+                Ok(s) => (t("titles.suggested_body"), s.body.clone(), Color::Cyan),
+                Err(e) => (t("titles.summarizer_error"), e.clone(), Color::Red),
+            };
+            let popup = centered_rect(area, 80, (text.lines().count() as u16 + 2).max(3));
+            let paragraph = Paragraph::new(text)
+                .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(color)))
+                .wrap(Wrap { trim: false });
+            f.render_widget(Clear, popup);
+            f.render_widget(paragraph, popup);
+        }
+    }
+
+    // The text field keys are currently typed into, if any
+    pub fn active_input(&self) -> Option<&str> {
+        let state = &*self.state;
+        if self.in_form() {
+            return steps::form::active_input(state);
+        }
+        match state.step {
+            Step::Scope if state.focus_input => Some(&state.custom_scope),
+            Step::Subject if state.focus_input => Some(&state.subject),
+            Step::Body if state.focus_input => Some(&state.body),
+            Step::Breaking if state.focus_input && state.has_breaking => Some(&state.breaking),
+            Step::Preview if state.smart_commit_field.is_some() => {
+                state.smart_commit_field.map(|field| state.smart_commit.field(field).as_str())
+            }
+            Step::Preview if state.focus_issues => Some(&state.issues),
+            _ => None,
+        }
+    }
+
+    pub fn is_skippable(&self) -> bool {
+        let name = self.state.step.name();
+        self.config.skippable_steps.as_ref().is_some_and(|steps| steps.iter().any(|s| s == name))
+    }
+
+    // Key hint for the current step's title
+    pub fn skip_hint(&self) -> &'static str {
+        if self.is_skippable() { t("text.skip_hint") } else { "" }
+    }
+
+    // `1 ✓ feat ▸ 2 ✓ api ▸ 3 Subject ▸ 4 Body ▸ ...`: answered steps show their
+    // answer, numbered as on the preview step where they can be jumped to
+    pub fn breadcrumb(&self) -> Line<'static> {
+        let state = &*self.state;
+        let current = step_number(&state.step);
+        let mut spans = Vec::new();
+        for (i, step) in STEPS.iter().enumerate() {
+            let number = i + 1;
+            if step.is_hidden(self.config) {
+                continue;
+            }
+            if !spans.is_empty() {
+                spans.push(Span::styled(" ▸ ", Style::default().fg(Color::DarkGray)));
+            }
+            // While editing from the preview, every other step is already answered
+            let done = number < current || (state.return_to_preview && number != current);
+            let (label, style) = if number == current {
+                (step_title(step).to_string(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD))
+            } else if done {
+                (format!("✓ {}", self.answer_label(step)), Style::default().fg(Color::Green))
+            } else {
+                (step_title(step).to_string(), Style::default().fg(Color::DarkGray))
+            };
+            spans.push(Span::styled(format!("{} {}", number, label), style));
+        }
+        Line::from(spans)
+    }
+
+    fn answer_label(&self, step: &Step) -> String {
+        let state = &*self.state;
+        match step {
+            Step::Type => state.chosen_type.clone().unwrap_or_else(|| t("breadcrumb.no_type").to_string()),
+            Step::Scope => state.chosen_scope.clone().unwrap_or_else(|| t("breadcrumb.no_scope").to_string()),
+            Step::Subject if state.subject.chars().count() > 20 => {
+                format!("{}…", state.subject.chars().take(20).collect::<String>())
+            }
+            Step::Subject => state.subject.clone(),
+            Step::Body if state.body_lines.is_empty() => t("breadcrumb.no_body").to_string(),
+            Step::Body => tf("breadcrumb.body_lines", &[("count", &state.body_lines.len().to_string())]),
+            Step::Breaking if state.has_breaking => t("breadcrumb.breaking").to_string(),
+            Step::Breaking => t("breadcrumb.not_breaking").to_string(),
+            Step::Preview => step_title(step).to_string(),
+        }
+    }
+
+    // Subject validation error and warning, only recomputed when the subject
+    // (or the type, which may have its own rules) changed
+    pub fn subject_check(&self) -> SubjectCheck {
+        let mut cache = self.subject_cache.borrow_mut();
+        let key = (self.state.chosen_type.clone(), self.state.subject.clone());
+        if let Some((cached, check)) = cache.as_ref() {
+            if *cached == key {
+                return check.clone();
+            }
+        }
+        let check = (
+            validate_subject(self.state.chosen_type.as_deref(), &self.state.subject, self.config),
+            subject_warning(self.state.chosen_type.as_deref(), &self.state.subject, self.config),
+        );
+        *cache = Some((key, check.clone()));
+        check
+    }
+
+    pub fn start_job(&mut self, label: &'static str, work: impl FnOnce() -> JobResult + Send + 'static) {
+        self.job = Some(Job::spawn(label, work));
+    }
+
+    // Apply a finished job's result; called by the event loop on every tick
+    pub fn poll_job(&mut self) -> Option<Action> {
+        if let Some(JobResult::Repo(facts)) = finished(&mut self.loading) {
+            let RepoFacts { published, identity, staged, unstaged, branch_violations } = *facts;
+            (self.published, self.identity, self.staged) = (published, identity, staged);
+            (self.unstaged, self.branch_violations) = (unstaged, branch_violations);
+        }
+        match finished(&mut self.job)? {
+            JobResult::Suggestions(suggestions) => {
+                self.state.suggestions = suggestions;
+                self.state.selected_suggestion = 0;
+                None
+            }
+            // Only useful on the steps it can fill in
+            JobResult::Summary(suggestion) if matches!(self.state.step, Step::Subject | Step::Body) => {
+                self.state.ai_suggestion = Some(suggestion);
+                None
+            }
+            JobResult::Summary(_) => None,
+            JobResult::History(entries) => Some(Action::OpenHistory(entries)),
+            JobResult::Repo(_) => None,
+            JobResult::Violations(count) => {
+                self.branch_violations = count;
+                None
+            }
+        }
+    }
+
+    // Step area and live preview pane, placed as configured (bottom by default)
+    fn split_for_pane(&self, area: Rect) -> (Rect, Rect) {
+        let chunks = match self.config.preview_pane {
+            Some(PanePosition::Right) => Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+                .split(area),
+            _ => Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Min(3), Constraint::Length((area.height / 3).max(3))])
+                .split(area),
+        };
+        (chunks[0], chunks[1])
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> Action {
+        let (step, in_form) = (self.state.step.clone(), self.in_form());
+        let action = self.dispatch_key(key);
+        let moved = self.state.step != step && !(in_form && self.in_form()); // The form is one screen
+        if moved && self.config.step_transition.unwrap_or_else(config::default_step_transition) {
+            self.transition = Some(Instant::now());
+        }
+        action
+    }
+
+    fn dispatch_key(&mut self, key: KeyEvent) -> Action {
+        if key.kind != KeyEventKind::Press {
+            return Action::Continue;
+        }
+        self.state.input_rejected = false; // The flash lasts until the next key
+        self.notice = None;
+
+        // Esc cancels a running job instead of quitting
+        if self.job.is_some() && key.code == KeyCode::Esc {
+            self.job = None;
+            return Action::Continue;
+        }
+
+        // The suggestion popup captures keys (including Esc) while it's open
+        if !self.state.suggestions.is_empty() {
+            steps::subject::handle_suggestion_key(self, key);
+            return Action::Continue;
+        }
+
+        // Same for the summarizer overlay: accept or reject
+        if let Some(suggestion) = &self.state.ai_suggestion {
+            match key.code {
+                KeyCode::Enter | KeyCode::Char('y') => {
+                    // An empty suggestion leaves what was typed alone
+                    if let Ok(suggestion) = suggestion {
+                        if self.state.step == Step::Subject {
+                            if !suggestion.subject.trim().is_empty() {
+                                self.state.subject = suggestion.subject.clone();
+                            }
+                        } else if !suggestion.body.trim().is_empty() {
+                            self.state.body_lines = suggestion.body.lines().map(|l| l.to_string()).collect();
+                            self.state.body.clear();
+                        }
+                    }
+                    self.state.ai_suggestion = None;
+                }
+                KeyCode::Esc | KeyCode::Char('n') => {
+                    self.state.ai_suggestion = None;
+                }
+                _ => {}
+            }
+            return Action::Continue;
+        }
+
+        // Ctrl+A asks the configured summarizer on the Subject and Body steps
+        let ai_command = self.config.ai.as_ref().and_then(|ai| ai.command.as_deref());
+        if let (Some(command), Step::Subject | Step::Body, false) = (ai_command, &self.state.step, self.in_form()) {
+            if key.code == KeyCode::Char('a') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                let command = command.to_string();
+                self.start_job(t("jobs.summarizer"), move || {
+                    let diff = staged_patch().unwrap_or_default();
+                    JobResult::Summary(run_summarizer(&command, &diff))
+                });
+                return Action::Continue;
+            }
+        }
+
+        // Ctrl+P toggles the live preview pane anywhere
+        if key.code == KeyCode::Char('p') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.state.live_preview = !self.state.live_preview;
+            return Action::Continue;
+        }
+
+        // Ctrl+R picks up config edits without leaving the wizard
+        if key.code == KeyCode::Char('r') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Action::ReloadConfig;
+        }
+
+        // Global quit hotkeys (Esc or Ctrl+C) always work
+        if (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
+            || key.code == KeyCode::Esc
+        {
+            return Action::Quit;
+        }
+
+        if self.in_form() {
+            return steps::form::handle_key(self, key);
+        }
+
+        // Ctrl+S skips an optional step; plain `s` too when not typing into an input
+        let skip_key = key.code == KeyCode::Char('s')
+            && (key.modifiers.contains(KeyModifiers::CONTROL) || (key.modifiers.is_empty() && !self.state.focus_input));
+
+        let from = step_number(&self.state.step);
+        let action = match self.state.step {
+            Step::Scope if skip_key && self.is_skippable() => steps::scope::skip(self),
+            Step::Body if skip_key && self.is_skippable() => steps::body::skip(self),
+            Step::Breaking if skip_key && self.is_skippable() => steps::breaking::skip(self),
+            Step::Type => steps::commit_type::handle_key(self, key),
+            Step::Scope => steps::scope::handle_key(self, key),
+            Step::Subject => steps::subject::handle_key(self, key),
+            Step::Body => steps::body::handle_key(self, key),
+            Step::Breaking => steps::breaking::handle_key(self, key),
+            Step::Preview => steps::preview::handle_key(self, key),
+        };
+
+        // Never stop on a hidden step, in whichever direction the user was going
+        if step_number(&self.state.step) != from {
+            self.state.list_filter = None;
+            let forward = step_number(&self.state.step) > from;
+            self.state.pass_hidden_steps(self.config, forward);
+        }
+
+        // A step picked from the preview returns there once it's confirmed
+        if self.state.return_to_preview && step_number(&self.state.step) > from {
+            self.state.step = Step::Preview;
+            self.state.focus_issues = false;
+            self.state.return_to_preview = false;
+        }
+
+        // Back from the preview: the form opens on the field picked there (1-5)
+        if self.in_form() {
+            if self.state.return_to_preview {
+                self.state.form_field = FormField::from_step(&self.state.step);
+                self.state.return_to_preview = false;
+            }
+            steps::form::enter(self);
+            return action;
+        }
+
+        // Special handling for multi-line body input state
+        if matches!(self.state.step, Step::Body) && !self.state.in_body {
+            self.state.body.clear(); // Clear current line when entering body step for first time
+            self.state.in_body = true;
+            self.state.focus_input = true; // Ensure body input starts focused
+            steps::body::apply_template(self);
+        }
+        if !matches!(self.state.step, Step::Body) {
+            self.state.in_body = false;
+        }
+
+        action
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use commitui::config::{preset, BreakingPosition, Convention, JiraConfig, MergeConfig, ScopeGroup, TypeRules};
+    use commitui::message::CommitMessage;
+    use commitui::validation::validate_issues;
+    use commitui::state::Prefill;
+
+    fn press(app: &mut App, code: KeyCode) -> Action {
+        app.handle_key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    #[test]
+    fn back_from_scope_restores_chosen_type() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Continue);
+        assert_eq!(app.state.step, Step::Scope);
+
+        app.state.selected_type = 0;
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.state.step, Step::Type);
+        assert_eq!(app.state.selected_type, 2);
+        assert_eq!(press(&mut app, KeyCode::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn editing_from_preview_returns_to_preview() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("feat".to_string());
+        state.subject = "add thing".to_string();
+        state.step = Step::Preview;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(app.state.step, Step::Subject);
+        press(&mut app, KeyCode::Char('s'));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Preview);
+        assert_eq!(app.state.subject, "add things");
+        assert!(!app.state.return_to_preview);
+    }
+
+    #[test]
+    fn step_transition_only_when_the_step_changes() {
+        let config = Config { step_transition: Some(true), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Down);
+        assert!(!app.animating());
+        press(&mut app, KeyCode::Enter);
+        assert!(app.animating());
+        let start = app.transition.unwrap();
+        assert!(app.animating_at(start + TRANSITION / 2));
+        assert!(!app.animating_at(start + TRANSITION));
+    }
+
+    #[test]
+    fn form_layout_edits_every_field_on_one_screen() {
+        let config = Config { layout: Some(WizardLayout::Form), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        let mut app = App::new(&config, &mut state, None, &[]);
+        let type_text = |app: &mut App, text: &str| text.chars().for_each(|c| { press(app, KeyCode::Char(c)); });
+
+        assert!(app.in_form());
+        assert_eq!(app.state.chosen_type.as_deref(), Some("feat"));
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Down);
+        press(&mut app, KeyCode::Down);
+        assert_eq!(app.state.chosen_scope.as_deref(), Some("api"));
+
+        // Enter goes to the first field that isn't right yet
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.state.form_field, FormField::Body);
+        press(&mut app, KeyCode::Tab);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.form_field, FormField::Subject);
+        type_text(&mut app, "handle empty input");
+        press(&mut app, KeyCode::Tab);
+        type_text(&mut app, "first");
+        press(&mut app, KeyCode::Enter);
+        type_text(&mut app, "second");
+        app.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Preview);
+        assert_eq!(build_message(app.state, &config), "fix(api): handle empty input\n\nfirst\nsecond\n");
+
+        // Picking a step on the preview reopens the form on that field
+        press(&mut app, KeyCode::Char('5'));
+        assert!(app.in_form());
+        assert_eq!(app.state.form_field, FormField::Breaking);
+        type_text(&mut app, "drops the v1 API");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Preview);
+        assert!(build_message(app.state, &config).ends_with("\n\nBREAKING CHANGE: drops the v1 API\n"));
+    }
+
+    #[test]
+    fn breaking_yes_requires_a_description() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.step = Step::Breaking;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Char('y'));
+        assert!(app.state.has_breaking);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Breaking);
+
+        // The footer token is added by the formatter, so typing it is refused
+        for c in "BREAKING CHANGE: x".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Breaking);
+
+        app.state.breaking = "x".to_string();
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Preview);
+        assert_eq!(app.state.breaking, "x");
+    }
+
+    #[test]
+    fn plain_convention_hides_type_scope_and_breaking() {
+        let config = preset_config(Convention::Plain);
+        let mut state = AppState::new(&config, Prefill::default());
+        assert_eq!(state.step, Step::Subject);
+        assert_eq!(state.chosen_type.as_deref(), Some("plain"));
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        // Back from the first visible step stays there
+        app.state.focus_input = false;
+        press(&mut app, KeyCode::Left);
+        assert_eq!(app.state.step, Step::Subject);
+
+        app.state.step = Step::Body;
+        app.state.in_body = true;
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Preview);
+    }
+
+    #[test]
+    fn every_preset_parses() {
+        for convention in [Convention::Angular, Convention::Gitmoji, Convention::Jira, Convention::Plain] {
+            assert!(preset(convention).is_some());
+        }
+    }
+
+    #[test]
+    fn reload_keeps_valid_answers_and_revisits_the_rest() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.chosen_scope = Some("core".to_string());
+        state.subject = "handle empty input".to_string();
+        state.step = Step::Preview;
+        assert_eq!(App::new(&config, &mut state, None, &[]).handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL)), Action::ReloadConfig);
+
+        // `core` is gone from the new scope list
+        let reloaded = Config { scopes: Some(vec!["api".to_string()]), scope_groups: Some(vec![]), allow_custom_scope: Some(false), ..Config::default() };
+        state.revalidate(&reloaded);
+        assert_eq!(state.step, Step::Scope);
+        assert!(state.return_to_preview);
+        assert_eq!(state.chosen_type.as_deref(), Some("fix"));
+        assert_eq!(state.custom_scope, "core");
+        assert_eq!(state.subject, "handle empty input");
+    }
+
+    #[test]
+    fn amending_a_pushed_commit_needs_an_explicit_yes() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "handle empty input".to_string();
+        state.step = Step::Preview;
+        let mut app = App::new(&config, &mut state, None, &[]);
+        app.published = Some("origin/main".to_string());
+
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Continue);
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Confirm);
+    }
+
+    #[test]
+    fn the_preview_waits_for_the_repository_to_be_read() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "handle empty input".to_string();
+        state.step = Step::Preview;
+        let mut options = CommitOptions::default();
+        let mut app = App::new(&config, &mut state, Some(&mut options), &[]);
+        assert!(app.loading.is_some());
+
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Continue);
+        let started = Instant::now();
+        while app.loading.is_some() && started.elapsed() < Duration::from_secs(10) {
+            app.poll_job();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert!(app.loading.is_none());
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Confirm);
+    }
+
+    #[test]
+    fn an_empty_summary_keeps_the_typed_body() {
+        use commitui::ai::AiSuggestion;
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.step = Step::Body;
+        state.body_lines = vec!["Explains why.".to_string()];
+        let suggestion = |body: &str| Some(Ok(AiSuggestion { subject: "add export".to_string(), body: body.to_string() }));
+        state.ai_suggestion = suggestion("");
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.body_lines, ["Explains why."]);
+        app.state.ai_suggestion = suggestion("Writes CSV.");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.body_lines, ["Writes CSV."]);
+    }
+
+    #[test]
+    fn n_on_the_preview_toggles_the_hooks() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.step = Step::Preview;
+        let mut options = CommitOptions::default();
+        let mut app = App::new(&config, &mut state, Some(&mut options), &[]);
+
+        press(&mut app, KeyCode::Char('n'));
+        assert!(app.options.as_deref().is_some_and(|options| options.no_verify));
+        press(&mut app, KeyCode::Char('n'));
+        assert!(!options.no_verify);
+    }
+
+    #[test]
+    fn bang_key_marks_the_header_breaking() {
+        let config = Config { bang_requires_footer: Some(true), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("feat".to_string());
+        state.chosen_scope = Some("api".to_string());
+        state.subject = "drop v1 endpoints".to_string();
+        state.step = Step::Preview;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Char('!'));
+        assert!(build_message(app.state, &config).starts_with("feat(api)!: drop v1 endpoints"));
+        // bang_requires_footer: not without a description
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Continue);
+        app.state.has_breaking = true;
+        app.state.breaking = "the v1 endpoints are gone".to_string();
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Confirm);
+
+        let config = Config::default();
+        let mut app = App::new(&config, &mut state, None, &[]);
+        app.state.has_breaking = false;
+        app.state.breaking.clear();
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Confirm);
+        assert_eq!(build_message(app.state, &config), "feat(api)!: drop v1 endpoints\n");
+    }
+
+    #[test]
+    fn header_over_the_server_limit_blocks_confirming() {
+        let config = Config { header_max_length: Some(20), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "handle empty input".to_string();
+        state.step = Step::Preview;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Continue);
+        app.state.subject = "handle it".to_string();
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Confirm);
+    }
+
+    #[test]
+    fn branch_violations_offer_the_reword_flow() {
+        let config = Config { base_branch: Some("upstream/develop".to_string()), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.step = Step::Preview;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        // Nothing to reword: R does nothing
+        assert_eq!(press(&mut app, KeyCode::Char('R')), Action::Continue);
+        app.branch_violations = 2;
+        assert_eq!(press(&mut app, KeyCode::Char('R')), Action::Reword("upstream/develop..HEAD".to_string()));
+    }
+
+    #[test]
+    fn issue_keywords_complete_and_references_are_checked() {
+        let config = Config { issue_pattern: Some("^#[0-9]+$".to_string()), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "handle empty input".to_string();
+        state.step = Step::Preview;
+        state.focus_issues = true;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        for c in "re".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Down); // Resolves, Refs
+        press(&mut app, KeyCode::Tab);
+        for c in "12 c".chars() {
+            press(&mut app, KeyCode::Char(c));
+        }
+        press(&mut app, KeyCode::Tab);
+        assert_eq!(app.state.issues, "Refs: 12\nCloses: ");
+
+        // Neither reference is valid yet
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Continue);
+        app.state.issues = "Refs: #12\nCloses: #3".to_string();
+        assert_eq!(press(&mut app, KeyCode::Enter), Action::Confirm);
+    }
+
+    #[test]
+    fn trailers_form_one_block_git_can_parse() {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+        let git_trailers = |text: &str| {
+            // Away from the user's own trailer.* settings
+            let mut git = Command::new("git")
+                .env("GIT_CONFIG_GLOBAL", "/dev/null")
+                .env("GIT_CONFIG_NOSYSTEM", "1")
+                .args(["interpret-trailers", "--parse"])
+                .stdin(Stdio::piped())
+                .stdout(Stdio::piped())
+                .spawn()
+                .unwrap();
+            git.stdin.take().unwrap().write_all(text.as_bytes()).unwrap();
+            String::from_utf8(git.wait_with_output().unwrap().stdout).unwrap()
+        };
+        let mut message = CommitMessage {
+            commit_type: Some("feat".to_string()),
+            subject: "add export".to_string(),
+            body: "Writes CSV.".to_string(),
+            footers: vec!["Refs: #1".to_string(), "Closes: #2".to_string(), "Refs: #1".to_string()],
+            ..CommitMessage::default()
+        };
+
+        let text = message.format_with(&Config::default());
+        assert_eq!(text, "feat: add export\n\nWrites CSV.\n\nRefs: #1\nCloses: #2\n");
+        assert_eq!(git_trailers(&text), "Refs: #1\nCloses: #2\n");
+
+        message.breaking = Some("drops XML".to_string());
+        assert_eq!(message.format(), "feat: add export\n\nWrites CSV.\n\nBREAKING CHANGE: drops XML\nRefs: #1\nCloses: #2\n");
+        let config = Config { breaking_position: Some(BreakingPosition::Last), ..Config::default() };
+        let text = message.format_with(&config);
+        assert_eq!(text, "feat: add export\n\nWrites CSV.\n\nRefs: #1\nCloses: #2\nBREAKING CHANGE: drops XML\n");
+        // git's tokens can't contain spaces, so it only reads the block once one of
+        // its own trailers is in it; the token is kept as Conventional Commits spells it
+        assert_eq!(git_trailers(&format!("{}Signed-off-by: A <a@example.com>\n", text)), "Refs: #1\nCloses: #2\nSigned-off-by: A <a@example.com>\n");
+
+        // Only `Key: value` lines are trailers to git
+        for issues in ["Closes #2", "see the PR", "Refs: #1\nnot a trailer"] {
+            assert!(validate_issues(issues, &config).is_some(), "{:?}", issues);
+        }
+        assert_eq!(validate_issues("Refs: #1\n\nCloses: #2", &config), None);
+    }
+
+    #[test]
+    fn type_rules_let_one_type_skip_the_subject() {
+        let wip = TypeRules { subject_min_length: Some(0), default_subject: Some("work in progress".to_string()), ..TypeRules::default() };
+        let config = Config {
+            types: Some(vec!["feat".to_string(), "wip".to_string()]),
+            type_rules: Some([("wip".to_string(), wip)].into_iter().collect()),
+            ..Config::default()
+        };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("feat".to_string());
+        state.step = Step::Subject;
+        let app = App::new(&config, &mut state, None, &[]);
+
+        assert!(app.subject_check().0.is_some());
+        app.state.chosen_type = Some("wip".to_string());
+        assert_eq!(app.subject_check().0, None);
+        assert_eq!(build_message(app.state, &config), "wip: work in progress\n");
+    }
+
+    #[test]
+    fn ctrl_f_fixes_the_subject_when_auto_fix_is_on() {
+        let config = Config { auto_fix: Some(true), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "Handle  empty input. ".to_string();
+        state.step = Step::Subject;
+        state.focus_input = true;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+        assert_eq!(app.state.subject, "handle empty input");
+        // Acronyms keep their case
+        app.state.subject = "API errors.".to_string();
+        app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+        assert_eq!(app.state.subject, "API errors");
+    }
+
+    #[test]
+    fn ctrl_f_moves_the_tail_of_an_overlong_subject_into_the_body() {
+        let config = Config { auto_fix: Some(true), subject_max_length: Some(40), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "retry uploads on timeout because the CDN drops slow connections".to_string();
+        state.body_lines = vec!["Seen in production.".to_string()];
+        state.step = Step::Subject;
+        state.focus_input = true;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::CONTROL));
+        assert_eq!(app.state.subject, "retry uploads on timeout");
+        assert_eq!(app.state.body_lines, ["Because the CDN drops slow connections.", "", "Seen in production."]);
+    }
+
+    #[test]
+    fn ctrl_l_starts_a_bullet_list_that_enter_continues() {
+        let config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("feat".to_string());
+        state.subject = "add export".to_string();
+        state.step = Step::Body;
+        state.in_body = true;
+        state.focus_input = true;
+        let mut app = App::new(&config, &mut state, None, &[]);
+        let type_text = |app: &mut App, text: &str| text.chars().for_each(|c| { press(app, KeyCode::Char(c)); });
+
+        type_text(&mut app, "CSV");
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        assert_eq!(app.state.body, "- CSV");
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.body, "- ");
+        type_text(&mut app, "JSON");
+        // An empty item ends the list; the next Enter ends the body
+        press(&mut app, KeyCode::Enter);
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.body, "");
+        assert_eq!(app.state.step, Step::Body);
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        app.handle_key(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::CONTROL));
+        press(&mut app, KeyCode::Enter);
+        assert_eq!(app.state.step, Step::Breaking);
+        assert_eq!(app.state.body_lines, ["- CSV", "- JSON"]);
+    }
+
+    #[test]
+    fn ctrl_w_rewraps_the_paragraph_being_typed() {
+        let config = Config { body_max_line_length: Some(20), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.step = Step::Body;
+        state.in_body = true;
+        state.focus_input = true;
+        state.body_lines = vec![
+            "Left alone, even though it is long.".to_string(),
+            String::new(),
+            "Intro text that runs".to_string(),
+            "on.".to_string(),
+            "- a list item that wraps around".to_string(),
+        ];
+        state.body = "- short".to_string();
+        let mut app = App::new(&config, &mut state, None, &[]);
+        assert!(steps::body::line_warning(&app).is_some_and(|warning| warning.contains("Body line 1 ")));
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL));
+        assert_eq!(app.state.body_lines, [
+            "Left alone, even though it is long.",
+            "",
+            "Intro text that runs",
+            "on.",
+            "- a list item that",
+            "  wraps around",
+        ]);
+        assert_eq!(app.state.body, "- short");
+    }
+
+    #[test]
+    fn jira_smart_commit_footer_from_its_inputs() {
+        let config = Config { jira: Some(JiraConfig { projects: Some(vec!["PROJ".to_string()]) }), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".to_string());
+        state.subject = "handle empty input".to_string();
+        state.step = Step::Preview;
+        let mut app = App::new(&config, &mut state, None, &[]);
+
+        press(&mut app, KeyCode::Char('j'));
+        for (text, next) in [("OPS-1", KeyCode::Tab), ("2h", KeyCode::Tab), ("fixed race", KeyCode::Enter)] {
+            for c in text.chars() {
+                press(&mut app, KeyCode::Char(c));
+            }
+            press(&mut app, next);
+        }
+        // OPS isn't a configured project: back to the ticket
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Continue);
+        assert_eq!(app.state.smart_commit_field, Some(0));
+        app.state.smart_commit.ticket = "PROJ-123".to_string();
+        app.state.smart_commit_field = None;
+        assert_eq!(press(&mut app, KeyCode::Char('y')), Action::Confirm);
+        assert_eq!(
+            build_message(app.state, &config),
+            "fix: handle empty input\n\nPROJ-123 #time 2h #comment fixed race\n"
+        );
+    }
+
+    fn preset_config(convention: Convention) -> Config {
+        let mut config = Config::default();
+        config.merge(preset(convention).unwrap());
+        config
+    }
+
+    #[test]
+    fn pinned_scopes_come_first_and_empty_groups_go() {
+        let config = Config {
+            scopes: Some(vec!["no scope".to_string(), "core".to_string(), "api".to_string()]),
+            scope_groups: Some(vec![ScopeGroup { name: "More".to_string(), scopes: vec!["ui".to_string()] }]),
+            ..Config::default()
+        };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.pinned_scopes = vec!["ui".to_string(), "gone".to_string()];
+        state.apply_prefill(&config, Prefill { scope: Some("api".to_string()), ..Prefill::default() });
+
+        let scopes: Vec<_> = state.scope_entries(&config).iter().map(|e| format!("{:?}", e)).collect();
+        assert_eq!(scopes, ["Scope(\"no scope\")", "Scope(\"ui\")", "Scope(\"core\")", "Scope(\"api\")"]);
+        assert_eq!(state.selected_scope, 3);
+    }
+}
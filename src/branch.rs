@@ -0,0 +1,123 @@
+use crate::config::{BranchRule, BranchTemplate};
+use crate::state::Prefill;
+use glob::Pattern;
+use regex::{Captures, Regex};
+use std::collections::BTreeMap;
+
+// The first rule whose pattern matches the branch wins. An invalid pattern
+// was already reported as a config problem, so it's just passed over here.
+pub fn derive_defaults(branch: &str, rules: &[BranchRule]) -> Prefill {
+    for rule in rules {
+        let Ok(re) = Regex::new(&rule.pattern) else { continue };
+        if let Some(caps) = re.captures(branch) {
+            return Prefill {
+                commit_type: capture_field(&caps, "type", rule.commit_type.as_deref()),
+                scope: capture_field(&caps, "scope", rule.scope.as_deref()),
+                // Branch names use separators where a subject would use spaces
+                subject: capture_field(&caps, "subject", rule.subject.as_deref())
+                    .map(|s| s.replace(['-', '_'], " ")),
+                issue: capture_field(&caps, "issue", rule.issue.as_deref()),
+                ..Prefill::default()
+            };
+        }
+    }
+    Prefill::default()
+}
+
+fn capture_field(caps: &Captures, group: &str, template: Option<&str>) -> Option<String> {
+    let value = match template {
+        Some(template) => {
+            let mut expanded = String::new();
+            caps.expand(template, &mut expanded);
+            expanded
+        }
+        None => caps.name(group).map(|m| m.as_str().to_string()).unwrap_or_default(),
+    };
+    let value = value.trim();
+    if value.is_empty() { None } else { Some(value.to_string()) }
+}
+
+// `[branch_templates]` over `defaults`: the longest glob matching the branch sets
+// the type and scope, and its footers go below whatever issue there already is.
+// A remembered issue may already hold them from last time, so none is repeated.
+pub fn apply_template(branch: &str, templates: &BTreeMap<String, BranchTemplate>, defaults: Prefill) -> Prefill {
+    let Some(template) = templates
+        .iter()
+        .filter(|(pattern, _)| Pattern::new(pattern).is_ok_and(|glob| glob.matches(branch)))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, template)| template)
+    else {
+        return defaults;
+    };
+    let mut footers: Vec<String> = defaults.issue.iter().flat_map(|issue| issue.lines()).map(String::from).collect();
+    for footer in &template.footers {
+        if !footers.contains(footer) {
+            footers.push(footer.clone());
+        }
+    }
+    Prefill {
+        commit_type: template.commit_type.clone(),
+        scope: template.scope.clone(),
+        issue: Some(footers.join("\n")).filter(|footers| !footers.is_empty()),
+        ..Prefill::default()
+    }
+    .or(defaults)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn the_built_in_rules_follow_the_configured_types() {
+        let config = Config { types: Some(vec!["feature".into(), "bugfix".into()]), ..Config::default() };
+        let rules = config.branch_rules();
+        let prefill = derive_defaults("bugfix/auth/token-expiry", &rules);
+        assert_eq!(prefill.commit_type.as_deref(), Some("bugfix"));
+        assert_eq!(prefill.scope.as_deref(), Some("auth"));
+        assert_eq!(prefill.subject.as_deref(), Some("token expiry"));
+        assert_eq!(derive_defaults("fix/login", &rules).commit_type, None);
+
+        let prefill = derive_defaults("PROJ-7-feature-export", &rules);
+        assert_eq!((prefill.commit_type.as_deref(), prefill.issue.as_deref()), (Some("feature"), Some("Refs: PROJ-7")));
+        assert_eq!(derive_defaults("fix/login", &Config::default().branch_rules()).commit_type.as_deref(), Some("fix"));
+    }
+
+    #[test]
+    fn an_invalid_rule_is_passed_over() {
+        let rule = |pattern: &str| BranchRule { pattern: pattern.into(), commit_type: None, scope: None, subject: None, issue: None };
+        let rules = [rule("(unclosed"), rule(r"^(?P<type>\w+)/(?P<subject>.+)$")];
+        assert_eq!(derive_defaults("docs/readme", &rules).commit_type.as_deref(), Some("docs"));
+    }
+
+    #[test]
+    fn branch_templates_fill_in_the_longest_matching_glob() {
+        let template = |commit_type: &str, scope: Option<&str>, footers: &[&str]| BranchTemplate {
+            commit_type: Some(commit_type.into()),
+            scope: scope.map(String::from),
+            footers: footers.iter().map(|footer| footer.to_string()).collect(),
+        };
+        let templates = [
+            ("hotfix/*".to_string(), template("fix", Some("prod"), &["Hotfix: true"])),
+            ("hotfix/db-*".to_string(), template("fix", Some("db"), &[])),
+            ("release/*".to_string(), template("chore", None, &[])),
+        ]
+        .into_iter()
+        .collect();
+        let from_name = Prefill { commit_type: Some("feat".into()), issue: Some("Refs #12".into()), ..Prefill::default() };
+
+        let hotfix = apply_template("hotfix/login", &templates, from_name.clone());
+        assert_eq!((hotfix.commit_type.as_deref(), hotfix.scope.as_deref()), (Some("fix"), Some("prod")));
+        assert_eq!(hotfix.issue.as_deref(), Some("Refs #12\nHotfix: true"));
+        // Remembered with the template's footer last time: not added twice
+        let again = apply_template("hotfix/login", &templates, Prefill { issue: hotfix.issue.clone(), ..from_name.clone() });
+        assert_eq!(again.issue, hotfix.issue);
+        // A misspelt key is reported rather than quietly doing nothing
+        assert!(toml::from_str::<BranchTemplate>("footer = [\"Hotfix: true\"]").is_err());
+        assert_eq!(apply_template("hotfix/db-index", &templates, Prefill::default()).scope.as_deref(), Some("db"));
+        let release = apply_template("release/2.0", &templates, Prefill { scope: Some("api".into()), ..Prefill::default() });
+        assert_eq!((release.commit_type.as_deref(), release.scope.as_deref()), (Some("chore"), Some("api")));
+        assert_eq!(apply_template("feature/x", &templates, from_name.clone()).commit_type, from_name.commit_type);
+    }
+}
@@ -0,0 +1,258 @@
+use clap::{Parser, Subcommand};
+use commitui::config::Convention;
+use commitui::state::Prefill;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+// Command-line flags; anything set here overrides the config files
+#[derive(Debug, Parser)]
+#[command(name = "commiTUI", version, about = "A TUI for greater commit messages", disable_help_subcommand = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Message convention preset: conventional, angular, gitmoji, jira or plain
+    #[arg(long, value_name = "NAME")]
+    pub convention: Option<Convention>,
+
+    /// Write a debug trace (keys, step changes, git calls) to the cache dir; RUST_LOG=debug does too
+    #[arg(short = 'v', long)]
+    pub verbose: bool,
+
+    /// GPG-sign the commit (git commit -S)
+    #[arg(short = 'S', long)]
+    pub gpg_sign: bool,
+
+    /// Add a Signed-off-by trailer (git commit --signoff)
+    #[arg(short = 's', long)]
+    pub signoff: bool,
+
+    /// Pick unstaged hunks to stage before writing the message (like git commit -p)
+    #[arg(short = 'p', long)]
+    pub patch: bool,
+
+    /// Split the working tree into several commits: back to the hunk picker after each one
+    #[arg(long, conflicts_with_all = ["non_interactive", "as_editor", "print_message_only", "output", "json", "copy"])]
+    pub queue: bool,
+
+    /// Stash unstaged changes for the commit (hooks see only what's committed) and pop them after
+    #[arg(long)]
+    pub stash_unstaged: bool,
+
+    /// Skip the pre-commit and commit-msg hooks (recorded with a `hooks_bypassed_footer` trailer)
+    #[arg(short = 'n', long)]
+    pub no_verify: bool,
+
+    /// Commit even with nothing staged, e.g. to trigger CI (git commit --allow-empty)
+    #[arg(long)]
+    pub allow_empty: bool,
+
+    /// Skip the checks for blocked paths, large files and secrets in the staged changes
+    #[arg(long)]
+    pub no_content_checks: bool,
+
+    /// Commit on someone else's behalf (git commit --author)
+    #[arg(long, value_name = "NAME <EMAIL>")]
+    pub author: Option<String>,
+
+    /// Author date to record instead of now (git commit --date)
+    #[arg(long, value_name = "WHEN")]
+    pub date: Option<String>,
+
+    /// Pre-populate every field from an existing commit's message (like git commit -c)
+    #[arg(long, value_name = "REF")]
+    pub reuse_message: Option<String>,
+
+    /// Don't commit; put the final message on the clipboard (c on the preview does the same)
+    #[arg(long)]
+    pub copy: bool,
+
+    /// Don't commit; print the final message to stdout (exits non-zero on abort)
+    #[arg(long)]
+    pub print_message_only: bool,
+
+    /// Also write the final message to this file (implies --print-message-only)
+    #[arg(long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Don't commit; print the result as JSON ({type, scope, subject, body, breaking, footers, aborted})
+    #[arg(long)]
+    pub json: bool,
+
+    /// Validate and commit the answers given as flags without showing the TUI
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Commit type (pre-fills the wizard, or is used as-is with --non-interactive)
+    #[arg(long = "type", value_name = "TYPE")]
+    pub commit_type: Option<String>,
+
+    /// Commit scope
+    #[arg(long)]
+    pub scope: Option<String>,
+
+    /// Commit subject
+    #[arg(long)]
+    pub subject: Option<String>,
+
+    /// Commit body (`-` reads it from stdin)
+    #[arg(long, conflicts_with = "body_file")]
+    pub body: Option<String>,
+
+    /// Read the commit body from a file (`-` for stdin), e.g. generated release notes
+    #[arg(long, value_name = "PATH")]
+    pub body_file: Option<PathBuf>,
+
+    /// Breaking change description
+    #[arg(long)]
+    pub breaking: Option<String>,
+
+    /// Footer line such as "Refs: #123" (repeatable)
+    #[arg(long = "footer", value_name = "FOOTER")]
+    pub footers: Vec<String>,
+
+    /// Read wizard answers from a TOML file (`-` for stdin); answered steps are skipped
+    #[arg(long, value_name = "PATH")]
+    pub answers_file: Option<PathBuf>,
+
+    /// Act as GIT_EDITOR: edit the given COMMIT_EDITMSG file instead of running git commit
+    #[arg(long, value_name = "FILE")]
+    pub as_editor: Option<PathBuf>,
+
+    /// Extra arguments forwarded to git commit (e.g. -- --allow-empty)
+    #[arg(last = true)]
+    pub git_args: Vec<String>,
+}
+
+impl Cli {
+    // Answers given directly on the command line
+    pub fn prefill(&self) -> Result<Prefill, Box<dyn std::error::Error>> {
+        let stdin = Path::new("-");
+        let body_path = self.body_file.as_deref().or(self.body.as_deref().filter(|body| *body == "-").map(Path::new));
+        if body_path == Some(stdin) && self.answers_file.as_deref() == Some(stdin) {
+            return Err("--body and --answers-file can't both read stdin".into());
+        }
+        // A generated file's trailing newlines would be empty body lines
+        let body = match body_path {
+            Some(path) if path == stdin => {
+                let mut content = String::new();
+                std::io::stdin().read_to_string(&mut content)?;
+                Some(content.trim_end().to_string())
+            }
+            Some(path) => Some(std::fs::read_to_string(path)?.trim_end().to_string()),
+            None => self.body.clone(),
+        };
+        Ok(Prefill {
+            commit_type: self.commit_type.clone(),
+            scope: self.scope.clone(),
+            subject: self.subject.clone(),
+            body,
+            breaking: self.breaking.clone(),
+            issue: Some(self.footers.join("\n")).filter(|f| !f.is_empty()),
+            bang: false,
+        })
+    }
+}
+
+// Conventional flows that pre-fill the wizard
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Revert a commit: `revert: <original subject>` with a "This reverts commit" body
+    Revert {
+        /// Commit to revert
+        sha: String,
+    },
+    /// Merge a branch (git merge --no-ff --no-commit) and write the merge commit's message in the wizard
+    Merge {
+        /// Branch to merge into the current one
+        branch: String,
+    },
+    /// Browse recent commits and reuse one as a template
+    Log,
+    /// Release commit: `chore(release): vX.Y.Z`
+    Release {
+        /// Version being released (a leading `v` is added if missing)
+        version: String,
+    },
+    /// Review and fix the messages of every commit in a range such as `main..HEAD`
+    Reword {
+        /// `<base>..HEAD`, or just `<base>`
+        range: String,
+    },
+    /// Suggest a pull request title and Markdown description from the branch's commits
+    Pr {
+        /// Branch the pull request goes into (defaults to `base_branch` from the config)
+        #[arg(long)]
+        base: Option<String>,
+        /// Put it on the clipboard instead of printing it
+        #[arg(long)]
+        copy: bool,
+    },
+    /// One conventional message for squash-merging the branch, with the lines to drop picked first
+    Squash {
+        /// Branch it gets squashed into (defaults to `base_branch` from the config)
+        #[arg(long)]
+        base: Option<String>,
+        /// Put it on the clipboard instead of printing it
+        #[arg(long)]
+        copy: bool,
+    },
+    /// Pick a recent commit and make a `fixup!` (or `squash!`) commit for it from the staged changes
+    Fixup {
+        /// Then fold it in with `git rebase -i --autosquash` (no editor opens)
+        #[arg(long)]
+        rebase: bool,
+    },
+    /// Review any commit's message read-only on the preview, with the rules it breaks
+    Show {
+        /// The commit to review, e.g. a teammate's sha or `HEAD~2`
+        rev: String,
+    },
+    /// Search the history by type, scope and fuzzy text; Enter shows a commit's fields and diff stat
+    Search {
+        /// Starting query, e.g. `type:fix scope:api login`
+        query: Option<String>,
+        /// How many recent commits to search
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// Dashboard of commit types, scopes and convention compliance
+    Stats {
+        /// Commit range to start with, e.g. `v1.0.0..HEAD` (defaults to the last 50 commits)
+        range: Option<String>,
+    },
+    /// Scrollable page of the steps, their keys and every config key
+    Help,
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Make a series of commits from a plan file, each staged and confirmed in turn
+    Plan {
+        #[command(subcommand)]
+        action: PlanCommand,
+    },
+}
+
+impl Command {
+    // Whether the subcommand reads or writes the repository, so it can't run outside one
+    pub fn needs_repo(&self) -> bool {
+        !matches!(self, Command::Help | Command::Config { .. })
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigCommand {
+    /// Print the effective config (files, convention preset and flags merged) and where each key comes from
+    Show,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum PlanCommand {
+    /// Stage each planned commit's paths and open the wizard on its answers
+    Apply {
+        /// Plan file: `[[commit]]` tables with the `paths` to stage and answers-file keys
+        path: PathBuf,
+    },
+}
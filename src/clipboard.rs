@@ -0,0 +1,34 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+// Clipboard tools tried in order; the first one that runs wins
+const TOOLS: &[(&str, &[&str])] = &[
+    ("pbcopy", &[]),                                // macOS
+    ("wl-copy", &[]),                               // Wayland
+    ("xclip", &["-selection", "clipboard"]),        // X11
+    ("xsel", &["--clipboard", "--input"]),          // X11
+    ("clip.exe", &[]),                              // Windows, WSL
+];
+
+// Put `text` on the system clipboard through whichever tool is installed
+pub fn copy(text: &str) -> Result<(), Box<dyn std::error::Error>> {
+    for (program, args) in TOOLS {
+        let Ok(mut child) = Command::new(program)
+            .args(*args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue; // Not installed
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    let names: Vec<&str> = TOOLS.iter().map(|(program, _)| *program).collect();
+    Err(format!("No clipboard tool worked (tried {}).", names.join(", ")).into())
+}
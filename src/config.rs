@@ -1,24 +1,322 @@
-use serde::{Deserialize};
-use std::{fs, path::PathBuf};
-use dirs; // For platform-specific config directory
+use crate::i18n::t;
+use serde::{Deserialize, Serialize};
+use regex::Regex;
+use std::{collections::BTreeMap, fmt, fs, path::{Path, PathBuf}};
 
 // Config Struct
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Config {
+    // Built-in preset the rest of the config is layered on (`--convention` wins)
+    pub convention: Option<Convention>,
+
     // Commit Types
     pub types: Option<Vec<String>>,
 
     // Scopes
     pub scopes: Option<Vec<String>>,
+    pub scope_groups: Option<Vec<ScopeGroup>>, // Listed after `scopes`, each under its own header
+    pub package_scopes: Option<bool>, // Offer monorepo workspace members as scopes
+    pub package_globs: Option<Vec<String>>, // Extra member directories, e.g. "services/*"
+    pub remember_scopes: Option<bool>, // Offer Ctrl+K to keep a custom scope (stored under .git, not here)
+
+    // Path Rules: globs over the staged paths, each naming a scope ("src/tui/**" =
+    // "tui") or a type. When every staged file matches one and they all agree, it's
+    // pre-selected; for each file the longest matching pattern wins.
+    pub scope_rules: Option<BTreeMap<String, String>>,
+    pub path_types: Option<BTreeMap<String, String>>, // "docs/**" = "docs"
+
+    // Scope Rules
+    pub allow_custom_scope: Option<bool>,
+    pub scope_pattern: Option<String>, // Regex a custom scope must match, e.g. "^[a-z0-9]+(-[a-z0-9]+)*$"
+    pub scope_required_for: Option<Vec<String>>,
+    pub scope_forbidden_for: Option<Vec<String>>,
+
+    // Per-type Templates (keyed by commit type)
+    pub subject_template: Option<BTreeMap<String, String>>, // revert = "revert: "
+    pub body_template: Option<BTreeMap<String, String>>, // feat = "Why:\n\n- "
+
+    // Final Message Layout, for formats other than Conventional Commits, e.g.
+    // "[{TYPE}] {subject}\n\n{body}". Placeholders: {type} {TYPE} {scope} {SCOPE}
+    // {scope_paren} {bang} {subject} {body} {breaking} {footers}; empty
+    // paragraphs are dropped. Unset means the standard conventional layout.
+    pub message_template: Option<String>,
+
+    // Snippets expanded while typing in any text field (":bc:" = "BREAKING CHANGE: ")
+    pub snippets: Option<BTreeMap<String, String>>,
+
+    // Characters each text field accepts, as a regex for one character
+    // (field = "scope", "subject", "body", "breaking", "issues" or "jira"), e.g. scope = "[a-z0-9-]"
+    pub input_filters: Option<BTreeMap<String, String>>,
+
+    // Issue References (the footer field on the preview): keywords offered with
+    // Tab, and a regex each reference after one must match, e.g. "^#[0-9]+$"
+    pub issue_keywords: Option<Vec<String>>,
+    pub issue_pattern: Option<String>,
 
     // Subject Validation Rules
+    pub subject_min_length: Option<usize>, // 0 allows an empty subject
     pub subject_max_length: Option<usize>,
     pub subject_start_lowercase: Option<bool>,
     pub subject_no_ending_period: Option<bool>,
+    pub imperative_mood: Option<MoodCheck>,
+    pub imperative_words: Option<BTreeMap<String, String>>, // "added" = "add", ...
+    // Footers: the breaking-change description's minimum length (it can never
+    // be empty), whether BREAKING CHANGE comes first or last among the trailers,
+    // and whether `git interpret-trailers` adds them so `trailer.*` git config applies
+    pub breaking_min_length: Option<usize>,
+    pub breaking_position: Option<BreakingPosition>,
+    pub interpret_trailers: Option<bool>,
+    pub bang_requires_footer: Option<bool>, // A `!` (the `!` key) needs a BREAKING CHANGE description too
+    // Limits some servers enforce on the whole message: the header line, each
+    // footer line (none by default), and a total size that only warns
+    pub header_max_length: Option<usize>,
+    pub footer_max_length: Option<usize>,
+    pub message_max_bytes: Option<usize>,
+    // Body lines longer than this get a warning, and Ctrl+W rewraps a paragraph to it
+    pub body_max_line_length: Option<usize>,
+    pub auto_fix: Option<bool>, // Offer Ctrl+F to fix case, a trailing period, stray whitespace and an overlong subject
+    // The rules above for a single type, e.g. `[type_rules.wip] subject_min_length = 0`
+    pub type_rules: Option<BTreeMap<String, TypeRules>>,
+
+    // Steps the wizard never asks ("type", "scope", "body", "breaking"); a
+    // hidden type step always uses the first entry of `types`
+    pub hidden_steps: Option<Vec<String>>,
+
+    // Optional steps that can be skipped with `s`/Ctrl+S ("scope", "body", "breaking")
+    pub skippable_steps: Option<Vec<String>>,
+
+    // UI Language: English built in, others from <config dir>/commiTUI/locales/<language>.toml
+    pub language: Option<String>,
+
+    // Colours (also off whenever NO_COLOR is set)
+    pub color: Option<bool>,
+
+    // Live Preview Pane (toggled with Ctrl+P on every step)
+    pub preview_pane: Option<PanePosition>,
+
+    // Layout: "wizard" asks one step per screen; "form" puts every field on one
+    // screen, moved between with Tab/Shift+Tab, and confirms on the preview
+    pub layout: Option<WizardLayout>,
+
+    // Compact Mode: on terminals with fewer rows than this, boxes lose their borders
+    // and the type and scope lists scroll, narrowed down with `/` (0 never compacts)
+    pub compact_height: Option<u16>,
+
+    // Step Transitions: the title of the step just reached is briefly highlighted
+    pub step_transition: Option<bool>,
+
+    // Idle Lock: minutes without a key press after which the answers are saved as
+    // a draft and the wizard dims behind a lock screen (0, the default, never locks)
+    pub idle_lock_minutes: Option<u64>,
+
+    // Selection Memory: quitting the wizard keeps its type and scope for a rerun
+    // within this many minutes (0 never keeps them); a draft still wins
+    pub selection_memory_minutes: Option<u64>,
+
+    // Commit Options (passed through to git commit). Unset, gpg_sign, verbose_commit
+    // and hooks_path follow commit.gpgSign, commit.verbose and core.hooksPath.
+    pub gpg_sign: Option<bool>,
+    pub signoff: Option<bool>,
+    // `git commit -v`: status and staged diff go into the message file as comments,
+    // and comment lines are stripped (so a body line starting with `#` is dropped too)
+    pub verbose_commit: Option<bool>,
+    pub hooks_path: Option<String>, // Hooks directory for the commit, instead of core.hooksPath
+    pub author: Option<String>, // "Name <email>" to commit as instead of user.name/user.email (--author)
+    pub stash_unstaged: Option<bool>, // Stash unstaged changes around the commit (u on the preview)
+    // Trailer recorded on commits made with the hooks skipped (--no-verify, n on the
+    // preview), so a bypass can be found in the history. Empty to record nothing.
+    pub hooks_bypassed_footer: Option<String>,
+    // False refuses commits with nothing staged, even with --allow-empty
+    pub allow_empty_commits: Option<bool>,
+    pub git_args: Option<Vec<String>>, // Extra flags for git commit the wizard doesn't model
+    // Author email domains this repository expects ("example.com"); the preview
+    // warns about any other user.email. Any domain when empty.
+    pub allowed_email_domains: Option<Vec<String>>,
+
+    // Staged Content Checks (a blocking warning before the wizard, with an
+    // override): paths that shouldn't be committed (globs; "dir/" matches a
+    // directory anywhere), a size limit in KB (0 for none), and regexes for
+    // secrets on added lines
+    pub blocked_paths: Option<Vec<String>>,
+    pub max_file_size_kb: Option<u64>,
+    pub secret_patterns: Option<Vec<String>>,
+
+    // Commit Size: the preview warns when the staged changes touch more files or
+    // lines than these (0 for no limit), as a hint to split the commit. With
+    // block_large_commits it's a staged content check, stopping before the wizard.
+    pub max_commit_files: Option<usize>,
+    pub max_commit_lines: Option<usize>,
+    pub block_large_commits: Option<bool>,
+
+    // Protected Branches: globs ("main", "release/*") for branches a commit on
+    // needs an explicit `c` on a warning screen first, or is refused outright
+    // with protected_branches_block
+    pub protected_branches: Option<Vec<String>>,
+    pub protected_branches_block: Option<bool>,
+
+    // Branch Check: the preview counts commits between this base and HEAD that
+    // break the rules (R rewords them). Empty to turn it off.
+    pub base_branch: Option<String>,
+
+    // Release Commits: offer an annotated tag after committing one of these
+    // ("type", or "type(scope)" for a single scope)
+    pub release_types: Option<Vec<String>>,
+
+    // Merge Commits: the type `commiTUI merge <branch>` pre-selects (added to
+    // `types` for that run if it isn't listed)
+    pub merge_type: Option<String>,
+
+    // Branch Name Rules (pre-fill type/scope/subject/issue from the current branch)
+    pub branch_rules: Option<Vec<BranchRule>>,
+    // Branch Templates: `"hotfix/*" = { type = "fix", scope = "prod", footers = [...] }`;
+    // the longest glob matching the branch wins over `branch_rules` and `scope_rules`
+    pub branch_templates: Option<BTreeMap<String, BranchTemplate>>,
+
+    // External Summarizer (opt-in)
+    pub ai: Option<AiConfig>,
+
+    // Jira Smart Commits (opt-in): ticket, time spent and comment inputs on the
+    // preview step, written as a `PROJ-123 #time 2h #comment ...` footer
+    pub jira: Option<JiraConfig>,
 
     // Add more configurable validation rules here as needed (as Option<Type>)
 }
 
+// Message conventions shipped as presets (see `preset`)
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Convention {
+    Conventional,
+    Angular,
+    Gitmoji,
+    Jira,  // `ABC-123: subject`
+    Plain, // Subject and body only
+}
+
+impl std::str::FromStr for Convention {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "conventional" => Ok(Convention::Conventional),
+            "angular" => Ok(Convention::Angular),
+            "gitmoji" => Ok(Convention::Gitmoji),
+            "jira" => Ok(Convention::Jira),
+            "plain" => Ok(Convention::Plain),
+            _ => Err(format!("unknown convention '{}' (expected conventional, angular, gitmoji, jira or plain)", name)),
+        }
+    }
+}
+
+// How the imperative-mood heuristic is applied to the subject
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum MoodCheck {
+    Off,
+    Warn,   // Shown, but doesn't block
+    Strict, // Treated like any other validation error
+}
+
+// Where the BREAKING CHANGE footer goes in the trailer block
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakingPosition {
+    First,
+    Last,
+}
+
+// Where the live preview pane starts out; Ctrl+P toggles it either way
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PanePosition {
+    Hidden,
+    Bottom,
+    Right,
+}
+
+// How the questions are laid out
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WizardLayout {
+    Wizard,
+    Form,
+}
+
+// A regex matched against the current branch name. Named capture groups
+// `type`, `scope`, `subject` and `issue` fill the matching wizard fields; the
+// optional templates override that and may reference any group (`$name`).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct BranchRule {
+    pub pattern: String,
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub subject: Option<String>,
+    pub issue: Option<String>,
+}
+
+// A `[branch_templates]` entry: what a family of branches always starts with.
+// The footers go into the preview's footer field, below any issue.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct BranchTemplate {
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    #[serde(default)]
+    pub footers: Vec<String>,
+}
+
+// `[[scope_groups]]`: related scopes shown under a non-selectable header
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ScopeGroup {
+    pub name: String,
+    pub scopes: Vec<String>,
+}
+
+// One row of the scope picker
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScopeEntry {
+    Header(String),
+    Scope(String),
+}
+
+impl ScopeEntry {
+    // The scope this row selects, None for headers
+    pub fn scope(&self) -> Option<&str> {
+        match self {
+            ScopeEntry::Header(_) => None,
+            ScopeEntry::Scope(scope) => Some(scope),
+        }
+    }
+}
+
+// `[ai]` section: any command that reads a diff on stdin and prints a message
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct AiConfig {
+    pub command: Option<String>,
+}
+
+// `[jira]` section; its presence turns the smart-commit inputs on
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct JiraConfig {
+    pub projects: Option<Vec<String>>, // Project keys a ticket may use ("PROJ"); any when unset
+}
+
+// `[type_rules.<type>]`: subject rules that differ for one type; unset ones
+// fall back to the top-level settings
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct TypeRules {
+    pub subject_min_length: Option<usize>,
+    pub subject_max_length: Option<usize>,
+    pub subject_start_lowercase: Option<bool>,
+    pub subject_no_ending_period: Option<bool>,
+    pub imperative_mood: Option<MoodCheck>,
+    // Written when the subject is left empty ("work in progress"); without
+    // one an empty subject leaves just `wip:` off the header
+    pub default_subject: Option<String>,
+}
+
 // --- Default Values for Config Fields (these are the true defaults) ---
 // MAKE THESE PUBLIC!
 pub fn default_types() -> Vec<String> { // <--- ADD pub
@@ -33,17 +331,212 @@ pub fn default_scopes() -> Vec<String> { // <--- ADD pub
         "no scope".into(),
         "core".into(), "api".into(), "ui".into(), "auth".into(), "db".into(),
         "test".into(), "build".into(), "deps".into(), "ci".into(),
-        "────────────".into(),
-        "config".into(), "infra".into(), "release".into(), "chore".into(), "perf".into(),
-        "style".into(), "lint".into(), "i18n".into(), "analytics".into(), "security".into(),
-        "logging".into(), "devops".into(), "deploy".into(), "assets".into(), "mock".into(), "example".into()
     ]
 }
 
+pub fn default_scope_groups() -> Vec<ScopeGroup> {
+    vec![ScopeGroup {
+        name: "More".into(),
+        scopes: vec![
+            "config".into(), "infra".into(), "release".into(), "chore".into(), "perf".into(),
+            "style".into(), "lint".into(), "i18n".into(), "analytics".into(), "security".into(),
+            "logging".into(), "devops".into(), "deploy".into(), "assets".into(), "mock".into(), "example".into(),
+        ],
+    }]
+}
+
+pub fn default_package_scopes() -> bool { true }
+pub fn default_package_globs() -> Vec<String> { vec![] }
+pub fn default_remember_scopes() -> bool { true }
+
+pub fn default_scope_rules() -> BTreeMap<String, String> { BTreeMap::new() }
+pub fn default_path_types() -> BTreeMap<String, String> { BTreeMap::new() }
+
+pub fn default_allow_custom_scope() -> bool { true }
+pub fn default_scope_required_for() -> Vec<String> { vec![] }
+pub fn default_scope_forbidden_for() -> Vec<String> { vec![] }
+
+pub fn default_subject_template() -> BTreeMap<String, String> { BTreeMap::new() }
+pub fn default_body_template() -> BTreeMap<String, String> { BTreeMap::new() }
+
+pub fn default_snippets() -> BTreeMap<String, String> { BTreeMap::new() }
+
+pub fn default_input_filters() -> BTreeMap<String, String> { BTreeMap::new() }
+
+pub fn default_issue_keywords() -> Vec<String> {
+    vec!["Closes".into(), "Fixes".into(), "Resolves".into(), "Refs".into(), "Relates-to".into()]
+}
+
+pub fn default_subject_min_length() -> usize { 1 }
 pub fn default_subject_max_length() -> usize { 72 } // <--- ADD pub
 pub fn default_subject_start_lowercase() -> bool { true } // <--- ADD pub
 pub fn default_subject_no_ending_period() -> bool { true } // <--- ADD pub
 
+pub fn default_imperative_mood() -> MoodCheck { MoodCheck::Off }
+pub fn default_breaking_min_length() -> usize { 1 }
+pub fn default_breaking_position() -> BreakingPosition { BreakingPosition::First }
+pub fn default_interpret_trailers() -> bool { false }
+pub fn default_bang_requires_footer() -> bool { false }
+pub fn default_header_max_length() -> usize { 100 }
+pub fn default_message_max_bytes() -> usize { 64 * 1024 }
+pub fn default_body_max_line_length() -> usize { 72 }
+pub fn default_auto_fix() -> bool { false }
+
+pub fn default_imperative_words() -> BTreeMap<String, String> {
+    [
+        ("added", "add"), ("adding", "add"), ("adds", "add"),
+        ("fixed", "fix"), ("fixing", "fix"), ("fixes", "fix"),
+        ("updated", "update"), ("updating", "update"), ("updates", "update"),
+        ("removed", "remove"), ("removing", "remove"), ("removes", "remove"),
+        ("changed", "change"), ("changing", "change"), ("changes", "change"),
+        ("refactored", "refactor"), ("refactoring", "refactor"), ("refactors", "refactor"),
+        ("implemented", "implement"), ("implementing", "implement"), ("implements", "implement"),
+        ("improved", "improve"), ("improving", "improve"), ("improves", "improve"),
+        ("created", "create"), ("creating", "create"), ("creates", "create"),
+        ("deleted", "delete"), ("deleting", "delete"), ("deletes", "delete"),
+        ("renamed", "rename"), ("renaming", "rename"), ("renames", "rename"),
+        ("moved", "move"), ("moving", "move"), ("moves", "move"),
+        ("bumped", "bump"), ("bumping", "bump"), ("bumps", "bump"),
+        ("made", "make"), ("making", "make"), ("makes", "make"),
+    ]
+    .into_iter()
+    .map(|(word, imperative)| (word.to_string(), imperative.to_string()))
+    .collect()
+}
+
+pub fn default_hidden_steps() -> Vec<String> { vec![] }
+
+pub fn default_skippable_steps() -> Vec<String> {
+    vec!["scope".into(), "body".into(), "breaking".into()]
+}
+
+pub fn default_language() -> String { "en".into() }
+
+pub fn default_color() -> bool { true }
+
+pub fn default_preview_pane() -> PanePosition { PanePosition::Hidden }
+
+pub fn default_layout() -> WizardLayout { WizardLayout::Wizard }
+
+pub fn default_compact_height() -> u16 { 18 }
+
+pub fn default_step_transition() -> bool { false }
+
+pub fn default_idle_lock_minutes() -> u64 { 0 }
+
+pub fn default_selection_memory_minutes() -> u64 { 10 }
+
+pub fn default_gpg_sign() -> bool { false }
+pub fn default_signoff() -> bool { false }
+pub fn default_verbose_commit() -> bool { false }
+pub fn default_stash_unstaged() -> bool { false }
+pub fn default_hooks_bypassed_footer() -> String { "Hooks-Bypassed: true".into() }
+pub fn default_allow_empty_commits() -> bool { true }
+pub fn default_git_args() -> Vec<String> { vec![] }
+pub fn default_allowed_email_domains() -> Vec<String> { vec![] }
+
+pub fn default_blocked_paths() -> Vec<String> {
+    vec![".env".into(), ".env.*".into(), "*.pem".into(), "*.key".into(), "id_rsa".into(), "node_modules/".into()]
+}
+
+pub fn default_max_file_size_kb() -> u64 { 1024 }
+
+pub fn default_secret_patterns() -> Vec<String> {
+    vec![
+        r"-----BEGIN [A-Z ]*PRIVATE KEY-----".into(),
+        r"AKIA[0-9A-Z]{16}".into(), // AWS access key id
+        r"gh[pousr]_[A-Za-z0-9]{36}".into(), // GitHub token
+        r"xox[baprs]-[A-Za-z0-9-]{10,}".into(), // Slack token
+        r#"(?i)(api[_-]?key|secret|password|passwd|token)\s*[:=]\s*['"][^'"\s]{8,}['"]"#.into(),
+    ]
+}
+
+pub fn default_max_commit_files() -> usize { 50 }
+pub fn default_max_commit_lines() -> usize { 1000 }
+pub fn default_block_large_commits() -> bool { false }
+
+pub fn default_protected_branches() -> Vec<String> { vec![] }
+pub fn default_protected_branches_block() -> bool { false }
+
+pub fn default_base_branch() -> String { "origin/main".into() }
+
+pub fn default_release_types() -> Vec<String> { vec!["chore(release)".into()] } // What `commiTUI release` writes
+
+pub fn default_merge_type() -> String { "merge".into() }
+
+pub fn default_branch_templates() -> BTreeMap<String, BranchTemplate> { BTreeMap::new() }
+
+// The built-in rules recognise the configured `types` in branch names
+pub fn default_branch_rules(types: &[String]) -> Vec<BranchRule> {
+    let types = types.iter().map(|t| regex::escape(t)).collect::<Vec<_>>().join("|");
+    vec![
+        // JIRA-123-fix-login -> issue "Refs: JIRA-123", type "fix", subject "login"
+        BranchRule {
+            pattern: format!(r"^(?P<issue>[A-Z][A-Z0-9]+-\d+)[-_/](?:(?P<type>{})[-_/])?(?P<subject>.+)$", types),
+            commit_type: None,
+            scope: None,
+            subject: None,
+            issue: Some("Refs: $issue".into()),
+        },
+        // feat/api-rate-limiting or fix/auth/token-expiry
+        BranchRule {
+            pattern: format!(r"^(?P<type>{})/(?:(?P<scope>[^/]+)/)?(?P<subject>.+)$", types),
+            commit_type: None,
+            scope: None,
+            subject: None,
+            issue: None,
+        },
+    ]
+}
+
+
+// --- Convention Presets (written like a config file, layered under the user's) ---
+const ANGULAR_PRESET: &str = r#"
+types = ["build", "ci", "docs", "feat", "fix", "perf", "refactor", "test"]
+subject_max_length = 100
+"#;
+
+const GITMOJI_PRESET: &str = r#"
+types = ["✨", "🐛", "📝", "🎨", "♻️", "⚡️", "✅", "🔧", "⬆️", "🚀", "💥", "⏪️"]
+hidden_steps = ["scope"]
+subject_start_lowercase = false
+message_template = "{type} {subject}\n\n{body}\n\n{footers}"
+"#;
+
+const JIRA_PRESET: &str = r#"
+types = ["jira"]
+scopes = []
+package_scopes = false
+hidden_steps = ["type", "breaking"]
+scope_required_for = ["jira"]
+scope_pattern = "^[A-Z][A-Z0-9]+-[0-9]+$"
+subject_start_lowercase = false
+message_template = "{scope}: {subject}\n\n{body}\n\n{footers}"
+
+# ABC-123-fix-login -> "ABC-123: fix login"
+[[branch_rules]]
+pattern = '^(?P<ticket>[A-Z][A-Z0-9]+-\d+)[-_/](?P<subject>.+)$'
+scope = "$ticket"
+"#;
+
+const PLAIN_PRESET: &str = r#"
+types = ["plain"]
+hidden_steps = ["type", "scope", "breaking"]
+subject_start_lowercase = false
+message_template = "{subject}\n\n{body}\n\n{footers}"
+"#;
+
+// The settings a convention changes from the defaults
+pub fn preset(convention: Convention) -> Option<Config> {
+    let source = match convention {
+        Convention::Conventional => return None,
+        Convention::Angular => ANGULAR_PRESET,
+        Convention::Gitmoji => GITMOJI_PRESET,
+        Convention::Jira => JIRA_PRESET,
+        Convention::Plain => PLAIN_PRESET,
+    };
+    Some(toml::from_str(source).expect("built-in presets are valid"))
+}
 
 // --- Trait for Merging Configs ---
 pub trait MergeConfig {
@@ -52,11 +545,70 @@ pub trait MergeConfig {
 
 impl MergeConfig for Config {
     fn merge(&mut self, other: Self) {
+        if let Some(convention) = other.convention {
+            self.convention = Some(convention);
+        }
         if let Some(types) = other.types {
             self.types = Some(types);
         }
         if let Some(scopes) = other.scopes {
             self.scopes = Some(scopes);
+            // A custom scope list replaces the default groups too, unless groups are given alongside
+            self.scope_groups = Some(vec![]);
+        }
+        if let Some(groups) = other.scope_groups {
+            self.scope_groups = Some(groups);
+        }
+        if let Some(enabled) = other.package_scopes {
+            self.package_scopes = Some(enabled);
+        }
+        if let Some(globs) = other.package_globs {
+            self.package_globs = Some(globs);
+        }
+        if let Some(rules) = other.scope_rules {
+            self.scope_rules = Some(rules);
+        }
+        if let Some(rules) = other.path_types {
+            self.path_types = Some(rules);
+        }
+        if let Some(enabled) = other.remember_scopes {
+            self.remember_scopes = Some(enabled);
+        }
+        if let Some(allow) = other.allow_custom_scope {
+            self.allow_custom_scope = Some(allow);
+        }
+        if let Some(pattern) = other.scope_pattern {
+            self.scope_pattern = Some(pattern);
+        }
+        if let Some(types) = other.scope_required_for {
+            self.scope_required_for = Some(types);
+        }
+        if let Some(types) = other.scope_forbidden_for {
+            self.scope_forbidden_for = Some(types);
+        }
+        if let Some(templates) = other.subject_template {
+            self.subject_template = Some(templates);
+        }
+        if let Some(templates) = other.body_template {
+            self.body_template = Some(templates);
+        }
+        if let Some(template) = other.message_template {
+            self.message_template = Some(template);
+        }
+        if let Some(snippets) = other.snippets {
+            self.snippets = Some(snippets);
+        }
+        if let Some(filters) = other.input_filters {
+            self.input_filters = Some(filters);
+        }
+        if let Some(keywords) = other.issue_keywords {
+            self.issue_keywords = Some(keywords);
+        }
+        if let Some(pattern) = other.issue_pattern {
+            self.issue_pattern = Some(pattern);
+        }
+        if let Some(length) = other.subject_min_length {
+            self.subject_min_length = Some(length);
         }
         if let Some(length) = other.subject_max_length {
             self.subject_max_length = Some(length);
@@ -67,6 +619,147 @@ impl MergeConfig for Config {
         if let Some(no_period) = other.subject_no_ending_period {
             self.subject_no_ending_period = Some(no_period);
         }
+        if let Some(mood) = other.imperative_mood {
+            self.imperative_mood = Some(mood);
+        }
+        if let Some(words) = other.imperative_words {
+            self.imperative_words = Some(words);
+        }
+        if let Some(length) = other.breaking_min_length {
+            self.breaking_min_length = Some(length);
+        }
+        if let Some(position) = other.breaking_position {
+            self.breaking_position = Some(position);
+        }
+        if let Some(interpret) = other.interpret_trailers {
+            self.interpret_trailers = Some(interpret);
+        }
+        if let Some(required) = other.bang_requires_footer {
+            self.bang_requires_footer = Some(required);
+        }
+        if let Some(length) = other.header_max_length {
+            self.header_max_length = Some(length);
+        }
+        if let Some(length) = other.footer_max_length {
+            self.footer_max_length = Some(length);
+        }
+        if let Some(bytes) = other.message_max_bytes {
+            self.message_max_bytes = Some(bytes);
+        }
+        if let Some(length) = other.body_max_line_length {
+            self.body_max_line_length = Some(length);
+        }
+        if let Some(auto_fix) = other.auto_fix {
+            self.auto_fix = Some(auto_fix);
+        }
+        if let Some(rules) = other.type_rules {
+            self.type_rules = Some(rules);
+        }
+        if let Some(steps) = other.hidden_steps {
+            self.hidden_steps = Some(steps);
+        }
+        if let Some(steps) = other.skippable_steps {
+            self.skippable_steps = Some(steps);
+        }
+        if let Some(language) = other.language {
+            self.language = Some(language);
+        }
+        if let Some(color) = other.color {
+            self.color = Some(color);
+        }
+        if let Some(position) = other.preview_pane {
+            self.preview_pane = Some(position);
+        }
+        if let Some(layout) = other.layout {
+            self.layout = Some(layout);
+        }
+        if let Some(height) = other.compact_height {
+            self.compact_height = Some(height);
+        }
+        if let Some(transition) = other.step_transition {
+            self.step_transition = Some(transition);
+        }
+        if let Some(minutes) = other.idle_lock_minutes {
+            self.idle_lock_minutes = Some(minutes);
+        }
+        if let Some(minutes) = other.selection_memory_minutes {
+            self.selection_memory_minutes = Some(minutes);
+        }
+        if let Some(gpg_sign) = other.gpg_sign {
+            self.gpg_sign = Some(gpg_sign);
+        }
+        if let Some(signoff) = other.signoff {
+            self.signoff = Some(signoff);
+        }
+        if let Some(verbose) = other.verbose_commit {
+            self.verbose_commit = Some(verbose);
+        }
+        if let Some(path) = other.hooks_path {
+            self.hooks_path = Some(path);
+        }
+        if let Some(author) = other.author {
+            self.author = Some(author);
+        }
+        if let Some(stash) = other.stash_unstaged {
+            self.stash_unstaged = Some(stash);
+        }
+        if let Some(footer) = other.hooks_bypassed_footer {
+            self.hooks_bypassed_footer = Some(footer);
+        }
+        if let Some(allow) = other.allow_empty_commits {
+            self.allow_empty_commits = Some(allow);
+        }
+        if let Some(git_args) = other.git_args {
+            self.git_args = Some(git_args);
+        }
+        if let Some(domains) = other.allowed_email_domains {
+            self.allowed_email_domains = Some(domains);
+        }
+        if let Some(paths) = other.blocked_paths {
+            self.blocked_paths = Some(paths);
+        }
+        if let Some(size) = other.max_file_size_kb {
+            self.max_file_size_kb = Some(size);
+        }
+        if let Some(patterns) = other.secret_patterns {
+            self.secret_patterns = Some(patterns);
+        }
+        if let Some(files) = other.max_commit_files {
+            self.max_commit_files = Some(files);
+        }
+        if let Some(lines) = other.max_commit_lines {
+            self.max_commit_lines = Some(lines);
+        }
+        if let Some(block) = other.block_large_commits {
+            self.block_large_commits = Some(block);
+        }
+        if let Some(branches) = other.protected_branches {
+            self.protected_branches = Some(branches);
+        }
+        if let Some(block) = other.protected_branches_block {
+            self.protected_branches_block = Some(block);
+        }
+        if let Some(base) = other.base_branch {
+            self.base_branch = Some(base);
+        }
+        if let Some(types) = other.release_types {
+            self.release_types = Some(types);
+        }
+        if let Some(merge_type) = other.merge_type {
+            self.merge_type = Some(merge_type);
+        }
+        if let Some(rules) = other.branch_rules {
+            self.branch_rules = Some(rules);
+        }
+        if let Some(templates) = other.branch_templates {
+            self.branch_templates = Some(templates);
+        }
+        if let Some(ai) = other.ai {
+            self.ai = Some(ai);
+        }
+        if let Some(jira) = other.jira {
+            self.jira = Some(jira);
+        }
     }
 }
 
@@ -75,51 +768,469 @@ impl MergeConfig for Config {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            convention: Some(Convention::Conventional),
             types: Some(default_types()),
             scopes: Some(default_scopes()),
+            scope_groups: Some(default_scope_groups()),
+            package_scopes: Some(default_package_scopes()),
+            package_globs: Some(default_package_globs()),
+            scope_rules: Some(default_scope_rules()),
+            path_types: Some(default_path_types()),
+            remember_scopes: Some(default_remember_scopes()),
+            allow_custom_scope: Some(default_allow_custom_scope()),
+            scope_pattern: None, // Any custom scope is accepted
+            scope_required_for: Some(default_scope_required_for()),
+            scope_forbidden_for: Some(default_scope_forbidden_for()),
+            subject_template: Some(default_subject_template()),
+            body_template: Some(default_body_template()),
+            message_template: None, // Conventional Commits layout
+            snippets: Some(default_snippets()),
+            input_filters: Some(default_input_filters()),
+            issue_keywords: Some(default_issue_keywords()),
+            issue_pattern: None, // Any reference is accepted
+            subject_min_length: Some(default_subject_min_length()),
             subject_max_length: Some(default_subject_max_length()),
             subject_start_lowercase: Some(default_subject_start_lowercase()),
             subject_no_ending_period: Some(default_subject_no_ending_period()),
+            imperative_mood: Some(default_imperative_mood()),
+            imperative_words: Some(default_imperative_words()),
+            breaking_min_length: Some(default_breaking_min_length()),
+            breaking_position: Some(default_breaking_position()),
+            interpret_trailers: Some(default_interpret_trailers()),
+            bang_requires_footer: Some(default_bang_requires_footer()),
+            header_max_length: Some(default_header_max_length()),
+            footer_max_length: None, // Footers can hold long URLs
+            message_max_bytes: Some(default_message_max_bytes()),
+            body_max_line_length: Some(default_body_max_line_length()),
+            auto_fix: Some(default_auto_fix()),
+            type_rules: None, // The same rules for every type
+            hidden_steps: Some(default_hidden_steps()),
+            skippable_steps: Some(default_skippable_steps()),
+            language: Some(default_language()),
+            color: Some(default_color()),
+            preview_pane: Some(default_preview_pane()),
+            layout: Some(default_layout()),
+            compact_height: Some(default_compact_height()),
+            step_transition: Some(default_step_transition()),
+            idle_lock_minutes: Some(default_idle_lock_minutes()),
+            selection_memory_minutes: Some(default_selection_memory_minutes()),
+            gpg_sign: None, // commit.gpgSign, else default_gpg_sign()
+            signoff: Some(default_signoff()),
+            verbose_commit: None, // commit.verbose, else default_verbose_commit()
+            hooks_path: None, // core.hooksPath
+            author: None, // user.name and user.email
+            stash_unstaged: Some(default_stash_unstaged()),
+            hooks_bypassed_footer: Some(default_hooks_bypassed_footer()),
+            allow_empty_commits: Some(default_allow_empty_commits()),
+            git_args: Some(default_git_args()),
+            allowed_email_domains: Some(default_allowed_email_domains()),
+            blocked_paths: Some(default_blocked_paths()),
+            max_file_size_kb: Some(default_max_file_size_kb()),
+            secret_patterns: Some(default_secret_patterns()),
+            max_commit_files: Some(default_max_commit_files()),
+            max_commit_lines: Some(default_max_commit_lines()),
+            block_large_commits: Some(default_block_large_commits()),
+            protected_branches: Some(default_protected_branches()),
+            protected_branches_block: Some(default_protected_branches_block()),
+            base_branch: Some(default_base_branch()),
+            release_types: Some(default_release_types()),
+            merge_type: Some(default_merge_type()),
+            branch_rules: None, // Built from `types`, see `branch_rules()`
+            branch_templates: Some(default_branch_templates()),
+            ai: None, // Nothing is ever sent anywhere unless configured
+            jira: None,
         }
     }
 }
 
+// --- Config Checking ---
+// Every top-level key `Config` understands; anything else is probably a typo
+const KNOWN_KEYS: &[&str] = &[
+    "convention", "types", "scopes", "scope_groups", "package_scopes", "package_globs", "scope_rules", "path_types",
+    "remember_scopes", "allow_custom_scope", "scope_pattern", "scope_required_for",
+    "scope_forbidden_for", "subject_template", "body_template", "message_template", "snippets",
+    "input_filters", "issue_keywords", "issue_pattern", "subject_min_length",
+    "subject_max_length", "subject_start_lowercase", "subject_no_ending_period",
+    "imperative_mood", "imperative_words", "breaking_min_length", "breaking_position",
+    "interpret_trailers", "bang_requires_footer", "header_max_length", "footer_max_length", "message_max_bytes",
+    "body_max_line_length", "auto_fix", "type_rules", "hidden_steps", "skippable_steps",
+    "language", "color", "preview_pane", "layout", "compact_height", "step_transition",
+    "idle_lock_minutes", "selection_memory_minutes", "gpg_sign", "signoff", "verbose_commit", "hooks_path", "author",
+    "stash_unstaged", "hooks_bypassed_footer", "allow_empty_commits", "git_args", "allowed_email_domains", "blocked_paths",
+    "max_file_size_kb", "secret_patterns", "max_commit_files", "max_commit_lines", "block_large_commits",
+    "protected_branches", "protected_branches_block", "base_branch", "release_types", "merge_type", "branch_rules", "branch_templates", "ai", "jira",
+];
+
+// Every `Config` key with the locale entry describing it, in the struct's
+// order, for the in-app help and `config show`
+pub const KEY_DESCRIPTIONS: &[(&str, &str)] = &[
+    ("convention", "config_keys.convention"), ("types", "config_keys.types"), ("scopes", "config_keys.scopes"),
+    ("scope_groups", "config_keys.scope_groups"), ("package_scopes", "config_keys.package_scopes"),
+    ("package_globs", "config_keys.package_globs"), ("remember_scopes", "config_keys.remember_scopes"),
+    ("scope_rules", "config_keys.scope_rules"), ("path_types", "config_keys.path_types"),
+    ("allow_custom_scope", "config_keys.allow_custom_scope"), ("scope_pattern", "config_keys.scope_pattern"),
+    ("scope_required_for", "config_keys.scope_required_for"),
+    ("scope_forbidden_for", "config_keys.scope_forbidden_for"), ("subject_template", "config_keys.subject_template"),
+    ("body_template", "config_keys.body_template"), ("message_template", "config_keys.message_template"),
+    ("snippets", "config_keys.snippets"), ("input_filters", "config_keys.input_filters"),
+    ("issue_keywords", "config_keys.issue_keywords"), ("issue_pattern", "config_keys.issue_pattern"),
+    ("subject_min_length", "config_keys.subject_min_length"), ("subject_max_length", "config_keys.subject_max_length"),
+    ("subject_start_lowercase", "config_keys.subject_start_lowercase"),
+    ("subject_no_ending_period", "config_keys.subject_no_ending_period"),
+    ("imperative_mood", "config_keys.imperative_mood"), ("imperative_words", "config_keys.imperative_words"),
+    ("breaking_min_length", "config_keys.breaking_min_length"), ("breaking_position", "config_keys.breaking_position"),
+    ("interpret_trailers", "config_keys.interpret_trailers"),
+    ("bang_requires_footer", "config_keys.bang_requires_footer"),
+    ("header_max_length", "config_keys.header_max_length"), ("footer_max_length", "config_keys.footer_max_length"),
+    ("message_max_bytes", "config_keys.message_max_bytes"),
+    ("body_max_line_length", "config_keys.body_max_line_length"), ("auto_fix", "config_keys.auto_fix"),
+    ("type_rules", "config_keys.type_rules"), ("hidden_steps", "config_keys.hidden_steps"),
+    ("skippable_steps", "config_keys.skippable_steps"), ("language", "config_keys.language"),
+    ("color", "config_keys.color"), ("preview_pane", "config_keys.preview_pane"), ("layout", "config_keys.layout"),
+    ("compact_height", "config_keys.compact_height"), ("step_transition", "config_keys.step_transition"),
+    ("idle_lock_minutes", "config_keys.idle_lock_minutes"),
+    ("selection_memory_minutes", "config_keys.selection_memory_minutes"), ("gpg_sign", "config_keys.gpg_sign"),
+    ("signoff", "config_keys.signoff"), ("verbose_commit", "config_keys.verbose_commit"),
+    ("hooks_path", "config_keys.hooks_path"), ("author", "config_keys.author"),
+    ("stash_unstaged", "config_keys.stash_unstaged"), ("hooks_bypassed_footer", "config_keys.hooks_bypassed_footer"),
+    ("allow_empty_commits", "config_keys.allow_empty_commits"), ("git_args", "config_keys.git_args"),
+    ("allowed_email_domains", "config_keys.allowed_email_domains"), ("blocked_paths", "config_keys.blocked_paths"),
+    ("max_file_size_kb", "config_keys.max_file_size_kb"), ("secret_patterns", "config_keys.secret_patterns"),
+    ("max_commit_files", "config_keys.max_commit_files"), ("max_commit_lines", "config_keys.max_commit_lines"),
+    ("block_large_commits", "config_keys.block_large_commits"),
+    ("protected_branches", "config_keys.protected_branches"),
+    ("protected_branches_block", "config_keys.protected_branches_block"), ("base_branch", "config_keys.base_branch"),
+    ("release_types", "config_keys.release_types"), ("merge_type", "config_keys.merge_type"),
+    ("branch_rules", "config_keys.branch_rules"), ("branch_templates", "config_keys.branch_templates"),
+    ("ai", "config_keys.ai"), ("jira", "config_keys.jira"),
+];
+
+pub fn documented_keys() -> Vec<(String, String)> {
+    KEY_DESCRIPTIONS.iter().map(|(key, description)| (key.to_string(), t(description).to_string())).collect()
+}
+
+// Something wrong in a config file, with where it is
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigProblem {
+    pub path: PathBuf,
+    pub line: Option<usize>, // 1-based
+    pub message: String,
+}
+
+impl fmt::Display for ConfigProblem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path.display(), line, self.message),
+            None => write!(f, "{}: {}", self.path.display(), self.message),
+        }
+    }
+}
+
+// Read, parse and check one config file; None if it can't be used at all
+fn load_layer(path: &Path, problems: &mut Vec<ConfigProblem>) -> Option<Config> {
+    let mut problem = |line: Option<usize>, message: String| {
+        problems.push(ConfigProblem { path: path.to_path_buf(), line, message });
+    };
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            problem(None, format!("could not read the file: {}", e));
+            return None;
+        }
+    };
+    let mut config = match toml::from_str::<Config>(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            let line = e.span().map(|span| line_at(&content, span.start));
+            problem(line, format!("{} (the whole file is ignored)", e.message().lines().collect::<Vec<_>>().join(", ")));
+            return None;
+        }
+    };
+
+    // Unknown keys parse fine (and are ignored), so look for them separately
+    if let Ok(table) = content.parse::<toml::Table>() {
+        for key in table.keys().filter(|k| !KNOWN_KEYS.contains(&k.as_str())) {
+            problem(key_line(&content, key), format!("unknown setting '{}' (ignored)", key));
+        }
+    }
+
+    if let Some(types) = &config.types {
+        if types.is_empty() {
+            problem(key_line(&content, "types"), "`types` is empty; using the default types".to_string());
+            config.types = None;
+        } else {
+            let mut seen = Vec::new();
+            for ty in types {
+                if seen.contains(&ty) {
+                    problem(key_line(&content, "types"), format!("type '{}' is listed more than once", ty));
+                }
+                seen.push(ty);
+            }
+        }
+    }
+    if config.subject_max_length == Some(0) {
+        problem(key_line(&content, "subject_max_length"), "`subject_max_length` must be at least 1; using the default".to_string());
+        config.subject_max_length = None;
+    }
+    if config.body_max_line_length == Some(0) {
+        problem(key_line(&content, "body_max_line_length"), "`body_max_line_length` must be at least 1; using the default".to_string());
+        config.body_max_line_length = None;
+    }
+    // An invalid regex is dropped from this file, so it can't quietly match
+    // nothing (or everything) later; the other layers still apply
+    if let Some(Err(e)) = config.scope_pattern.as_deref().map(Regex::new) {
+        problem(key_line(&content, "scope_pattern"), format!("invalid `scope_pattern` regex: {} (ignored)", regex_error(&e)));
+        config.scope_pattern = None;
+    }
+    if let Some(rules) = &mut config.branch_rules {
+        rules.retain(|rule| match Regex::new(&rule.pattern) {
+            Ok(_) => true,
+            Err(e) => {
+                problem(text_line(&content, &rule.pattern), format!("invalid branch rule pattern: {} (rule ignored)", regex_error(&e)));
+                false
+            }
+        });
+    }
+    if let Some(Err(e)) = config.issue_pattern.as_deref().map(Regex::new) {
+        problem(key_line(&content, "issue_pattern"), format!("invalid `issue_pattern` regex: {} (ignored)", regex_error(&e)));
+        config.issue_pattern = None;
+    }
+    if let Some(patterns) = &mut config.secret_patterns {
+        patterns.retain(|pattern| match Regex::new(pattern) {
+            Ok(_) => true,
+            Err(e) => {
+                problem(text_line(&content, pattern), format!("invalid secret pattern: {} (ignored)", regex_error(&e)));
+                false
+            }
+        });
+    }
+    // Likewise an invalid glob, which would otherwise match nothing and so turn
+    // off the check it's part of
+    let mut valid_glob = |pattern: &str, glob: &str, what: &str| match glob::Pattern::new(glob) {
+        Ok(_) => true,
+        Err(e) => {
+            problem(text_line(&content, pattern), format!("invalid {} '{}': {} (ignored)", what, pattern, e.msg));
+            false
+        }
+    };
+    if let Some(patterns) = &mut config.blocked_paths {
+        patterns.retain(|pattern| valid_glob(pattern, pattern.trim_end_matches('/'), "blocked path"));
+    }
+    if let Some(patterns) = &mut config.protected_branches {
+        patterns.retain(|pattern| valid_glob(pattern, pattern, "protected branch"));
+    }
+    for rules in [&mut config.scope_rules, &mut config.path_types].into_iter().flatten() {
+        rules.retain(|pattern, _| valid_glob(pattern, pattern, "path rule"));
+    }
+    if let Some(templates) = &mut config.branch_templates {
+        templates.retain(|pattern, _| valid_glob(pattern, pattern, "branch template"));
+    }
+    if let Some(filters) = &mut config.input_filters {
+        filters.retain(|field, pattern| match Regex::new(pattern) {
+            Ok(_) => true,
+            Err(e) => {
+                problem(text_line(&content, pattern), format!("invalid input filter for '{}': {} (ignored)", field, regex_error(&e)));
+                false
+            }
+        });
+    }
+
+    Some(config)
+}
+
+// regex errors draw a caret diagram over several lines; the last one says what's wrong
+fn regex_error(e: &regex::Error) -> String {
+    let text = e.to_string();
+    let last = text.lines().last().unwrap_or_default();
+    last.strip_prefix("error: ").unwrap_or(last).to_string()
+}
+
+// 1-based line holding byte `offset`
+fn line_at(content: &str, offset: usize) -> usize {
+    content[..offset.min(content.len())].matches('\n').count() + 1
+}
+
+// Where `key = ...` or `[key]` is set
+fn key_line(content: &str, key: &str) -> Option<usize> {
+    content.lines().position(|line| {
+        let line = line.trim_start().trim_start_matches('[');
+        line.strip_prefix(key).is_some_and(|rest| rest.trim_start().starts_with(['=', ']']))
+    }).map(|i| i + 1)
+}
+
+// First line mentioning `text` (e.g. a regex that doesn't compile)
+fn text_line(content: &str, text: &str) -> Option<usize> {
+    content.lines().position(|line| line.contains(text)).map(|i| i + 1)
+}
+
+// Where an effective setting came from (`commiTUI config show`)
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    Preset(Convention),
+    File(PathBuf),
+    CommandLine,
+    Git(&'static str), // Not set in any file, so the git setting applies
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Preset(convention) => write!(f, "from the {} preset", format!("{:?}", convention).to_lowercase()),
+            Source::File(path) => write!(f, "from {}", path.display()),
+            Source::CommandLine => write!(f, "from the command line"),
+            Source::Git(key) => write!(f, "from git config {}", key),
+        }
+    }
+}
+
+// Credit `source` with every key `layer` sets, the way `merge` applies them
+fn note_sources(sources: &mut BTreeMap<String, Source>, layer: &Config, source: Source) {
+    let Ok(table) = toml::Table::try_from(layer) else { return };
+    for key in table.keys() {
+        sources.insert(key.clone(), source.clone());
+    }
+    // A scope list replaces the default groups as well
+    if table.contains_key("scopes") && !table.contains_key("scope_groups") {
+        sources.insert("scope_groups".to_string(), source);
+    }
+}
+
+// The per-repository config file, which in-app edits are written to
+pub const LOCAL_CONFIG: &str = "./commitui.toml";
+
+// Set `key = [items]` in a config file (created if missing), leaving the rest
+// of it, comments included, as it was. A scope list would replace the groups
+// from other layers, so `groups` go alongside it unless the file has its own.
+pub fn save_list(path: &Path, key: &str, items: &[String], groups: &[ScopeGroup]) -> Result<(), Box<dyn std::error::Error>> {
+    let content = if path.exists() { fs::read_to_string(path)? } else { String::new() };
+    let mut doc: toml_edit::DocumentMut = content.parse()?;
+
+    let mut array: toml_edit::Array = items.iter().collect();
+    match doc.get_mut(key).and_then(|item| item.as_value_mut()) {
+        Some(value) => {
+            // One item per line if that's how it was written
+            if value.to_string().trim().contains('\n') {
+                for item in array.iter_mut() {
+                    item.decor_mut().set_prefix("\n    ");
+                }
+                array.set_trailing("\n");
+                array.set_trailing_comma(true);
+            }
+            let decor = value.decor().clone();
+            *value = array.into();
+            *value.decor_mut() = decor;
+        }
+        None => {
+            doc.insert(key, toml_edit::value(array));
+        }
+    }
+    if key == "scopes" && !groups.is_empty() && !doc.contains_key("scope_groups") {
+        let mut tables = toml_edit::ArrayOfTables::new();
+        for group in groups {
+            let mut table = toml_edit::Table::new();
+            table.insert("name", toml_edit::value(group.name.as_str()));
+            table.insert("scopes", toml_edit::value(group.scopes.iter().collect::<toml_edit::Array>()));
+            tables.push(table);
+        }
+        doc.insert("scope_groups", toml_edit::Item::ArrayOfTables(tables));
+    }
+    fs::write(path, doc.to_string())?;
+    Ok(())
+}
+
 // --- Config Loading Logic ---
 impl Config {
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
-        let mut final_config = Config::default();
+        Config::load_with_convention(None)
+    }
+
+    // `convention` (from --convention) overrides the one in the config files
+    pub fn load_with_convention(convention: Option<Convention>) -> Result<Self, Box<dyn std::error::Error>> {
+        let (config, problems) = Config::load_checked(convention);
+        for problem in problems {
+            eprintln!("Warning: {}", problem);
+        }
+        Ok(config)
+    }
+
+    // Load everything that can be used, along with what was wrong in the files.
+    // Unparsable files are skipped, and invalid settings fall back to their defaults.
+    pub fn load_checked(convention: Option<Convention>) -> (Self, Vec<ConfigProblem>) {
+        let (config, _, problems) = Config::load_explained(convention);
+        (config, problems)
+    }
+
+    // `load_checked`, also saying which layer set each key; keys left out of
+    // the map are at their defaults
+    pub fn load_explained(convention: Option<Convention>) -> (Self, BTreeMap<String, Source>, Vec<ConfigProblem>) {
+        let mut layers = Vec::new();
+        let mut problems = Vec::new();
 
         // 1. Try to load global config
         if let Some(global_config_path) = Config::get_global_config_path() {
             if global_config_path.exists() {
-                if let Ok(content) = fs::read_to_string(&global_config_path) {
-                    match toml::from_str::<Config>(&content) {
-                        Ok(global_config) => {
-                            final_config.merge(global_config);
-                        },
-                        Err(e) => eprintln!("Warning: Could not parse global config at {}: {}", global_config_path.display(), e),
-                    }
-                } else {
-                    eprintln!("Warning: Could not read global config at {}", global_config_path.display());
-                }
+                layers.extend(load_layer(&global_config_path, &mut problems).map(|layer| (global_config_path.clone(), layer)));
             }
         }
 
         // 2. Try to load local config (./commitui.toml)
-        let local_config_paths = ["./commitui.toml"];
+        let local_config_paths = [LOCAL_CONFIG];
         for path in &local_config_paths {
-            if let Ok(content) = fs::read_to_string(path) {
-                match toml::from_str::<Config>(&content) {
-                    Ok(local_config) => {
-                        final_config.merge(local_config);
-                        return Ok(final_config);
-                    },
-                    Err(e) => eprintln!("Warning: Could not parse local config at {}: {}", path, e),
+            let path = Path::new(path);
+            if path.exists() {
+                if let Some(layer) = load_layer(path, &mut problems) {
+                    layers.push((path.to_path_buf(), layer));
+                    break;
                 }
             }
         }
 
-        Ok(final_config)
+        // 3. Defaults, then the convention's preset, then the files on top
+        let from_cli = convention.is_some();
+        let convention = convention
+            .or_else(|| layers.iter().rev().find_map(|(_, layer)| layer.convention))
+            .unwrap_or(Convention::Conventional);
+        let mut final_config = Config::default();
+        let mut sources = BTreeMap::new();
+        if let Some(preset) = preset(convention) {
+            note_sources(&mut sources, &preset, Source::Preset(convention));
+            final_config.merge(preset);
+        }
+        for (path, layer) in layers {
+            note_sources(&mut sources, &layer, Source::File(path));
+            final_config.merge(layer);
+        }
+        final_config.convention = Some(convention);
+        if from_cli {
+            sources.insert("convention".to_string(), Source::CommandLine);
+        }
+
+        (final_config, sources, problems)
+    }
+
+    // The scope picker rows: plain `scopes` first, then each group under its header
+    pub fn scope_entries(&self) -> Vec<ScopeEntry> {
+        let mut entries: Vec<ScopeEntry> = self.scopes.iter().flatten().map(|s| ScopeEntry::Scope(s.clone())).collect();
+        for group in self.scope_groups.iter().flatten() {
+            entries.push(ScopeEntry::Header(group.name.clone()));
+            entries.extend(group.scopes.iter().map(|s| ScopeEntry::Scope(s.clone())));
+        }
+        entries
+    }
+
+    // `branch_rules` as set, or the built-in ones for the configured types
+    pub fn branch_rules(&self) -> Vec<BranchRule> {
+        self.branch_rules.clone().unwrap_or_else(|| default_branch_rules(self.types.as_deref().unwrap_or(&default_types())))
+    }
+
+    // `[type_rules]` entry for a type, if it has one
+    pub fn type_rules(&self, commit_type: Option<&str>) -> Option<&TypeRules> {
+        self.type_rules.as_ref()?.get(commit_type?)
+    }
+
+    // Whether `scope` is offered in the picker (as opposed to a custom scope)
+    pub fn is_listed_scope(&self, scope: &str) -> bool {
+        self.scope_entries().iter().any(|e| e.scope() == Some(scope))
     }
 
     pub fn get_global_config_path() -> Option<PathBuf> {
@@ -131,4 +1242,74 @@ impl Config {
             None
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_invalid_regex_is_dropped_from_its_layer() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, br#"
+scope_pattern = "^[a-z+$"
+
+[input_filters]
+subject = "[unclosed"
+scope = "^[a-z]$"
+
+[[branch_rules]]
+pattern = "(?P<type>feat"
+
+[[branch_rules]]
+pattern = '^(?P<type>\w+)/(?P<subject>.+)$'
+"#).unwrap();
+        let mut problems = Vec::new();
+        let config = load_layer(file.path(), &mut problems).unwrap();
+
+        assert_eq!(problems.len(), 3, "{:?}", problems);
+        assert!(problems.iter().all(|problem| problem.message.contains("ignored")));
+        assert_eq!(config.scope_pattern, None);
+        assert_eq!(config.input_filters.unwrap().into_keys().collect::<Vec<_>>(), ["scope"]);
+        let rules = config.branch_rules.unwrap();
+        assert_eq!(rules.len(), 1);
+        assert!(rules[0].pattern.starts_with("^(?P<type>"));
+    }
+
+    #[test]
+    fn an_invalid_glob_is_dropped_from_its_layer() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, br#"
+blocked_paths = ["secrets/[", ".env"]
+protected_branches = ["release/[", "main"]
+
+[scope_rules]
+"src/[" = "core"
+"docs/**" = "docs"
+
+[branch_templates."feature/["]
+scope = "ui"
+"#).unwrap();
+        let mut problems = Vec::new();
+        let config = load_layer(file.path(), &mut problems).unwrap();
+
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems.iter().all(|problem| problem.message.contains("(ignored)")));
+        assert_eq!(config.blocked_paths.unwrap(), [".env"]);
+        assert_eq!(config.protected_branches.unwrap(), ["main"]);
+        assert_eq!(config.scope_rules.unwrap().into_keys().collect::<Vec<_>>(), ["docs/**"]);
+        assert!(config.branch_templates.unwrap().is_empty());
+    }
+
+    #[test]
+    fn every_known_key_is_described() {
+        let described: Vec<&str> = KEY_DESCRIPTIONS.iter().map(|(key, _)| *key).collect();
+        let mut known = KNOWN_KEYS.to_vec();
+        known.sort_unstable();
+        let mut sorted = described.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, known);
+        for (key, description) in KEY_DESCRIPTIONS {
+            assert_ne!(t(description), *description, "{} has no description", key);
+        }
+    }
+}
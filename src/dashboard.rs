@@ -0,0 +1,137 @@
+use commitui::git::{range_commits, recent_commits};
+use commitui::i18n::{t, tf};
+use commitui::stats::CommitStats;
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    widgets::{BarChart, Block, Borders, Gauge, Paragraph},
+    style::{Style, Color},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+const TOP_SCOPES: usize = 8;
+
+// The commits a dashboard is computed over; Left/Right cycles through them
+#[derive(Debug, Clone, PartialEq)]
+enum StatsRange {
+    Last(usize),
+    All,
+    Range(String), // Given on the command line
+}
+
+impl StatsRange {
+    fn label(&self) -> String {
+        match self {
+            StatsRange::Last(n) => tf("stats.last", &[("count", &n.to_string())]),
+            StatsRange::All => t("stats.all").to_string(),
+            StatsRange::Range(range) => range.clone(),
+        }
+    }
+
+    fn stats(&self) -> Result<CommitStats, Box<dyn std::error::Error>> {
+        let entries = match self {
+            StatsRange::Last(n) => recent_commits(*n)?,
+            StatsRange::All => range_commits("HEAD")?,
+            StatsRange::Range(range) => range_commits(range)?,
+        };
+        Ok(CommitStats::from_log(&entries))
+    }
+}
+
+// `commiTUI stats [range]`
+pub fn show_stats(range: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut ranges = vec![StatsRange::Last(50), StatsRange::Last(200), StatsRange::Last(1000), StatsRange::All];
+    if let Some(range) = range {
+        ranges.insert(0, StatsRange::Range(range.to_string()));
+    }
+    // Fail before touching the terminal if the range is wrong
+    let first = ranges[0].stats()?;
+
+    let mut terminal = setup_terminal()?;
+    let result = stats_screen(&mut terminal, &mut CrosstermEvents, &ranges, first);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+fn stats_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    ranges: &[StatsRange],
+    first: CommitStats,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut selected: usize = 0;
+    let mut stats = first;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Totals
+                    Constraint::Length(3), // Compliance gauge
+                    Constraint::Min(8),    // Charts
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let totals = Paragraph::new(tf("stats.totals", &[
+                ("total", &stats.total.to_string()),
+                ("conventional", &stats.conventional.to_string()),
+                ("average", &format!("{:.1}", stats.average_subject_length)),
+            ]))
+            .block(Block::default().title(tf("stats.title", &[("range", &ranges[selected].label())])).borders(Borders::ALL));
+            f.render_widget(totals, chunks[0]);
+
+            let gauge = Gauge::default()
+                .block(Block::default().title(t("stats.compliance")).borders(Borders::ALL))
+                .gauge_style(Style::default().fg(if stats.compliance() >= 80 { Color::Green } else { Color::Yellow }))
+                .percent(stats.compliance());
+            f.render_widget(gauge, chunks[1]);
+
+            let charts = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunks[2]);
+            let types: Vec<(&str, u64)> = stats.types.iter().map(|(t, n)| (t.as_str(), *n)).collect();
+            let type_chart = BarChart::default()
+                .block(Block::default().title(t("stats.types")).borders(Borders::ALL))
+                .data(&types)
+                .bar_width(8)
+                .bar_style(Style::default().fg(Color::Cyan))
+                .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+            f.render_widget(type_chart, charts[0]);
+            let scopes: Vec<(&str, u64)> = stats.scopes.iter().take(TOP_SCOPES).map(|(s, n)| (s.as_str(), *n)).collect();
+            let scope_chart = BarChart::default()
+                .block(Block::default().title(tf("stats.scopes", &[("count", &TOP_SCOPES.to_string())])).borders(Borders::ALL))
+                .data(&scopes)
+                .bar_width(8)
+                .bar_style(Style::default().fg(Color::Magenta))
+                .value_style(Style::default().fg(Color::Black).bg(Color::Magenta));
+            f.render_widget(scope_chart, charts[1]);
+
+            let hints = Paragraph::new(t("hints.stats"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[3]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let previous = selected;
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Right | KeyCode::Tab => selected = (selected + 1) % ranges.len(),
+                KeyCode::Left | KeyCode::BackTab => selected = (selected + ranges.len() - 1) % ranges.len(),
+                _ => {}
+            }
+            if selected != previous {
+                stats = ranges[selected].stats()?;
+            }
+        }
+    }
+}
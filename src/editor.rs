@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::Path;
+
+// A COMMIT_EDITMSG file as git wrote it, taken apart so the wizard can start
+// from its message and everything else goes back unchanged
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EditMessage {
+    // What's already there (amending, a merge, a `rebase -i` reword), comments left out
+    pub message: String,
+    // Comment lines above the scissors (status, merge hints), shown and kept
+    pub comments: Vec<String>,
+    // The scissors line and everything below it (`commit -v`'s diff), verbatim
+    pub scissors: Option<String>,
+}
+
+// `# ------------------------ >8 ------------------------`: git drops the line
+// and all that follows, so nothing below it is a comment or message line
+fn is_scissors(line: &str, comment_char: char) -> bool {
+    line.starts_with(comment_char) && line.contains("------------------------ >8 ------------------------")
+}
+
+pub fn read_edit_message(path: &Path, comment_char: char) -> Result<EditMessage, Box<dyn std::error::Error>> {
+    Ok(parse_edit_message(&fs::read_to_string(path)?, comment_char))
+}
+
+pub fn parse_edit_message(content: &str, comment_char: char) -> EditMessage {
+    let (above, scissors) = match content.lines().position(|line| is_scissors(line, comment_char)) {
+        Some(at) => {
+            let lines: Vec<&str> = content.lines().collect();
+            (lines[..at].join("\n"), Some(lines[at..].join("\n")))
+        }
+        None => (content.to_string(), None),
+    };
+    let (comments, message): (Vec<&str>, Vec<&str>) = above.lines().partition(|line| line.starts_with(comment_char));
+    EditMessage {
+        message: message.join("\n").trim().to_string(),
+        comments: comments.into_iter().map(String::from).collect(),
+        scissors,
+    }
+}
+
+// The comments are written back after the message, then the scissors section;
+// git strips them on cleanup.
+pub fn write_message(path: &Path, message: &str, edit: &EditMessage) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, format_edit_message(message, edit))?;
+    Ok(())
+}
+
+pub fn format_edit_message(message: &str, edit: &EditMessage) -> String {
+    let mut content = message.to_string();
+    for section in [(!edit.comments.is_empty()).then(|| edit.comments.join("\n")), edit.scissors.clone()].into_iter().flatten() {
+        if !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push('\n');
+        content.push_str(&section);
+        content.push('\n');
+    }
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VERBOSE: &str = "fix: old subject\n\nOld body.\n\n\
+        # Please enter the commit message for your changes.\n\
+        # On branch main\n\
+        # ------------------------ >8 ------------------------\n\
+        # Do not modify or remove the line above.\n\
+        diff --git a/f b/f\n\
+        -old\n\
+        +new\n";
+
+    #[test]
+    fn the_message_prefills_and_the_scissors_section_stays() {
+        let edit = parse_edit_message(VERBOSE, '#');
+        assert_eq!(edit.message, "fix: old subject\n\nOld body.");
+        assert_eq!(edit.comments, ["# Please enter the commit message for your changes.", "# On branch main"]);
+        let scissors = edit.scissors.as_deref().unwrap();
+        assert!(scissors.starts_with("# ------------------------ >8"));
+        assert!(scissors.ends_with("+new"));
+
+        let written = format_edit_message("fix: new subject\n", &edit);
+        assert!(written.starts_with("fix: new subject\n\n# Please enter"));
+        assert!(written.ends_with("# On branch main\n\n# ------------------------ >8 ------------------------\n\
+            # Do not modify or remove the line above.\ndiff --git a/f b/f\n-old\n+new\n"));
+    }
+
+    #[test]
+    fn a_fresh_commit_has_no_message_and_no_scissors() {
+        let edit = parse_edit_message("\n# Please enter the commit message\n#\n", '#');
+        assert_eq!(edit.message, "");
+        assert_eq!(edit.scissors, None);
+        assert_eq!(format_edit_message("feat: x", &edit), "feat: x\n\n# Please enter the commit message\n#\n");
+    }
+}
@@ -0,0 +1,163 @@
+use commitui::git::{diff_stat, recent_commits, LogEntry};
+use commitui::i18n::{t, tf};
+use commitui::parser::{parse_message, ParsedCommit};
+use commitui::search::{search, Query};
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// `commiTUI search [query]` over the last `limit` commits
+pub fn search_history(query: Option<&str>, limit: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let entries = recent_commits(limit)?;
+    let mut terminal = setup_terminal()?;
+    let stat = |sha: &str| diff_stat(sha).unwrap_or_default();
+    let result = search_screen(&mut terminal, &mut CrosstermEvents, &entries, query.unwrap_or_default(), stat);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+// Typing narrows the list; Enter opens a commit's parsed fields and diff stat
+// (`stat` reads it, only for the commit opened)
+pub fn search_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    entries: &[LogEntry],
+    initial: &str,
+    mut stat: impl FnMut(&str) -> String,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let parsed: Vec<ParsedCommit> = entries.iter().map(|e| parse_message(&e.message)).collect();
+    let mut input = initial.to_string();
+    let mut matches = search(&Query::parse(&input), &parsed);
+    let mut selected: usize = 0;
+    // The commit being looked at, with its diff stat
+    let mut detail: Option<(usize, String)> = None;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Query
+                    Constraint::Min(3),
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let query = Paragraph::new(input.as_str())
+                .block(Block::default()
+                    .title(tf("titles.search", &[("count", &matches.len().to_string()), ("total", &entries.len().to_string())]))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Green)))
+                .style(Style::default().fg(Color::Yellow));
+            f.render_widget(query, chunks[0]);
+
+            match &detail {
+                Some((index, stat)) => {
+                    let paragraph = Paragraph::new(detail_lines(&entries[*index], &parsed[*index], stat))
+                        .block(Block::default().title(t("titles.search_detail")).borders(Borders::ALL))
+                        .wrap(Wrap { trim: false });
+                    f.render_widget(paragraph, chunks[1]);
+                }
+                None => {
+                    let items: Vec<ListItem> = matches
+                        .iter()
+                        .map(|&i| {
+                            let (entry, commit) = (&entries[i], &parsed[i]);
+                            let line = format!(
+                                "{:<8} {:<10} {:<12} {}",
+                                &entry.sha[..entry.sha.len().min(7)],
+                                commit.commit_type.as_deref().unwrap_or("-"),
+                                commit.scope.as_deref().unwrap_or("-"),
+                                commit.subject
+                            );
+                            // Non-conventional commits are listed but dimmed, as in `log`
+                            if commit.commit_type.is_some() {
+                                ListItem::new(line)
+                            } else {
+                                ListItem::new(line).style(Style::default().fg(Color::DarkGray))
+                            }
+                        })
+                        .collect();
+                    let mut list_state = ListState::default();
+                    list_state.select((!matches.is_empty()).then_some(selected));
+                    let list = List::new(items)
+                        .block(Block::default().title(t("titles.search_results")).borders(Borders::ALL))
+                        .highlight_style(Style::default().bg(Color::Blue))
+                        .highlight_symbol(">> ");
+                    f.render_stateful_widget(list, chunks[1], &mut list_state);
+                }
+            }
+
+            let hints = Paragraph::new(if detail.is_some() { t("hints.search_detail") } else { t("hints.search") })
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[2]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                return Ok(());
+            }
+            if detail.is_some() {
+                if matches!(key.code, KeyCode::Esc | KeyCode::Enter | KeyCode::Left | KeyCode::Backspace) {
+                    detail = None;
+                }
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc => return Ok(()),
+                KeyCode::Down => selected = (selected + 1).min(matches.len().saturating_sub(1)),
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Enter if !matches.is_empty() => {
+                    let index = matches[selected];
+                    detail = Some((index, stat(&entries[index].sha)));
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => input.push(c),
+                _ => continue,
+            }
+            if matches!(key.code, KeyCode::Backspace | KeyCode::Char(_)) {
+                matches = search(&Query::parse(&input), &parsed);
+                selected = 0;
+            }
+        }
+    }
+}
+
+// The parsed fields first, then the message as written and what it changed
+fn detail_lines<'a>(entry: &'a LogEntry, commit: &'a ParsedCommit, stat: &'a str) -> Vec<Line<'a>> {
+    let label = Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD);
+    let field = |name: &'static str, value: String| Line::from(vec![Span::styled(t(name), label), Span::raw(value)]);
+    let mut lines = vec![
+        field("search.sha", entry.sha.clone()),
+        field("search.type", commit.commit_type.clone().unwrap_or_else(|| "-".into())),
+        field("search.scope", commit.scope.clone().unwrap_or_else(|| "-".into())),
+    ];
+    if let Some(breaking) = &commit.breaking {
+        lines.push(Line::from(vec![
+            Span::styled(t("search.breaking"), label.fg(Color::Red)),
+            Span::raw(breaking.clone()),
+        ]));
+    } else if commit.bang {
+        lines.push(Line::from(Span::styled(t("search.breaking"), label.fg(Color::Red))));
+    }
+    lines.push(Line::from(""));
+    lines.extend(entry.message.lines().map(Line::from));
+    if !stat.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(stat.lines().map(|line| Line::from(Span::styled(line, Style::default().add_modifier(Modifier::DIM)))));
+    }
+    lines
+}
@@ -0,0 +1,124 @@
+use commitui::git::{autosquash, commit_fixup, recent_commits, staged_changes, CommitOptions, LogEntry};
+use commitui::i18n::{t, tf};
+use crate::history::HISTORY_LIMIT;
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Style, Color},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// What the picker was confirmed with
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixupChoice {
+    pub sha: String,
+    pub squash: bool, // `squash!` (keeps a message to combine) rather than `fixup!`
+    pub rebase: bool, // Fold it in with `git rebase -i --autosquash` right away
+}
+
+// `commiTUI fixup`: a `fixup!`/`squash!` commit of the staged changes for a
+// recent commit, optionally rebased into it straight away
+pub fn fixup(rebase: bool, options: &CommitOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if staged_changes()?.is_empty() {
+        return Err("Nothing is staged: stage the changes that belong in the earlier commit first.".into());
+    }
+    let entries = recent_commits(HISTORY_LIMIT)?;
+
+    let mut terminal = setup_terminal()?;
+    let result = fixup_screen(&mut terminal, &mut CrosstermEvents, &entries, rebase);
+    restore_terminal(&mut terminal)?;
+    let Some(choice) = result? else {
+        eprintln!("Aborted.");
+        std::process::exit(1);
+    };
+
+    let output = commit_fixup(&choice.sha, choice.squash, options)?;
+    print!("{}", output.stdout);
+    if !output.success {
+        eprint!("{}", output.stderr);
+        std::process::exit(1);
+    }
+    if choice.rebase {
+        let output = autosquash(&choice.sha)?;
+        print!("{}", output.stdout);
+        eprint!("{}", output.stderr); // git reports rebase progress on stderr
+        if !output.success {
+            eprintln!("The rebase stopped; finish it with `git rebase --continue` or undo it with `git rebase --abort`.");
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+// Recent commits to pick the target from; None if the user backed out
+pub fn fixup_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    entries: &[LogEntry],
+    mut rebase: bool,
+) -> Result<Option<FixupChoice>, Box<dyn std::error::Error>> {
+    let mut selected: usize = 0;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),
+                    Constraint::Length(1), // Rebase toggle
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let items: Vec<ListItem> = entries
+                .iter()
+                .map(|entry| {
+                    let header = entry.message.lines().next().unwrap_or_default();
+                    // Earlier fixups are targets too, but rarely the one meant
+                    let style = if header.starts_with("fixup! ") || header.starts_with("squash! ") {
+                        Style::default().fg(Color::DarkGray)
+                    } else {
+                        Style::default()
+                    };
+                    ListItem::new(format!("{:<8} {}", &entry.sha[..entry.sha.len().min(7)], header)).style(style)
+                })
+                .collect();
+            let title = if entries.is_empty() { t("titles.recent_commits_empty") } else { t("titles.fixup") };
+            let mut list_state = ListState::default();
+            list_state.select(Some(selected));
+            let list = List::new(items)
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .highlight_style(Style::default().bg(Color::Blue))
+                .highlight_symbol(">> ");
+            f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+            let mark = if rebase { "x" } else { " " };
+            f.render_widget(Paragraph::new(tf("text.autosquash", &[("mark", mark)])), chunks[1]);
+
+            let hints = Paragraph::new(t("hints.fixup"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[2]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let choice = |squash| entries.get(selected).map(|entry| FixupChoice { sha: entry.sha.clone(), squash, rebase });
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Down => selected = (selected + 1).min(entries.len().saturating_sub(1)),
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Tab | KeyCode::Char('r') => rebase = !rebase,
+                KeyCode::Enter | KeyCode::Char('f') if !entries.is_empty() => return Ok(choice(false)),
+                KeyCode::Char('s') if !entries.is_empty() => return Ok(choice(true)),
+                _ => {}
+            }
+        }
+    }
+}
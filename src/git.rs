@@ -1,21 +1,846 @@
+use serde::Serialize;
+use std::fmt;
 use std::io::Write;
-use std::process::Command;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::Mutex;
+use crate::suggest::StagedFile;
+use crate::trace::TracedCommand;
 
-pub fn commit_with_message(message: &str) -> Result<(), Box<dyn std::error::Error>> {
+// Captured result of `git commit`; hooks write to both streams
+#[derive(Debug, Clone)]
+pub struct CommitOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+// A git command that exited non-zero, with everything it printed, so callers
+// can show it on a screen or in --json rather than as one squashed line
+#[derive(Debug, Clone, Serialize)]
+pub struct GitError {
+    pub command: String, // "git revert --no-commit abc123"
+    pub status: Option<i32>, // None when killed by a signal
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl GitError {
+    fn new(command: &Command, output: &Output) -> Self {
+        let args = command.get_args().map(|arg| arg.to_string_lossy().into_owned());
+        GitError {
+            command: std::iter::once("git".to_string()).chain(args).collect::<Vec<_>>().join(" "),
+            status: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+
+    // Both streams, the way they'd have appeared in a terminal (near enough)
+    pub fn details(&self) -> String {
+        format!("{}{}", self.stdout, self.stderr).trim().to_string()
+    }
+}
+
+impl fmt::Display for GitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // "git revert failed: ..." without the arguments, as before
+        let mut words = self.command.split(' ').skip(1);
+        let mut name = "";
+        while let Some(word) = words.next() {
+            match word {
+                "-c" => {
+                    words.next();
+                }
+                word if word.starts_with('-') => {}
+                word => {
+                    name = word;
+                    break;
+                }
+            }
+        }
+        write!(f, "git {} failed: {}", name, self.details())
+    }
+}
+
+impl std::error::Error for GitError {}
+
+impl CommitOutput {
+    // git gave up because the signing program (gpg, gpg-agent's pinentry,
+    // ssh-keygen) failed, rather than a hook or the commit itself
+    pub fn signing_failed(&self) -> bool {
+        !self.success
+            && ["gpg failed to sign the data", "gpg-agent", "No pinentry", "Inappropriate ioctl for device", "ssh-keygen"]
+                .iter()
+                .any(|needle| self.stderr.contains(needle))
+    }
+}
+
+// Extra behaviour for the `git commit` invocation
+#[derive(Debug, Clone, Default)]
+pub struct CommitOptions {
+    pub gpg_sign: bool,
+    pub signoff: bool,
+    pub verbose: bool, // Status and diff commented into the message file, like `git commit -v`
+    pub author: Option<String>, // --author, "Name <email>"
+    pub date: Option<String>, // --date, anything git accepts
+    pub stash_unstaged: bool, // Set unstaged changes aside so hooks only see what's committed
+    pub hooks_path: Option<String>, // Run the hooks from here rather than core.hooksPath
+    pub no_verify: bool, // Skip the pre-commit and commit-msg hooks
+    pub bypass_trailer: Option<String>, // Added to the message when the hooks are skipped, so it shows in the history
+    pub allow_empty: bool, // Commit even with nothing staged
+    pub extra_args: Vec<String>, // Forwarded verbatim, after our own flags
+}
+
+impl CommitOptions {
+    // `-- --amend` on the command line or in `git_args`
+    pub fn amends(&self) -> bool {
+        self.extra_args.iter().any(|arg| arg == "--amend")
+    }
+
+    // Amending, or `-a`/`--all` in the forwarded args: the commit has content
+    // without anything staged
+    pub fn brings_changes(&self) -> bool {
+        self.amends() || self.extra_args.iter().any(|arg| arg == "-a" || arg == "--all")
+    }
+
+    // `-c` settings, before the `commit` subcommand
+    fn git_flags(&self, command: &mut Command) {
+        if let Some(hooks_path) = &self.hooks_path {
+            command.arg("-c").arg(format!("core.hooksPath={}", hooks_path));
+        }
+    }
+
+    // Signing is spelled out either way: `gpg_sign` already took commit.gpgSign
+    // into account, and turning it off in the preview has to win over it
+    fn commit_flags(&self, command: &mut Command) {
+        command.arg(if self.gpg_sign { "-S" } else { "--no-gpg-sign" });
+        if self.gpg_sign {
+            // The wizard has handed the terminal back by now: pinentry-tty and
+            // pinentry-curses ask for the passphrase on it, found through GPG_TTY
+            command.stdin(Stdio::inherit());
+            if std::env::var_os("GPG_TTY").is_none() {
+                if let Some(tty) = terminal_name() {
+                    command.env("GPG_TTY", tty);
+                }
+            }
+        }
+        if self.signoff {
+            command.arg("--signoff");
+        }
+        // Emptied in the preview's override panel means "not overridden"
+        if let Some(author) = self.author.as_deref().filter(|a| !a.trim().is_empty()) {
+            command.arg(format!("--author={}", author.trim()));
+        }
+        if let Some(date) = self.date.as_deref().filter(|d| !d.trim().is_empty()) {
+            command.arg(format!("--date={}", date.trim()));
+        }
+        if self.allow_empty {
+            command.arg("--allow-empty");
+        }
+        if self.no_verify {
+            command.arg("--no-verify");
+            if let Some(trailer) = &self.bypass_trailer {
+                command.arg(format!("--trailer={}", trailer));
+            }
+        }
+    }
+
+    // The --author/--date override being edited on the preview (0 = author, 1 = date)
+    pub fn override_field(&mut self, index: usize) -> &mut String {
+        let field = if index == 0 { &mut self.author } else { &mut self.date };
+        field.get_or_insert_with(String::new)
+    }
+}
+
+// The terminal on stdin (`tty`), None when it isn't one
+fn terminal_name() -> Option<String> {
+    let output = Command::new("tty").stdin(Stdio::inherit()).output().ok()?;
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (output.status.success() && !name.is_empty()).then_some(name)
+}
+
+// Output is captured rather than inherited so a failing hook can be shown in the TUI.
+pub fn commit_with_message(message: &str, options: &CommitOptions) -> Result<CommitOutput, Box<dyn std::error::Error>> {
     let mut file = tempfile::NamedTempFile::new()?;
     write!(file, "{}", message)?;
+    if options.verbose {
+        write!(file, "{}", verbose_comments(comment_char()))?;
+    }
     let path = file.path();
 
-    let status = Command::new("git")
-        .arg("commit")
-        .arg("-F")
-        .arg(path)
-        .status()?;
+    let mut command = Command::new("git");
+    options.git_flags(&mut command);
+    command.arg("commit").arg("-F").arg(path);
+    if options.verbose {
+        // Hooks see the comments in COMMIT_EDITMSG; the commit itself doesn't
+        command.arg("--cleanup=strip");
+    }
+    options.commit_flags(&mut command);
+    command.args(&options.extra_args);
+
+    let here = Path::new(".");
+    let stash = if options.stash_unstaged && has_unstaged_changes() { stash_unstaged(here)? } else { None };
+    let output = command.traced_output();
+    // Whatever happened to the commit, the changes go back where they were
+    let restored = stash.as_deref().map(|stash| restore_stashed(here, stash));
+    let output = output?;
 
-    if status.success() {
-        println!("Commit successful!");
+    let mut stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    match (restored, stash) {
+        (Some(Ok(())), _) => stdout.push_str("Unstaged changes were stashed for the commit and restored.\n"),
+        (Some(Err(e)), Some(stash)) => stdout.push_str(&format!(
+            "Could not restore the unstaged changes ({}); they are still in stash {}: run \
+             `git restore --source={} --worktree -- :/` then `git stash drop`.\n",
+            e, stash, stash
+        )),
+        _ => {}
+    }
+    Ok(CommitOutput {
+        success: output.status.success(),
+        stdout,
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+// `git commit --fixup=<sha>` (or `--squash`) with what's staged; git writes the
+// `fixup! <subject>` message itself
+pub fn commit_fixup(sha: &str, squash: bool, options: &CommitOptions) -> Result<CommitOutput, Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    options.git_flags(&mut command);
+    command.arg("commit");
+    if squash {
+        command.arg(format!("--squash={}", sha)).arg("--no-edit");
     } else {
-        println!("Commit failed. See above for details.");
+        command.arg(format!("--fixup={}", sha));
+    }
+    options.commit_flags(&mut command);
+    let output = command.traced_output()?;
+    Ok(CommitOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+// Fold the fixup!/squash! commits into their targets: `git rebase -i
+// --autosquash` from below `sha`, taking the todo list and any combined
+// squash message as git writes them
+pub fn autosquash(sha: &str) -> Result<CommitOutput, Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command
+        .env("GIT_SEQUENCE_EDITOR", "true")
+        .env("GIT_EDITOR", "true")
+        .args(["rebase", "-i", "--autosquash", "--autostash"]);
+    match rev_parse(&format!("{}~", sha)) {
+        Ok(parent) => command.arg(parent),
+        Err(_) => command.arg("--root"), // The target is the first commit
+    };
+    let output = command.traced_output()?;
+    Ok(CommitOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+        stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+    })
+}
+
+// Tracked files with changes that aren't staged
+pub fn has_unstaged_changes() -> bool {
+    Command::new("git")
+        .args(["diff", "--quiet"])
+        .traced_output()
+        .is_ok_and(|output| output.status.code() == Some(1))
+}
+
+// `git stash push --keep-index`: the staged changes stay in place. Returns
+// the stash's commit, None when there was nothing to set aside.
+fn stash_unstaged(dir: &Path) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let before = git_output_in(dir, &["rev-parse", "-q", "--verify", "refs/stash"]).ok();
+    git_output_in(dir, &["stash", "push", "--keep-index", "--message", "commiTUI: unstaged changes"])?;
+    let after = git_output_in(dir, &["rev-parse", "-q", "--verify", "refs/stash"]).ok();
+    Ok(after.filter(|after| Some(after) != before.as_ref()))
+}
+
+// The stash's tree is the whole working tree as it was, so it's copied back
+// rather than popped: a pop merges, and conflicts on any partially staged file
+// whose staged lines sit next to unstaged ones. `:/` is the top of the working
+// tree wherever `dir` is in it, and the stash is only dropped once the working
+// tree is back to what it holds.
+fn restore_stashed(dir: &Path, stash: &str) -> Result<(), Box<dyn std::error::Error>> {
+    git_output_in(dir, &["restore", &format!("--source={}", stash), "--worktree", "--", ":/"])?;
+    git_output_in(dir, &["diff", "--quiet", stash, "--", ":/"])
+        .map_err(|_| "the working tree doesn't match the stash")?;
+    if git_output_in(dir, &["rev-parse", "stash@{0}"])? != stash {
+        return Err("the stash is no longer the newest one".into());
+    }
+    git_output_in(dir, &["stash", "drop", "-q"]).map(|_| ())
+}
+
+// What `git commit -v` puts below the message: the status, then the staged
+// diff, every line commented out so `--cleanup=strip` removes it again
+fn verbose_comments(comment: char) -> String {
+    let status = git_output(&["-c", "color.status=false", "status"]).unwrap_or_default();
+    let diff = git_output(&["diff", "--cached", "--no-color"]).unwrap_or_default();
+    let mut text = String::from("\n");
+    for line in status.lines().chain([""]).chain(diff.lines()) {
+        if line.is_empty() {
+            text.push(comment);
+        } else {
+            text.push_str(&format!("{} {}", comment, line));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+// Returns None when HEAD is detached or we're not inside a repository.
+pub fn current_branch() -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--abbrev-ref", "HEAD"])
+        .traced_output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let branch = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if branch.is_empty() || branch == "HEAD" { None } else { Some(branch) }
+}
+
+
+// Whether we're somewhere there's anything to commit from
+pub fn inside_work_tree() -> bool {
+    Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .traced_output()
+        .is_ok_and(|output| output.status.success() && output.stdout.starts_with(b"true"))
+}
+
+// Top of the working tree; staged paths are relative to it
+pub fn repo_root() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--show-toplevel"])
+        .traced_output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+// The .git directory (or the worktree's own one)
+pub fn git_dir() -> Option<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-dir"])
+        .traced_output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(PathBuf::from(String::from_utf8_lossy(&output.stdout).trim()))
+}
+
+// Inside a repository, but HEAD doesn't point at a branch
+pub fn is_detached_head() -> bool {
+    let inside = Command::new("git")
+        .args(["rev-parse", "--verify", "-q", "HEAD"])
+        .traced_output()
+        .is_ok_and(|o| o.status.success());
+    let on_branch = Command::new("git")
+        .args(["symbolic-ref", "-q", "HEAD"])
+        .traced_output()
+        .is_ok_and(|o| o.status.success());
+    inside && !on_branch
+}
+
+// Who a commit made now is attributed to and what would sign it
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Identity {
+    pub name: String,
+    pub email: String,
+    pub signing_key: Option<String>, // user.signingkey
+    pub signing_format: String, // gpg.format: openpgp, ssh or x509
+    pub ssh_key_command: Option<String>, // gpg.ssh.defaultKeyCommand, for when no key is set
+    pub hooks_path: Option<String>, // core.hooksPath
+}
+
+// `git var GIT_AUTHOR_IDENT` so GIT_AUTHOR_* overrides count, then the signing config
+pub fn identity() -> Option<Identity> {
+    let ident = git_output(&["var", "GIT_AUTHOR_IDENT"]).ok()?;
+    // "Name <email> 1700000000 +0100"
+    let (name, rest) = ident.split_once(" <")?;
+    let (email, _) = rest.split_once('>')?;
+    Some(Identity {
+        name: name.to_string(),
+        email: email.to_string(),
+        signing_key: git_output(&["config", "--get", "user.signingkey"]).ok().filter(|k| !k.is_empty()),
+        signing_format: git_output(&["config", "--get", "gpg.format"]).unwrap_or_else(|_| "openpgp".to_string()),
+        ssh_key_command: git_output(&["config", "--get", "gpg.ssh.defaultKeyCommand"]).ok().filter(|c| !c.is_empty()),
+        hooks_path: git_config().hooks_path,
+    })
+}
+
+// The git settings commiTUI follows unless commitui.toml says otherwise
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GitConfig {
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    pub gpg_sign: Option<bool>, // commit.gpgSign
+    pub verbose: Option<bool>, // commit.verbose (a level counts as on)
+    pub hooks_path: Option<String>, // core.hooksPath
+}
+
+// One `git config` read of every key in `GitConfig`; keys come back lowercased
+pub fn git_config() -> GitConfig {
+    let listed = Command::new("git")
+        .args(["config", "-z", "--get-regexp", r"^(user\.(name|email)|commit\.(gpgsign|verbose)|core\.hookspath)$"])
+        .traced_output()
+        .map(|output| String::from_utf8_lossy(&output.stdout).into_owned())
+        .unwrap_or_default();
+    // git's boolean spellings; commit.verbose may also be a level like 2
+    let boolean = |value: &str| !matches!(value.to_lowercase().as_str(), "false" | "no" | "off" | "0" | "");
+
+    let mut config = GitConfig::default();
+    // "key\nvalue\0" per entry, later ones (more local files) winning
+    for entry in listed.split('\0').filter(|entry| !entry.is_empty()) {
+        let (key, value) = entry.split_once('\n').unwrap_or((entry, "true")); // `[commit] gpgSign` alone means true
+        match key {
+            "user.name" => config.user_name = Some(value.to_string()),
+            "user.email" => config.user_email = Some(value.to_string()),
+            "commit.gpgsign" => config.gpg_sign = Some(boolean(value)),
+            "commit.verbose" => config.verbose = Some(boolean(value)),
+            "core.hookspath" => config.hooks_path = Some(value.to_string()).filter(|path| !path.is_empty()),
+            _ => {}
+        }
+    }
+    config
+}
+
+// Remote-tracking branches that already contain `rev` (it has been pushed)
+pub fn remote_branches_containing(rev: &str) -> Vec<String> {
+    git_output(&["branch", "-r", "--contains", rev, "--format=%(refname:short)"])
+        .map(|out| out.lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default()
+}
+
+// Paths with unresolved merge conflicts
+pub fn conflicted_files() -> Vec<String> {
+    Command::new("git")
+        .args(["diff", "--name-only", "--diff-filter=U"])
+        .traced_output()
+        .map(|o| String::from_utf8_lossy(&o.stdout).lines().map(|l| l.to_string()).collect())
+        .unwrap_or_default()
+}
+
+// core.commentChar, falling back to git's default '#'
+pub fn comment_char() -> char {
+    Command::new("git")
+        .args(["config", "--get", "core.commentChar"])
+        .traced_output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8_lossy(&output.stdout).trim().chars().next())
+        .unwrap_or('#')
+}
+
+// Full hash and subject line of a commit, for templates that refer to it
+pub fn commit_summary(rev: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%H%n%s", rev])
+        .traced_output()?;
+    if !output.status.success() {
+        return Err(format!("Unknown commit '{}': {}", rev, String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut lines = stdout.lines();
+    let sha = lines.next().unwrap_or_default().to_string();
+    let subject = lines.next().unwrap_or_default().to_string();
+    Ok((sha, subject))
+}
+
+// Raw message of a single commit
+pub fn commit_message(rev: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(["log", "-1", "--format=%B", rev])
+        .traced_output()?;
+    if !output.status.success() {
+        return Err(format!("Unknown commit '{}': {}", rev, String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Stage the inverse of a commit without committing, so the wizard writes the message
+pub fn revert_no_commit(sha: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command.args(["revert", "--no-commit", sha]);
+    let output = command.traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::new(&command, &output).into());
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+// `git revert --abort` or `git merge --abort`: back to before it started
+pub fn abort_operation(operation: &str) -> Result<(), Box<dyn std::error::Error>> {
+    git_output(&[operation, "--abort"]).map(|_| ())
+}
+
+// `git merge --no-ff --no-commit`: even a fast-forward leaves a merge to conclude,
+// so there's always a merge commit for the wizard to write
+pub fn merge_no_commit(branch: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command.args(["merge", "--no-ff", "--no-commit", branch]);
+    let output = command.traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::new(&command, &output).into());
+    }
+    Ok(())
+}
+
+// A commit from `git log` with its full raw message
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub sha: String,
+    pub message: String,
+}
+
+pub fn recent_commits(limit: usize) -> Result<Vec<LogEntry>, Box<dyn std::error::Error>> {
+    log_entries(&["-n", &limit.to_string()])
+}
+
+// Commits in a range such as `main..HEAD`, oldest first
+pub fn range_commits(range: &str) -> Result<Vec<LogEntry>, Box<dyn std::error::Error>> {
+    log_entries(&["--reverse", range])
+}
+
+fn log_entries(args: &[&str]) -> Result<Vec<LogEntry>, Box<dyn std::error::Error>> {
+    // Unit/record separators keep multi-line messages intact
+    let mut command = Command::new("git");
+    command.arg("log").args(args).arg("--format=%H%x1f%B%x1e");
+    let output = command.traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::new(&command, &output).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let (sha, message) = record.trim_start_matches('\n').split_once('\x1f')?;
+            Some(LogEntry { sha: sha.to_string(), message: message.trim().to_string() })
+        })
+        .collect())
+}
+
+// stdout of a git command that has to succeed
+fn git_output(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    git_output_in(Path::new("."), args)
+}
+
+fn git_output_in(repo: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command.current_dir(repo).args(args);
+    let output = command.traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::new(&command, &output).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// `message` with `trailers` added by `git interpret-trailers`, so the
+// `trailer.*` settings decide the separators and where they go. The preview
+// asks for the same text on every redraw, so the last answer is kept.
+pub fn interpret_trailers(message: &str, trailers: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    type Answer = (String, Vec<String>, String);
+    static LAST: Mutex<Option<Answer>> = Mutex::new(None);
+    let mut last = LAST.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((asked, asked_trailers, answer)) = last.as_ref() {
+        if asked == message && asked_trailers == trailers {
+            return Ok(answer.clone());
+        }
+    }
+
+    let mut file = tempfile::NamedTempFile::new()?;
+    write!(file, "{}", message)?;
+    let path = file.path().to_string_lossy().into_owned();
+    let mut args = vec!["interpret-trailers"];
+    for trailer in trailers {
+        args.extend(["--trailer", trailer]);
+    }
+    args.push(&path);
+    let answer = git_output(&args)? + "\n";
+    *last = Some((message.to_string(), trailers.to_vec(), answer.clone()));
+    Ok(answer)
+}
+
+// Full hash of a revision
+pub fn rev_parse(rev: &str) -> Result<String, Box<dyn std::error::Error>> {
+    git_output(&["rev-parse", "--verify", rev])
+}
+
+// Merge commits in a range (they can't be reworded by replaying a single parent)
+pub fn range_merges(range: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    Ok(git_output(&["rev-list", "--merges", range])?.lines().map(|l| l.to_string()).collect())
+}
+
+// `git show --stat` for a commit, without the message
+pub fn diff_stat(sha: &str) -> Result<String, Box<dyn std::error::Error>> {
+    git_output(&["show", "--stat", "--no-color", "--format=", sha])
+}
+
+// Recreate `commits` (a linear run ending at HEAD, oldest first) with new
+// messages and move HEAD to the result. Trees, authors and author dates are
+// kept; the committer becomes whoever runs this, as with `git rebase`.
+// Commits before the first changed message keep their hashes.
+pub fn rewrite_messages(commits: &[LogEntry], messages: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    let (Some(first), Some(last)) = (commits.first(), commits.last()) else {
+        return Err("No commits to rewrite".into());
+    };
+    let mut parent = git_output(&["rev-parse", "--verify", "-q", &format!("{}^", first.sha)]).ok();
+    let mut rewritten = false;
+
+    for (entry, message) in commits.iter().zip(messages) {
+        if !rewritten && entry.message.trim() == message.trim() {
+            parent = Some(entry.sha.clone());
+            continue;
+        }
+        rewritten = true;
+
+        let tree = git_output(&["rev-parse", &format!("{}^{{tree}}", entry.sha)])?;
+        let author = git_output(&["log", "-1", "--date=raw", "--format=%an%x1f%ae%x1f%ad", &entry.sha])?;
+        let mut author = author.split('\x1f');
+
+        let mut file = tempfile::NamedTempFile::new()?;
+        writeln!(file, "{}", message.trim())?;
+        let mut command = Command::new("git");
+        command.arg("commit-tree").arg(&tree).arg("-F").arg(file.path());
+        if let Some(parent) = &parent {
+            command.arg("-p").arg(parent);
+        }
+        command
+            .env("GIT_AUTHOR_NAME", author.next().unwrap_or_default())
+            .env("GIT_AUTHOR_EMAIL", author.next().unwrap_or_default())
+            .env("GIT_AUTHOR_DATE", author.next().unwrap_or_default());
+        let output = command.traced_output()?;
+        if !output.status.success() {
+            return Err(GitError::new(&command, &output).into());
+        }
+        parent = Some(String::from_utf8_lossy(&output.stdout).trim().to_string());
+    }
+
+    let new_head = parent.unwrap_or_else(|| last.sha.clone());
+    if rewritten {
+        git_output(&["update-ref", "-m", "commiTUI: reword", "HEAD", &new_head, &last.sha])?;
+    }
+    Ok(new_head)
+}
+
+pub fn staged_files() -> Result<Vec<StagedFile>, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--name-status"])
+        .traced_output()?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split('\t');
+            let status = parts.next()?.chars().next()?;
+            // Renames list the old path first; the new one is what matters
+            let path = parts.next_back()?.to_string();
+            Some(StagedFile { status, path })
+        })
+        .collect())
+}
+
+// A staged file with its line counts (None for binary files)
+#[derive(Debug, Clone, PartialEq)]
+pub struct StagedChange {
+    pub status: char, // A, M, D, T
+    pub path: String,
+    pub added: Option<usize>,
+    pub removed: Option<usize>,
+}
+
+// `--name-status` joined with `--numstat`; renames show as a delete and an add
+pub fn staged_changes() -> Result<Vec<StagedChange>, Box<dyn std::error::Error>> {
+    let statuses = git_output(&["diff", "--cached", "--no-renames", "--name-status"])?;
+    let numstat = git_output(&["diff", "--cached", "--no-renames", "--numstat"])?;
+    let counts: Vec<(&str, Option<usize>, Option<usize>)> = numstat
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            // Binary files count as "-"
+            let (added, removed) = (parts.next()?.parse().ok(), parts.next()?.parse().ok());
+            Some((parts.next()?, added, removed))
+        })
+        .collect();
+    Ok(statuses
+        .lines()
+        .filter_map(|line| {
+            let (status, path) = line.split_once('\t')?;
+            let (_, added, removed) = counts.iter().find(|(p, _, _)| *p == path).copied().unwrap_or((path, None, None));
+            Some(StagedChange { status: status.chars().next()?, path: path.to_string(), added, removed })
+        })
+        .collect())
+}
+
+// Changes in tracked files that aren't staged, in full (patches are built from it)
+pub fn unstaged_diff() -> Result<String, Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command.args(["diff", "--no-color", "--no-ext-diff"]);
+    let output = command.traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::new(&command, &output).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Untracked files (not ignored) as `git diff` would show them once added, so
+// picking hunks can stage them as new files
+pub fn untracked_diff() -> Result<String, Box<dyn std::error::Error>> {
+    let mut diff = String::new();
+    for path in git_output(&["ls-files", "--others", "--exclude-standard"])?.lines().filter(|path| !path.is_empty()) {
+        // Exits with 1 when there are differences, which there always are
+        let output = Command::new("git")
+            .args(["diff", "--no-color", "--no-ext-diff", "--no-index", "--", "/dev/null", path])
+            .traced_output()?;
+        diff.push_str(&String::from_utf8_lossy(&output.stdout));
+    }
+    Ok(diff)
+}
+
+// Size in bytes of the staged version of `path`
+pub fn staged_size(path: &str) -> Option<u64> {
+    git_output(&["cat-file", "-s", &format!(":{}", path)]).ok()?.parse().ok()
+}
+
+// Stage everything under `paths`: new, changed and deleted files alike. With
+// `dry_run` nothing is staged, but a path git doesn't know is still an error.
+pub fn stage_paths(paths: &[String], dry_run: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let flags: &[&str] = if dry_run { &["add", "--dry-run", "-A", "--"] } else { &["add", "-A", "--"] };
+    let args: Vec<&str> = flags.iter().copied().chain(paths.iter().map(String::as_str)).collect();
+    git_output(&args).map(|_| ())
+}
+
+// Stage a patch (a subset of `unstaged_diff`) without touching the working tree
+pub fn apply_cached(patch: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut file = tempfile::NamedTempFile::new()?;
+    write!(file, "{}", patch)?;
+    let path = file.path().to_string_lossy().into_owned();
+    git_output(&["apply", "--cached", &path]).map(|_| ())
+}
+
+// The staged changes with git's usual context lines, for a reader (the summarizer)
+pub fn staged_patch() -> Result<String, Box<dyn std::error::Error>> {
+    let mut command = Command::new("git");
+    command.args(["diff", "--cached", "--no-color", "--no-ext-diff"]);
+    let output = command.traced_output()?;
+    if !output.status.success() {
+        return Err(GitError::new(&command, &output).into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+pub fn staged_diff() -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(["diff", "--cached", "--no-color", "--unified=0"])
+        .traced_output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Most recent tag reachable from HEAD
+pub fn latest_tag() -> Option<String> {
+    git_output(&["describe", "--tags", "--abbrev=0"]).ok().filter(|t| !t.is_empty())
+}
+
+// Annotated tag on HEAD
+pub fn create_tag(name: &str, message: &str) -> Result<(), Box<dyn std::error::Error>> {
+    git_output(&["tag", "-a", name, "-m", message]).map(|_| ())
+}
+
+// Push a single tag to the current branch's remote (origin if it has none)
+pub fn push_tag(name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let remote = current_branch()
+        .and_then(|branch| git_output(&["config", "--get", &format!("branch.{}.remote", branch)]).ok())
+        .unwrap_or_else(|| "origin".to_string());
+    git_output(&["push", &remote, &format!("refs/tags/{}", name)]).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(repo: &Path, args: &[&str]) -> String {
+        let mut all = vec!["-c", "user.name=Test", "-c", "user.email=test@example.com"];
+        all.extend(args);
+        git_output_in(repo, &all).unwrap()
+    }
+
+    #[test]
+    fn author_and_date_overrides_reach_git_commit() {
+        let args = |options: &CommitOptions| {
+            let mut command = Command::new("git");
+            options.commit_flags(&mut command);
+            command.get_args().map(|arg| arg.to_string_lossy().into_owned()).collect::<Vec<_>>()
+        };
+        let mut options = CommitOptions {
+            author: Some("Ada <ada@example.com> ".into()),
+            date: Some("2024-05-01T12:00".into()),
+            ..CommitOptions::default()
+        };
+        let given = args(&options);
+        assert!(given.contains(&"--author=Ada <ada@example.com>".to_string()), "{:?}", given);
+        assert!(given.contains(&"--date=2024-05-01T12:00".to_string()), "{:?}", given);
+
+        // Cleared on the preview: git's own author and date again
+        options.override_field(0).clear();
+        options.override_field(1).clear();
+        assert!(!args(&options).iter().any(|arg| arg.starts_with("--author") || arg.starts_with("--date")));
+    }
+
+    #[test]
+    fn stashed_changes_come_back_next_to_staged_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "-q"]);
+        std::fs::write(repo.join("f"), "1\n2\n3\n").unwrap();
+        git(repo, &["add", "f"]);
+        git(repo, &["commit", "-q", "-m", "base"]);
+        // A and B staged, the C right below them not
+        std::fs::write(repo.join("f"), "A\nB\n3\n").unwrap();
+        git(repo, &["add", "f"]);
+        std::fs::write(repo.join("f"), "A\nB\nC\n").unwrap();
+
+        let stash = stash_unstaged(repo).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(repo.join("f")).unwrap(), "A\nB\n3\n");
+        git(repo, &["commit", "-q", "-m", "staged half"]);
+        restore_stashed(repo, &stash).unwrap();
+
+        assert_eq!(std::fs::read_to_string(repo.join("f")).unwrap(), "A\nB\nC\n");
+        assert_eq!(git(repo, &["status", "--porcelain"]), "M f");
+        assert!(git_output_in(repo, &["rev-parse", "-q", "--verify", "refs/stash"]).is_err());
+        assert_eq!(git(repo, &["show", "HEAD:f"]), "A\nB\n3");
+    }
+
+    #[test]
+    fn stashed_changes_come_back_when_committing_from_a_subdirectory() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        let sub = repo.join("sub");
+        git(repo, &["init", "-q"]);
+        std::fs::create_dir(&sub).unwrap();
+        std::fs::write(repo.join("top"), "1\n").unwrap();
+        std::fs::write(sub.join("f"), "1\n").unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "-q", "-m", "base"]);
+        std::fs::write(sub.join("f"), "2\n").unwrap();
+        git(repo, &["add", "sub/f"]);
+        // Unstaged, and outside the directory the commit is made from
+        std::fs::write(repo.join("top"), "changed\n").unwrap();
+
+        let stash = stash_unstaged(&sub).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(repo.join("top")).unwrap(), "1\n");
+        git(&sub, &["commit", "-q", "-m", "sub only"]);
+        restore_stashed(&sub, &stash).unwrap();
+
+        assert_eq!(std::fs::read_to_string(repo.join("top")).unwrap(), "changed\n");
+        assert_eq!(git(repo, &["status", "--porcelain"]), "M top");
+        assert!(git_output_in(repo, &["rev-parse", "-q", "--verify", "refs/stash"]).is_err());
+    }
+}
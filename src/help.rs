@@ -0,0 +1,110 @@
+use commitui::config::documented_keys;
+use commitui::i18n::{t, tf};
+use commitui::state::Step;
+use crate::app::{step_title, GLOBAL_KEYS, STEPS};
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    widgets::{Block, Borders, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// The titles each step shows, which are where its keys are listed
+fn step_hints(step: &Step) -> &'static [&'static str] {
+    match step {
+        Step::Type => &["titles.type"],
+        Step::Scope => &["titles.scope_hints", "titles.custom_scope"],
+        Step::Subject => &["titles.subject", "titles.subject_editing", "titles.suggestions"],
+        Step::Body => &["titles.body", "titles.body_editing"],
+        Step::Breaking => &["titles.breaking_question", "titles.breaking"],
+        Step::Preview => &["titles.preview", "titles.issues", "titles.smart_commit", "titles.overrides"],
+    }
+}
+
+// Built from the same strings and tables the wizard uses, so it describes
+// this build (and this language) rather than a copy that can go stale
+pub fn help_lines() -> Vec<Line<'static>> {
+    let heading = |key: &'static str| Line::from(Span::styled(t(key), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    let skip = t("text.skip_hint");
+    let mut lines = vec![heading("help.steps")];
+    for (i, step) in STEPS.iter().enumerate() {
+        lines.push(Line::from(Span::styled(format!("{}. {}", i + 1, step_title(step)), Style::default().add_modifier(Modifier::BOLD))));
+        for key in step_hints(step) {
+            lines.push(Line::from(format!("   {}", tf(key, &[("skip", skip)]))));
+        }
+    }
+    let options = tf("text.commit_options", &[("gpg", t("text.off")), ("signoff", t("text.off"))]);
+    lines.push(Line::from(format!("   {}{}", options, tf("text.hooks_option", &[("hooks", t("text.on"))]))));
+
+    lines.push(Line::from(""));
+    lines.push(heading("help.global"));
+    let width = GLOBAL_KEYS.iter().map(|(keys, _)| keys.len()).max().unwrap_or(0);
+    for (keys, description) in GLOBAL_KEYS {
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {:width$}  ", keys, width = width), Style::default().fg(Color::Cyan)),
+            Span::raw(t(description)),
+        ]));
+    }
+
+    lines.push(Line::from(""));
+    lines.push(heading("help.config"));
+    for (key, description) in documented_keys() {
+        let description = if description.is_empty() { t("help.undocumented").to_string() } else { description };
+        lines.push(Line::from(vec![
+            Span::styled(format!("   {}", key), Style::default().fg(Color::Cyan)),
+            Span::styled(format!(": {}", description), Style::default().fg(Color::DarkGray)),
+        ]));
+    }
+    lines
+}
+
+// `commiTUI help`
+pub fn show_help() -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = setup_terminal()?;
+    let result = help_screen(&mut terminal, &mut CrosstermEvents);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+pub fn help_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let lines = help_lines();
+    let mut scroll: u16 = 0;
+    let mut page: u16 = 1;
+
+    loop {
+        terminal.draw(|f| {
+            let area = f.size();
+            page = area.height.saturating_sub(2).max(1);
+            // Wrapped lines can make the text taller than `lines`, so this only bounds it loosely
+            scroll = scroll.min(lines.len().saturating_sub(1) as u16);
+            let help = Paragraph::new(lines.clone())
+                .block(Block::default().title(t("help.title")).borders(Borders::ALL))
+                .wrap(Wrap { trim: false })
+                .scroll((scroll, 0));
+            f.render_widget(help, area);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                KeyCode::Down | KeyCode::Char('j') => scroll = scroll.saturating_add(1),
+                KeyCode::Up | KeyCode::Char('k') => scroll = scroll.saturating_sub(1),
+                KeyCode::PageDown | KeyCode::Char(' ') => scroll = scroll.saturating_add(page),
+                KeyCode::PageUp => scroll = scroll.saturating_sub(page),
+                KeyCode::Home => scroll = 0,
+                _ => {}
+            }
+        }
+    }
+}
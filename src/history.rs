@@ -0,0 +1,93 @@
+use commitui::git::LogEntry;
+use commitui::i18n::t;
+use commitui::parser::{parse_message, ParsedCommit};
+use commitui::state::Prefill;
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Style, Color},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+pub const HISTORY_LIMIT: usize = 50;
+
+// Standalone browser for `commiTUI log`
+pub fn browse_history(entries: &[LogEntry]) -> Result<Option<Prefill>, Box<dyn std::error::Error>> {
+    let mut terminal = setup_terminal()?;
+    let result = history_screen(&mut terminal, &mut CrosstermEvents, entries);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+// List recent commits with their parsed parts; returns None if the user backs out.
+pub fn history_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    entries: &[LogEntry],
+) -> Result<Option<Prefill>, Box<dyn std::error::Error>> {
+    let parsed: Vec<ParsedCommit> = entries.iter().map(|e| parse_message(&e.message)).collect();
+    let mut selected: usize = 0;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let items: Vec<ListItem> = entries
+                .iter()
+                .zip(&parsed)
+                .map(|(entry, commit)| {
+                    let line = format!(
+                        "{:<8} {:<10} {:<12} {}",
+                        &entry.sha[..entry.sha.len().min(7)],
+                        commit.commit_type.as_deref().unwrap_or("-"),
+                        commit.scope.as_deref().unwrap_or("-"),
+                        commit.subject
+                    );
+                    // Non-conventional commits are listed but dimmed
+                    if commit.commit_type.is_some() {
+                        ListItem::new(line)
+                    } else {
+                        ListItem::new(line).style(Style::default().fg(Color::DarkGray))
+                    }
+                })
+                .collect();
+            let title = if entries.is_empty() { t("titles.recent_commits_empty") } else { t("titles.recent_commits") };
+            let mut list_state = ListState::default();
+            list_state.select(Some(selected));
+            let list = List::new(items)
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .highlight_style(Style::default().bg(Color::Blue))
+                .highlight_symbol(">> ");
+            f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+            let hints = Paragraph::new(t("hints.history"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[1]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Down => selected = (selected + 1).min(entries.len().saturating_sub(1)),
+                KeyCode::Up => selected = selected.saturating_sub(1),
+                KeyCode::Enter if !entries.is_empty() => return Ok(Some(parsed[selected].to_prefill(true))),
+                KeyCode::Char('t') if !entries.is_empty() => return Ok(Some(parsed[selected].to_prefill(false))),
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// The unstaged changes as files and hunks, so some of them can be staged with
+// `git apply --cached`, the way `git add -p` does it.
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    pub path: String,
+    pub header: String, // `diff --git` through `+++`, repeated in front of every patch
+    pub hunks: Vec<Hunk>,
+    pub new_file: bool, // Untracked until staged: the whole file is one hunk
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hunk {
+    pub text: String, // The `@@ -a,b +c,d @@` line and everything up to the next one
+    pub added: usize,
+    pub removed: usize,
+}
+
+impl Hunk {
+    pub fn range(&self) -> &str {
+        self.text.lines().next().unwrap_or("")
+    }
+}
+
+// Split `git diff` output. Files without hunks (binary, mode-only) are left
+// out, since there's nothing in them to pick from.
+pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = Vec::new();
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            let path = paths.split_once(" b/").map_or(paths, |(_, b)| b);
+            files.push(FileDiff { path: path.to_string(), header: format!("{}\n", line), hunks: Vec::new(), new_file: false });
+            continue;
+        }
+        let Some(file) = files.last_mut() else { continue };
+        if line.starts_with("@@") {
+            file.hunks.push(Hunk { text: String::new(), added: 0, removed: 0 });
+        }
+        match file.hunks.last_mut() {
+            Some(hunk) => {
+                if line.starts_with('+') {
+                    hunk.added += 1;
+                } else if line.starts_with('-') {
+                    hunk.removed += 1;
+                }
+                hunk.text.push_str(line);
+                hunk.text.push('\n');
+            }
+            None => {
+                file.new_file |= line.starts_with("new file mode");
+                file.header.push_str(line);
+                file.header.push('\n');
+            }
+        }
+    }
+    files.retain(|file| !file.hunks.is_empty());
+    files
+}
+
+// A patch with only the chosen hunks; `selected[f][h]` is hunk h of file f.
+// Later hunks keep their original line numbers, `git apply` finds them anyway.
+pub fn build_patch(files: &[FileDiff], selected: &[Vec<bool>]) -> String {
+    let mut patch = String::new();
+    for (file, chosen) in files.iter().zip(selected) {
+        if !chosen.contains(&true) {
+            continue;
+        }
+        patch.push_str(&file.header);
+        for (hunk, _) in file.hunks.iter().zip(chosen).filter(|(_, chosen)| **chosen) {
+            patch.push_str(&hunk.text);
+        }
+    }
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/app.rs b/src/app.rs
+index 1111111..2222222 100644
+--- a/src/app.rs
++++ b/src/app.rs
+@@ -1,3 +1,3 @@
+ use std::fs;
+-use std::io;
++use std::io::{self, Write};
+ 
+@@ -40,2 +40,3 @@ fn run() {
+     start();
++    finish();
+ }
+diff --git a/notes.txt b/notes.txt
+index 3333333..4444444 100644
+--- a/notes.txt
++++ b/notes.txt
+@@ -1 +1 @@
+-old last line
+\\ No newline at end of file
++new last line
+\\ No newline at end of file
+diff --git a/old_name.rs b/new_name.rs
+similarity 90%
+rename from old_name.rs
+rename to new_name.rs
+index 5555555..6666666 100644
+--- a/old_name.rs
++++ b/new_name.rs
+@@ -2 +2 @@
+-fn old() {}
++fn new() {}
+diff --git a/logo.png b/logo.png
+index 7777777..8888888 100644
+Binary files a/logo.png and b/logo.png differ
+diff --git a/todo.md b/todo.md
+new file mode 100644
+index 0000000..9999999
+--- /dev/null
++++ b/todo.md
+@@ -0,0 +1,2 @@
++- write tests
++- ship
+";
+
+    #[test]
+    fn files_split_into_hunks_with_their_counts() {
+        let files = parse_diff(DIFF);
+        let paths: Vec<&str> = files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, ["src/app.rs", "notes.txt", "new_name.rs", "todo.md"]); // The binary file has no hunks
+
+        let app = &files[0];
+        assert_eq!(app.hunks.len(), 2);
+        assert_eq!(app.hunks[1].range(), "@@ -40,2 +40,3 @@ fn run() {");
+        assert_eq!((app.hunks[0].added, app.hunks[0].removed), (1, 1));
+        assert_eq!((app.hunks[1].added, app.hunks[1].removed), (1, 0));
+        assert!(app.header.ends_with("+++ b/src/app.rs\n"));
+
+        // The marker belongs to the hunk and isn't counted as a change
+        let notes = &files[1].hunks[0];
+        assert_eq!((notes.added, notes.removed), (1, 1));
+        assert_eq!(notes.text.matches("\\ No newline at end of file").count(), 2);
+
+        assert!(files[2].header.contains("rename from old_name.rs\nrename to new_name.rs\n"));
+        assert!(!files[2].new_file);
+        assert!(files[3].new_file);
+    }
+
+    #[test]
+    fn a_patch_keeps_only_the_chosen_hunks_under_their_headers() {
+        let files = parse_diff(DIFF);
+        let mut selected: Vec<Vec<bool>> = files.iter().map(|file| vec![false; file.hunks.len()]).collect();
+        assert_eq!(build_patch(&files, &selected), "");
+
+        selected[0][1] = true;
+        selected[1][0] = true;
+        selected[2][0] = true;
+        let patch = build_patch(&files, &selected);
+        assert!(patch.starts_with(&files[0].header));
+        assert!(!patch.contains("use std::io::{self, Write};"));
+        assert!(patch.contains("+    finish();\n"));
+        assert!(patch.contains("+new last line\n\\ No newline at end of file\n"));
+        assert!(patch.contains("rename to new_name.rs\n"));
+        assert!(!patch.contains("todo.md"));
+    }
+}
@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+const ENGLISH: &str = include_str!("../locales/en.toml");
+
+// Every user-facing string by dotted key ("validation.subject_empty")
+static CATALOG: OnceLock<HashMap<String, &'static str>> = OnceLock::new();
+
+// Load `language` over the English defaults, from
+// <config dir>/commiTUI/locales/<language>.toml. Missing keys stay English.
+// Only the first call has any effect; without one, English is used.
+pub fn init(language: &str) {
+    let mut catalog = english();
+    if language != "en" {
+        match load_translation(language) {
+            Ok(translation) => catalog.extend(translation),
+            Err(e) => eprintln!("Warning: {}", e),
+        }
+    }
+    let _ = CATALOG.set(catalog);
+}
+
+fn english() -> HashMap<String, &'static str> {
+    let table: toml::Table = toml::from_str(ENGLISH).expect("built-in English strings are valid");
+    flatten(table)
+}
+
+fn load_translation(language: &str) -> Result<HashMap<String, &'static str>, Box<dyn std::error::Error>> {
+    let mut path = dirs::config_dir().ok_or("No config directory for translations")?;
+    path.push("commiTUI");
+    path.push("locales");
+    path.push(format!("{}.toml", language));
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Could not read translation {}: {}", path.display(), e))?;
+    let table: toml::Table = toml::from_str(&content)
+        .map_err(|e| format!("Could not parse translation {}: {}", path.display(), e))?;
+    Ok(flatten(table))
+}
+
+// `[validation] subject_empty = "..."` becomes "validation.subject_empty".
+// Strings live for the whole run, so they're leaked once instead of cloned per frame.
+fn flatten(table: toml::Table) -> HashMap<String, &'static str> {
+    let mut strings = HashMap::new();
+    for (section, value) in table {
+        match value {
+            toml::Value::Table(entries) => {
+                for (key, text) in flatten(entries) {
+                    strings.insert(format!("{}.{}", section, key), text);
+                }
+            }
+            toml::Value::String(text) => {
+                strings.insert(section, &*Box::leak(text.into_boxed_str()));
+            }
+            _ => {}
+        }
+    }
+    strings
+}
+
+// The string for `key`; the key itself if no catalog has it
+pub fn t(key: &'static str) -> &'static str {
+    CATALOG.get_or_init(english).get(key).copied().unwrap_or(key)
+}
+
+// `t` with `{name}` placeholders filled in
+pub fn tf(key: &'static str, args: &[(&str, &str)]) -> String {
+    let mut text = t(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
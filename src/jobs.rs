@@ -0,0 +1,63 @@
+use commitui::ai::AiSuggestion;
+use commitui::git::{Identity, LogEntry, StagedChange};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::Instant;
+
+const SPINNER: [char; 10] = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
+// What a background job hands back to the wizard
+pub enum JobResult {
+    Suggestions(Vec<String>),
+    Summary(Result<AiSuggestion, String>),
+    History(Vec<LogEntry>),
+    Repo(Box<RepoFacts>),
+    // `base_branch..HEAD` commits breaking the rules, counted again after a reword
+    Violations(usize),
+}
+
+// What the preview shows about the repository, read when the wizard opens
+pub struct RepoFacts {
+    pub published: Option<String>,
+    pub identity: Option<Identity>,
+    pub staged: Vec<StagedChange>,
+    pub unstaged: bool,
+    pub branch_violations: usize,
+}
+
+pub enum JobStatus {
+    Running,
+    Done(JobResult),
+    Failed,
+}
+
+// Slow git/summarizer work running on a worker thread, so the UI keeps
+// drawing (and can show a spinner) on big repositories
+pub struct Job {
+    pub label: &'static str,
+    started: Instant,
+    rx: Receiver<JobResult>,
+}
+
+impl Job {
+    pub fn spawn(label: &'static str, work: impl FnOnce() -> JobResult + Send + 'static) -> Self {
+        let (tx, rx) = mpsc::channel();
+        thread::spawn(move || {
+            // The wizard may have cancelled the job and dropped the receiver
+            let _ = tx.send(work());
+        });
+        Job { label, started: Instant::now(), rx }
+    }
+
+    pub fn status(&self) -> JobStatus {
+        match self.rx.try_recv() {
+            Ok(result) => JobStatus::Done(result),
+            Err(TryRecvError::Empty) => JobStatus::Running,
+            Err(TryRecvError::Disconnected) => JobStatus::Failed, // The worker panicked
+        }
+    }
+
+    pub fn spinner(&self) -> char {
+        SPINNER[(self.started.elapsed().as_millis() / 100) as usize % SPINNER.len()]
+    }
+}
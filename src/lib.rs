@@ -0,0 +1,34 @@
+//! Conventional-commit building, validation and parsing, without the TUI.
+//!
+//! The `commiTUI` binary is a thin frontend over these modules; other tools can
+//! use them to compose or check commit messages the same way.
+
+pub mod ai;
+pub mod answers;
+pub mod branch;
+pub mod clipboard;
+pub mod config;
+pub mod editor;
+pub mod git;
+pub mod hunks;
+pub mod i18n;
+pub mod message;
+pub mod packages;
+pub mod parser;
+pub mod path_rules;
+pub mod plan;
+pub mod pr;
+pub mod preflight;
+pub mod release;
+pub mod remembered;
+pub mod search;
+pub mod state;
+pub mod stats;
+pub mod suggest;
+pub mod trace;
+pub mod validation;
+
+pub use config::Config;
+pub use message::CommitMessage;
+pub use parser::{parse_message, ParsedCommit};
+pub use validation::validate_subject;
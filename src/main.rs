@@ -1,25 +1,740 @@
-mod config;
 mod tui;
-mod validation;
-mod state;
-mod git;
+mod app;
+mod steps;
+mod render;
+mod jobs;
+mod cli;
+mod templates;
+mod history;
+mod reword;
+mod review;
+mod dashboard;
+mod staging;
+mod squash;
+mod help;
+mod manage;
+mod fixup;
+mod finder;
 
-use config::Config;
-use tui::run_tui;
-use git::commit_with_message;
+use clap::Parser;
+use std::io::IsTerminal;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::Duration;
+use cli::{Cli, Command, ConfigCommand, PlanCommand};
+use commitui::config::{self, Config, ConfigProblem, Convention, ScopeGroup};
+use commitui::state::{AppState, Prefill, Step};
+use tui::{
+    run_tui, show_commit_failure, show_config_problems, show_content_warnings, show_empty_commit, show_git_error, show_not_a_repo,
+    show_protected_branch, show_repo_warnings, show_signing_failure, show_signing_problem, show_tag_prompt, FailureAction,
+};
+use commitui::git::{
+    abort_operation, comment_char, commit_message, commit_with_message, create_tag, current_branch, git_config, has_unstaged_changes,
+    identity, inside_work_tree, latest_tag, push_tag, range_commits, repo_root, rev_parse, stage_paths, staged_changes,
+    staged_files, CommitOptions, GitError,
+};
+use commitui::packages::{detect_packages, majority_package};
+use commitui::path_rules::infer_prefill;
+use commitui::preflight::{
+    check_repo, check_signing, check_staged_content, merge_message, protected_branch, RepoWarning,
+};
+use commitui::release::{is_release, suggested_tag};
+use commitui::parser::parse_message;
+use commitui::plan::{Plan, PlannedCommit};
+use commitui::message::{CommitMessage, JsonError, JsonOutput};
+use commitui::validation::validate_message;
+use commitui::editor::{read_edit_message, write_message, EditMessage};
+use commitui::branch::{apply_template, derive_defaults};
+use commitui::answers::Answers;
+use commitui::i18n::{t, tf};
+use commitui::trace;
+use commitui::{clipboard, pr, remembered};
+use templates::{prefill_for, start_template};
+
+// The git operation a template subcommand started (`revert`, `merge`), until committed
+static STARTED: Mutex<Option<&'static str>> = Mutex::new(None);
+
+fn started() -> MutexGuard<'static, Option<&'static str>> {
+    STARTED.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+// Leave with `code`, undoing a started revert or merge first (`git merge --abort`)
+// so quitting never leaves MERGE_HEAD and a staged merge behind
+fn exit(code: i32) -> ! {
+    if let Some(operation) = *started() {
+        if let Err(e) = abort_operation(operation) {
+            eprintln!("Warning: could not undo the {}: {}", operation, e);
+        }
+    }
+    std::process::exit(code);
+}
+
+// Quitting the wizard is a deliberate "no": nothing is committed and scripts can tell
+fn abort() -> ! {
+    eprintln!("Aborted.");
+    exit(1);
+}
+
+// Stage what a template subcommand commits, once the checks before it passed
+fn start_operation(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(operation) = cli.command.as_ref().map(start_template).transpose()?.flatten() {
+        *started() = Some(operation);
+    }
+    Ok(())
+}
+
+// Print/write the final message for message-only modes (--print-message-only, --output, --json)
+fn emit_message(cli: &Cli, config: &Config, message: &CommitMessage, aborted: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if cli.json {
+        let output = JsonOutput { message, aborted };
+        println!("{}", serde_json::to_string(&output)?);
+    }
+    if aborted {
+        abort();
+    }
+    let text = message.format_with(config);
+    if let Some(path) = &cli.output {
+        std::fs::write(path, &text)?;
+    }
+    if cli.copy {
+        clipboard::copy(&text)?;
+        eprintln!("Copied the message to the clipboard.");
+    }
+    // --copy alone keeps stdout clean
+    if !cli.json && (!cli.copy || cli.print_message_only) {
+        print!("{}", text);
+    }
+    Ok(())
+}
+
+// `commiTUI pr`: title, blank line, Markdown body
+fn suggest_pr(config: &Config, base: Option<&str>, copy: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let base = base.map(str::to_string).unwrap_or_else(|| config.base_branch.clone().unwrap_or_else(config::default_base_branch));
+    if rev_parse(&base).is_err() {
+        return Err(format!("Base branch '{}' not found; pass --base or set base_branch.", base).into());
+    }
+    let commits = range_commits(&format!("{}..HEAD", base))?;
+    if commits.is_empty() {
+        return Err(format!("No commits between {} and HEAD.", base).into());
+    }
+    let pr = pr::describe(&commits, config.types.as_deref().unwrap_or(&[]));
+    let text = format!("{}\n\n{}", pr.title, pr.body);
+    if copy {
+        clipboard::copy(&text)?;
+        eprintln!("Copied the pull request title and description to the clipboard.");
+    } else {
+        print!("{}", text);
+    }
+    Ok(())
+}
+
+// `commiTUI config show`: the merged config as TOML, each key commented with
+// the layer that set it. Problems are warnings here, not a screen.
+fn show_config(cli: &Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let (config, mut sources, problems) = Config::load_explained(cli.convention);
+    for problem in &problems {
+        eprintln!("Warning: {}", problem);
+    }
+    let mut table = toml::Table::try_from(&config)?;
+    // Flags that switch an option on whatever the config says
+    for (key, flag) in [("gpg_sign", cli.gpg_sign), ("signoff", cli.signoff), ("stash_unstaged", cli.stash_unstaged)] {
+        if flag {
+            table.insert(key.to_string(), toml::Value::Boolean(true));
+            sources.insert(key.to_string(), config::Source::CommandLine);
+        }
+    }
+    // Keys no file sets fall back to these
+    let git = git_config();
+    let identity = git.user_name.zip(git.user_email).map(|(name, email)| format!("{} <{}>", name, email));
+    let inherited = [
+        ("gpg_sign", "commit.gpgSign", git.gpg_sign.map(toml::Value::Boolean)),
+        ("verbose_commit", "commit.verbose", git.verbose.map(toml::Value::Boolean)),
+        ("hooks_path", "core.hooksPath", git.hooks_path.map(toml::Value::String)),
+        ("author", "user.name/user.email", identity.map(toml::Value::String)),
+    ];
+    for (key, git_key, value) in inherited {
+        if let Some(value) = value.filter(|_| !table.contains_key(key)) {
+            table.insert(key.to_string(), value);
+            sources.insert(key.to_string(), config::Source::Git(git_key));
+        }
+    }
+
+    for (key, _) in config::documented_keys() {
+        let source = sources.get(&key).map_or_else(|| "default".to_string(), |source| source.to_string());
+        match table.get(&key) {
+            Some(value) => println!("{} = {}  # {}", key, value, source),
+            None => println!("# {} is not set ({})", key, source),
+        }
+    }
+    Ok(())
+}
+
+// CLI flags can only switch options on; config decides the default, and where
+// it's silent the user's git config does
+fn commit_options(cli: &Cli, config: &Config) -> CommitOptions {
+    let git = git_config();
+    // Config args first so anything after `--` on the command line comes last
+    let mut extra_args: Vec<String> = config.git_args.clone().unwrap_or_else(config::default_git_args)
+        .into_iter()
+        .chain(cli.git_args.iter().cloned())
+        .collect();
+    // `-- --no-verify` skips the hooks as well, and is recorded the same way
+    let no_verify = cli.no_verify || extra_args.iter().any(|arg| arg == "--no-verify" || arg == "-n");
+    extra_args.retain(|arg| arg != "--no-verify" && arg != "-n");
+    let allow_empty = cli.allow_empty || extra_args.iter().any(|arg| arg == "--allow-empty");
+    extra_args.retain(|arg| arg != "--allow-empty");
+    CommitOptions {
+        gpg_sign: cli.gpg_sign || config.gpg_sign.or(git.gpg_sign).unwrap_or_else(config::default_gpg_sign),
+        signoff: cli.signoff || config.signoff.unwrap_or_else(config::default_signoff),
+        verbose: config.verbose_commit.or(git.verbose).unwrap_or_else(config::default_verbose_commit),
+        author: cli.author.clone().or_else(|| config.author.clone()),
+        date: cli.date.clone(),
+        stash_unstaged: cli.stash_unstaged || config.stash_unstaged.unwrap_or_else(config::default_stash_unstaged),
+        hooks_path: config.hooks_path.clone(),
+        no_verify,
+        bypass_trailer: Some(config.hooks_bypassed_footer.clone().unwrap_or_else(config::default_hooks_bypassed_footer))
+            .filter(|footer| !footer.trim().is_empty()),
+        allow_empty,
+        extra_args,
+    }
+}
+
+// Offer monorepo workspace members as a "Packages" scope group. Returns the
+// package holding most of the staged changes, to pre-select it.
+fn add_package_scopes(config: &mut Config) -> Option<String> {
+    let root = repo_root()?;
+    let globs = config.package_globs.clone().unwrap_or_else(config::default_package_globs);
+    let packages = detect_packages(&root, &globs);
+    if packages.is_empty() {
+        return None;
+    }
+    let files = staged_files().unwrap_or_default();
+    let majority = majority_package(&packages, &files).map(|p| p.name.clone());
+    let group = ScopeGroup {
+        name: "Packages".into(),
+        scopes: packages.into_iter().map(|p| p.name).collect(),
+    };
+    config.scope_groups.get_or_insert_with(Vec::new).insert(0, group);
+    majority
+}
+
+// Custom scopes kept with Ctrl+K, as a group after the configured ones
+fn add_remembered_scopes(config: &mut Config) {
+    let scopes: Vec<String> = remembered::load().into_iter().filter(|scope| !config.is_listed_scope(scope)).collect();
+    if !scopes.is_empty() {
+        config.scope_groups.get_or_insert_with(Vec::new).push(ScopeGroup { name: "Remembered".into(), scopes });
+    }
+}
+
+// `--convention`, kept so a reload from the wizard still honours it
+static CONVENTION: OnceLock<Option<Convention>> = OnceLock::new();
+
+// The config for this run: files layered over the convention preset, plus the
+// workspace packages and remembered scopes when enabled. Returns the package to
+// pre-select as well.
+// Ctrl+R in the wizard loads it again through here.
+pub fn load_config() -> (Config, Vec<ConfigProblem>, Option<String>) {
+    let (mut config, problems) = Config::load_checked(CONVENTION.get().copied().flatten());
+    let package_scope = if config.package_scopes.unwrap_or_else(config::default_package_scopes) {
+        add_package_scopes(&mut config)
+    } else {
+        None
+    };
+    if config.remember_scopes.unwrap_or_else(config::default_remember_scopes) {
+        add_remembered_scopes(&mut config);
+    }
+    (config, problems, package_scope)
+}
+
+// After a release commit: create an annotated tag and optionally push it.
+// The commit is already made, so failures here are only reported.
+fn offer_tag(message: &CommitMessage) -> Result<(), Box<dyn std::error::Error>> {
+    let suggested = suggested_tag(message, latest_tag().as_deref());
+    let Some((name, push)) = show_tag_prompt(&suggested)? else { return Ok(()) };
+    if let Err(e) = create_tag(&name, &message.header()) {
+        eprintln!("Warning: could not create tag {}: {}", name, e);
+        return Ok(());
+    }
+    println!("Tagged {}.", name);
+    if push {
+        match push_tag(&name) {
+            Ok(()) => println!("Pushed {}.", name),
+            Err(e) => eprintln!("Warning: could not push tag {}: {}", name, e),
+        }
+    }
+    Ok(())
+}
+
+// Keep a commit's issue references for the next commit on the branch
+fn remember_issues(branch: Option<&str>, issues: &str) {
+    if let Some(branch) = branch {
+        if let Err(e) = remembered::remember_issues(branch, issues) {
+            eprintln!("Warning: could not remember the issue references: {}", e);
+        }
+    }
+}
+
+// The staged content checks, with their warning screen; whether to go on
+fn content_ok(config: &Config) -> Result<bool, Box<dyn std::error::Error>> {
+    let warnings = check_staged_content(config);
+    Ok(warnings.is_empty() || show_content_warnings(&warnings)?)
+}
+
+// `protected_branches`: refused outright with protected_branches_block, else
+// only after an explicit `c` (headless runs just warn; there's nobody to ask).
+// Whether to go on.
+fn protected_branch_ok(config: &Config, branch: Option<&str>, interactive: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    let Some(branch) = branch else { return Ok(true) };
+    let Some(pattern) = protected_branch(config, branch) else { return Ok(true) };
+    let args = [("branch", branch), ("pattern", pattern.as_str())];
+    if config.protected_branches_block.unwrap_or_else(config::default_protected_branches_block) {
+        eprintln!("error: {}", tf("preflight.protected_branch_blocked", &args));
+        return Ok(false);
+    }
+    if !interactive {
+        eprintln!("Warning: {}", tf("preflight.protected_branch", &args));
+        return Ok(true);
+    }
+    show_protected_branch(branch, &pattern)
+}
+
+// With nothing staged git would only refuse the commit after the whole wizard:
+// --allow-empty (or `c` on the screen) makes an empty one on purpose, unless
+// allow_empty_commits forbids it. Concluding a merge needs nothing staged.
+fn empty_commit_ok(config: &Config, options: &mut CommitOptions, interactive: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    if options.brings_changes() || !staged_changes()?.is_empty() || check_repo().contains(&RepoWarning::Merge) {
+        return Ok(true);
+    }
+    if !config.allow_empty_commits.unwrap_or_else(config::default_allow_empty_commits) {
+        eprintln!("error: {}", t("preflight.empty_commit_forbidden"));
+        exit(1);
+    }
+    if options.allow_empty {
+        return Ok(true);
+    }
+    if !interactive {
+        eprintln!("error: {}", t("preflight.nothing_staged_headless"));
+        exit(1);
+    }
+    options.allow_empty = show_empty_commit()?;
+    Ok(options.allow_empty)
+}
+
+// --queue: pick the hunks for the next commit. False when the session is over
+// (nothing left, nothing picked, or the picker was quit).
+fn next_in_queue(cli: &Cli, config: &Config) -> Result<bool, Box<dyn std::error::Error>> {
+    if !has_unstaged_changes() || !staging::stage_hunks()? || staged_changes()?.is_empty() {
+        return Ok(false);
+    }
+    Ok(cli.no_content_checks || content_ok(config)?)
+}
+
+// `plan apply` under way: the planned commits not made yet, numbered from 0
+struct PlanRun {
+    commits: std::iter::Enumerate<std::vec::IntoIter<PlannedCommit>>,
+    total: usize,
+    // The one the wizard is on, numbered from 1
+    current: usize,
+}
+
+// `plan apply` stages each commit itself, so it can't start from anything already
+// staged, and it always asks before committing
+fn start_plan(cli: &Cli, path: &Path) -> Result<PlanRun, Box<dyn std::error::Error>> {
+    let message_flags = cli.print_message_only || cli.output.is_some() || cli.json || cli.copy;
+    if cli.non_interactive || cli.patch || cli.queue || cli.as_editor.is_some() || message_flags {
+        return Err("`plan apply` stages and commits each planned commit itself; it can't be combined with \
+                    --non-interactive, --patch, --queue or the message-only flags.".into());
+    }
+    if !staged_changes()?.is_empty() {
+        return Err("Unstage the staged changes first: `plan apply` stages each planned commit's paths itself.".into());
+    }
+    let plan = Plan::load(path)?;
+    // A mistyped path stops the plan before its first commit, not halfway through
+    for (index, planned) in plan.commits.iter().enumerate() {
+        stage_paths(&planned.paths, true).map_err(|e| format!("{}: commit {}: {}", path.display(), index + 1, e))?;
+    }
+    Ok(PlanRun { total: plan.commits.len(), commits: plan.commits.into_iter().enumerate(), current: 0 })
+}
+
+// Stage the next planned commit and return its answers, or None once the plan is
+// done. One whose paths have nothing to stage is skipped with a warning.
+fn next_in_plan(plan: &mut PlanRun, cli: &Cli, config: &Config) -> Result<Option<Prefill>, Box<dyn std::error::Error>> {
+    for (index, planned) in plan.commits.by_ref() {
+        plan.current = index + 1;
+        stage_paths(&planned.paths, false)?;
+        if staged_changes()?.is_empty() {
+            eprintln!(
+                "Skipping planned commit {} of {}: nothing to stage in {}.",
+                plan.current, plan.total, planned.paths.join(", "),
+            );
+            continue;
+        }
+        if !cli.no_content_checks && !content_ok(config)? {
+            abort();
+        }
+        return Ok(Some(planned.answers.to_prefill()));
+    }
+    Ok(None)
+}
+
+// A failed git command keeps everything it printed: a screen to read it on,
+// JSON for --json, or all of it on stderr
+fn main() {
+    let cli = Cli::parse();
+    let (json, interactive) = (cli.json, !cli.non_interactive && std::io::stdout().is_terminal());
+    let Err(error) = run(cli) else { return };
+    match error.downcast_ref::<GitError>() {
+        Some(git) if json => {
+            println!("{}", serde_json::to_string(&JsonError { error: git, aborted: true }).unwrap_or_default());
+        }
+        Some(git) => {
+            if interactive {
+                let _ = show_git_error(git);
+            }
+            eprintln!("error: {} failed (exit status {})", git.command, git.status.map_or("-".to_string(), |code| code.to_string()));
+            eprintln!("{}", git.details());
+        }
+        None => eprintln!("Error: {:?}", error),
+    }
+    exit(1);
+}
+
+fn run(mut cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    if cli.verbose || trace::requested_by_env() {
+        match trace::default_path() {
+            Some(path) => match trace::init(&path) {
+                Ok(()) => eprintln!("Writing a debug trace to {}", path.display()),
+                Err(e) => eprintln!("Warning: could not open {}: {}", path.display(), e),
+            },
+            None => eprintln!("Warning: no cache directory for the debug trace"),
+        }
+    }
+
+    if let Some(Command::Config { action: ConfigCommand::Show }) = &cli.command {
+        return show_config(&cli);
+    }
 
-fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load config (from file or use default)
-    let config = Config::load().unwrap_or_else(|e| {
-        eprintln!("Warning: {}", e);
-        Config::default()
-    });
+    let _ = CONVENTION.set(cli.convention);
+    let (mut config, problems, mut package_scope) = load_config();
+    commitui::i18n::init(&config.language.clone().unwrap_or_else(config::default_language));
+    tui::init_display(&config);
+    if !problems.is_empty() {
+        // Headless runs can't show a screen; there's nobody to ask either
+        if cli.non_interactive {
+            for problem in &problems {
+                eprintln!("Warning: {}", problem);
+            }
+        } else if !show_config_problems(&problems)? {
+            abort();
+        }
+    }
+    // Outside a repository only the message can be written: say so now rather
+    // than after the wizard, and print it instead of committing
+    let message_flags = cli.print_message_only || cli.output.is_some() || cli.json || cli.copy;
+    if cli.as_editor.is_none() && !message_flags && !inside_work_tree() {
+        let not_a_repo = "Not inside a git repository";
+        match &cli.command {
+            Some(command) if command.needs_repo() => return Err(format!("{}.", not_a_repo).into()),
+            Some(_) => {}
+            None if cli.patch || cli.queue => return Err(format!("{}: there are no hunks to pick.", not_a_repo).into()),
+            None if cli.non_interactive => {
+                return Err(format!("{}; pass --print-message-only or --copy to only write the message.", not_a_repo).into());
+            }
+            None if !show_not_a_repo()? => abort(),
+            None => cli.print_message_only = true,
+        }
+    }
+    let mut plan = match &cli.command {
+        Some(Command::Plan { action: PlanCommand::Apply { path } }) => Some(start_plan(&cli, path)?),
+        _ => None,
+    };
+    // Hunks first: the staged files decide the pre-selected package
+    if (cli.patch || cli.queue) && !cli.non_interactive {
+        if !staging::stage_hunks()? {
+            abort();
+        }
+        let (reloaded, _, scope) = load_config();
+        config = reloaded;
+        package_scope = scope;
+    }
+    // Rewording, fixups and stats work on existing commits and need none of the pre-filling below
+    match &cli.command {
+        Some(Command::Reword { range }) => return reword::reword_range(&mut config, range),
+        Some(Command::Pr { base, copy }) => return suggest_pr(&config, base.as_deref(), *copy),
+        Some(Command::Squash { base, copy }) => {
+            let base = base.clone().unwrap_or_else(|| config.base_branch.clone().unwrap_or_else(config::default_base_branch));
+            return squash::squash(&base, *copy);
+        }
+        Some(Command::Stats { range }) => return dashboard::show_stats(range.as_deref()),
+        Some(Command::Show { rev }) => return review::review_commit(&config, rev),
+        Some(Command::Search { query, limit }) => return finder::search_history(query.as_deref(), *limit),
+        Some(Command::Fixup { rebase }) => return fixup::fixup(*rebase, &commit_options(&cli, &config)),
+        Some(Command::Help) => return help::show_help(),
+        _ => {}
+    }
+
+    let mut commit_options = commit_options(&cli, &config);
+
+    // Pre-fill what we can from the branch name (e.g. feat/api-rate-limiting)
+    // (a scope there beats `scope_rules`, which beat the package guess; an
+    // issue there beats the one last used on it)
+    let branch = current_branch();
+    let branch_defaults = branch.as_deref()
+        .map(|branch| derive_defaults(branch, &config.branch_rules()))
+        .unwrap_or_default()
+        .or(infer_prefill(&config, &staged_files().unwrap_or_default()))
+        .or(Prefill {
+            scope: package_scope,
+            issue: branch.as_deref().and_then(remembered::issues_for),
+            ..Prefill::default()
+        });
+    let templates = config.branch_templates.clone().unwrap_or_else(config::default_branch_templates);
+    let branch_defaults = match branch.as_deref() {
+        Some(branch) => apply_template(branch, &templates, branch_defaults),
+        None => branch_defaults,
+    };
+    // Later commits of a --queue session start from the branch name alone
+    let mut queue_defaults = branch_defaults.clone();
+    // Template subcommands (revert, merge, release, log) take precedence over the branch name
+    let mut prefill = match &cli.command {
+        Some(command) => prefill_for(command, &config)?.or(branch_defaults),
+        None => branch_defaults,
+    };
+    // The merge type can only be pre-selected from the list
+    if let Some(Command::Merge { .. }) = &cli.command {
+        let merge_type = config.merge_type.clone().unwrap_or_else(config::default_merge_type);
+        let types = config.types.get_or_insert_with(config::default_types);
+        if !types.contains(&merge_type) {
+            types.push(merge_type);
+        }
+    }
+    // --reuse-message wins over those, and explicit answers (flags, then
+    // --answers-file) over everything
+    if let Some(rev) = &cli.reuse_message {
+        prefill = parse_message(&commit_message(rev)?).to_prefill(true).or(prefill);
+    }
+    let mut answers = cli.prefill()?;
+    if let Some(path) = &cli.answers_file {
+        answers = answers.or(Answers::load(path)?.to_prefill());
+    }
+    // A planned commit's answers beat the flags, which fill in what it leaves out
+    let flag_answers = answers.clone();
+    if let Some(plan) = &mut plan {
+        let planned = next_in_plan(plan, &cli, &config)?.ok_or("None of the planned commits has anything to stage.")?;
+        answers = planned.or(answers);
+    }
+    prefill = answers.clone().or(prefill);
+    let message_only = cli.print_message_only || cli.output.is_some() || cli.json || cli.copy;
+
+    // Headless mode: the pre-filled answers are final, but every rule still applies
+    if cli.non_interactive {
+        // A hidden type step is never asked, so it can't be missing
+        if Step::Type.is_hidden(&config) && prefill.commit_type.is_none() {
+            prefill.commit_type = config.types.as_ref().and_then(|types| types.first().cloned());
+        }
+        let message = CommitMessage::from_prefill(&prefill);
+        let errors = validate_message(&message, &config);
+        if !errors.is_empty() {
+            for error in &errors {
+                eprintln!("error: {}: {}", error.field, error.message);
+            }
+            exit(2);
+        }
+        if message_only {
+            start_operation(&cli)?;
+            return emit_message(&cli, &config, &message, false);
+        }
+        if !protected_branch_ok(&config, branch.as_deref(), false)? {
+            exit(1);
+        }
+        if let Some(problem) = identity().as_ref().and_then(check_signing).filter(|_| commit_options.gpg_sign) {
+            eprintln!("error: {}", problem.describe());
+            exit(1);
+        }
+        start_operation(&cli)?;
+        empty_commit_ok(&config, &mut commit_options, false)?;
+        if !cli.no_content_checks {
+            let warnings = check_staged_content(&config);
+            if !warnings.is_empty() {
+                for warning in &warnings {
+                    eprintln!("error: {}", warning.describe());
+                }
+                eprintln!("Pass --no-content-checks to commit anyway.");
+                exit(1);
+            }
+        }
+        let output = commit_with_message(&message.format_with(&config), &commit_options)?;
+        print!("{}", output.stdout);
+        if !output.success {
+            eprint!("{}", output.stderr);
+            exit(1);
+        }
+        started().take();
+        remember_issues(branch.as_deref(), prefill.issue.as_deref().unwrap_or_default());
+        return Ok(());
+    }
+
+    // A session locked for being idle, or killed, left its answers behind: resumed
+    // if the user says so, and anything given explicitly still wins. Subcommands
+    // bring their own answers and leave the draft for a plain run.
+    let draft = match cli.command {
+        None => remembered::take_draft(),
+        Some(_) => None,
+    };
+    let draft = match draft {
+        Some(draft) if tui::show_resume_draft(&draft)? => Some(draft.answers),
+        _ => None,
+    };
+    let restored = draft.is_some();
+    // Without one, the type and scope of a wizard quit a moment ago; subcommands
+    // bring their own
+    let selection_minutes =
+        config.selection_memory_minutes.unwrap_or_else(commitui::config::default_selection_memory_minutes);
+    let selection = remembered::take_selection(Duration::from_secs(selection_minutes.saturating_mul(60)))
+        .filter(|_| cli.command.is_none());
+    if let Some(draft) = draft {
+        prefill = answers.clone().or(draft).or(prefill);
+    } else if let Some(selection) = selection {
+        prefill = answers.clone().or(selection).or(prefill);
+    }
+
+    // Warn about merges, rebases, conflicts, ... before committing anything
+    if !message_only && cli.as_editor.is_none() {
+        let warnings = check_repo();
+        if !warnings.is_empty() && !show_repo_warnings(&warnings)? {
+            abort();
+        }
+        if !protected_branch_ok(&config, branch.as_deref(), true)? {
+            abort();
+        }
+    }
+    // Only now do `revert` and `merge` touch the index; quitting from here on undoes it
+    if cli.as_editor.is_none() {
+        start_operation(&cli)?;
+    }
+    if !message_only && cli.as_editor.is_none() && plan.is_none() && !empty_commit_ok(&config, &mut commit_options, true)? {
+        abort();
+    }
+    // Keys, .env files, huge binaries, ... (git hooks may check too, but only after the wizard)
+    // (a planned commit was checked when it was staged)
+    if !message_only && cli.as_editor.is_none() && plan.is_none() && !cli.no_content_checks && !content_ok(&config)? {
+        abort();
+    }
+    // A signing key git can't find would only fail the commit after the wizard
+    if !message_only && cli.as_editor.is_none() && commit_options.gpg_sign {
+        if let Some(problem) = identity().as_ref().and_then(check_signing) {
+            if !show_signing_problem(&problem)? {
+                abort();
+            }
+            commit_options.gpg_sign = false;
+        }
+    }
+    // Concluding a merge (or cherry-pick/revert): git's prepared message is the starting body
+    if cli.as_editor.is_none() {
+        if let Some(message) = merge_message(comment_char()) {
+            prefill = prefill.or(Prefill { body: Some(message), ..Prefill::default() });
+        }
+    }
+    // Editor mode: a message already in the file (amending, merging, rewording)
+    // is the starting point; explicit answers still win
+    let edit = match &cli.as_editor {
+        Some(path) => read_edit_message(path, comment_char())?,
+        None => EditMessage::default(),
+    };
+    if !edit.message.is_empty() {
+        prefill = answers.clone().or(parse_message(&edit.message).to_prefill(true)).or(prefill);
+    }
+
+    let mut state = AppState::new(&config, prefill);
+    state.skip_answered(&config, &answers);
+    state.draft_restored = restored;
+
+    // Editor mode: git is already running `commit`, we only fill in the message file
+    if let Some(path) = &cli.as_editor {
+        // An empty message makes git abort the commit itself, so that's how a
+        // protected branch refuses it here
+        if !protected_branch_ok(&config, branch.as_deref(), true)? {
+            write_message(path, "", &edit)?;
+            return Ok(());
+        }
+        let commit_message = run_tui(&mut config, &mut state, None, &edit.comments)?.unwrap_or_default();
+        write_message(path, &commit_message, &edit)?;
+        return Ok(());
+    }
+
+    // Message-only mode: never commit, hand the result to whoever called us
+    if message_only {
+        let result = run_tui(&mut config, &mut state, None, &[])?;
+        return emit_message(&cli, &config, &CommitMessage::from_state(&state), result.is_none());
+    }
+
+    // Run the TUI and commit; a failed commit sends the user back to the preview
+    // step with everything they typed still there.
+    'wizard: loop {
+        let commit_message = run_tui(&mut config, &mut state, Some(&mut commit_options), &[])?.unwrap_or_else(|| {
+            if let Some(plan) = &plan {
+                eprintln!("Stopped at planned commit {} of {}; its paths are still staged.", plan.current, plan.total);
+            }
+            let _ = remembered::save_selection(&state.draft());
+            abort()
+        });
+        state.commit_error = None;
+        if state.copy_requested {
+            clipboard::copy(&commit_message)?;
+            println!("Copied the message to the clipboard; nothing was committed.");
+            return Ok(());
+        }
 
-    // Run the TUI and get the commit message
-    let commit_message = run_tui(config)?;
+        loop {
+            if commit_options.gpg_sign {
+                println!("Signing the commit; gpg may ask for the key's passphrase.");
+            }
+            let output = commit_with_message(&commit_message, &commit_options)?;
+            if output.success {
+                print!("{}", output.stdout);
+                println!("Commit successful!");
+                started().take();
+                remember_issues(branch.as_deref(), &state.issues);
+                let message = CommitMessage::from_state(&state);
+                if is_release(&message, &config) {
+                    offer_tag(&message)?;
+                }
+                if let Some(plan) = &mut plan {
+                    println!("Planned commit {} of {} done.", plan.current, plan.total);
+                    let Some(planned) = next_in_plan(plan, &cli, &config)? else { break 'wizard };
+                    let answers = planned.or(flag_answers.clone());
+                    state = AppState::new(&config, answers.clone().or(queue_defaults.clone()));
+                    state.skip_answered(&config, &answers);
+                    continue 'wizard;
+                }
+                if !cli.queue || !next_in_queue(&cli, &config)? {
+                    break 'wizard;
+                }
+                // The issue just used, if the branch name didn't give one
+                let issue = Some(state.issues.trim().to_string()).filter(|issue| !issue.is_empty());
+                queue_defaults = queue_defaults.or(Prefill { issue, ..Prefill::default() });
+                state = AppState::new(&config, queue_defaults.clone());
+                continue 'wizard;
+            }
 
-    // Actually perform the commit
-    commit_with_message(&commit_message)?;
+            let details = format!("{}{}", output.stdout, output.stderr);
+            let action = if output.signing_failed() { show_signing_failure(&details)? } else { show_commit_failure(&details)? };
+            match action {
+                FailureAction::Retry => {}
+                FailureAction::Unsigned => commit_options.gpg_sign = false,
+                FailureAction::Edit => {
+                    state.commit_error = Some(details);
+                    state.step = Step::Preview;
+                    continue 'wizard;
+                }
+                FailureAction::Abort => {
+                    eprint!("{}", details);
+                    abort();
+                }
+            }
+        }
+    }
 
     Ok(())
 }
\ No newline at end of file
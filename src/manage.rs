@@ -0,0 +1,170 @@
+use commitui::config::LOCAL_CONFIG;
+use commitui::i18n::{t, tf};
+use crate::tui::{degrade, poll_event, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Style, Color},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// The config lists `m` edits from the Type and Scope steps
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ManagedList {
+    Types,
+    Scopes,
+}
+
+impl ManagedList {
+    pub fn key(self) -> &'static str {
+        match self {
+            ManagedList::Types => "types",
+            ManagedList::Scopes => "scopes",
+        }
+    }
+
+    // Leading entries that stay put: the scope picker's first row means "no scope"
+    fn fixed(self) -> usize {
+        match self {
+            ManagedList::Types => 0,
+            ManagedList::Scopes => 1,
+        }
+    }
+}
+
+enum Edit {
+    Add,
+    Rename,
+}
+
+// Add, rename, delete and reorder the entries of `items`. Returns the new
+// list to save, or None if the user backed out.
+pub fn manage_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    list: ManagedList,
+    items: &[String],
+) -> Result<Option<Vec<String>>, Box<dyn std::error::Error>> {
+    let fixed = list.fixed().min(items.len());
+    let mut items = items.to_vec();
+    let mut current = fixed.min(items.len().saturating_sub(1));
+    let mut editing: Option<(Edit, String)> = None;
+
+    loop {
+        // Names must be unique; renaming an entry to itself is fine
+        let valid = |edit: &Edit, input: &str, items: &[String], current: usize| {
+            let name = input.trim();
+            !name.is_empty() && !items.iter().enumerate().any(|(i, item)| item == name && !(matches!(edit, Edit::Rename) && i == current))
+        };
+
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),                                          // Entries
+                    Constraint::Length(if editing.is_some() { 3 } else { 0 }),   // Name input
+                    Constraint::Length(1),                                       // Key hints
+                ])
+                .split(f.size());
+
+            let rows: Vec<ListItem> = items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let style = if i < fixed { Style::default().fg(Color::DarkGray) } else { Style::default() };
+                    ListItem::new(item.as_str()).style(style)
+                })
+                .collect();
+            let mut list_state = ListState::default();
+            list_state.select(Some(current));
+            let entries = List::new(rows)
+                .block(Block::default().title(tf("titles.manage", &[("list", list.key()), ("path", LOCAL_CONFIG)])).borders(Borders::ALL))
+                .highlight_style(Style::default().bg(Color::Blue))
+                .highlight_symbol(">> ");
+            f.render_stateful_widget(entries, chunks[0], &mut list_state);
+
+            if let Some((edit, input)) = &editing {
+                let title = match edit {
+                    Edit::Add => t("titles.manage_add"),
+                    Edit::Rename => t("titles.manage_rename"),
+                };
+                let border = if valid(edit, input, &items, current) { Color::Green } else { Color::Red };
+                let paragraph = Paragraph::new(input.as_str())
+                    .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(border)))
+                    .style(Style::default().fg(Color::Yellow));
+                f.render_widget(paragraph, chunks[1]);
+                f.set_cursor(chunks[1].x + 1 + input.chars().count() as u16, chunks[1].y + 1);
+            }
+
+            let hints = Paragraph::new(if editing.is_some() { t("hints.manage_editing") } else { t("hints.manage") })
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[2]);
+            degrade(f);
+        })?;
+
+        let Some(Event::Key(key)) = poll_event(terminal, events)? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(None);
+        }
+
+        if let Some((edit, input)) = &mut editing {
+            match key.code {
+                KeyCode::Esc => editing = None,
+                KeyCode::Enter if valid(edit, input, &items, current) => {
+                    let name = input.trim().to_string();
+                    match edit {
+                        Edit::Add => {
+                            current = (current + 1).max(fixed).min(items.len());
+                            items.insert(current, name);
+                        }
+                        Edit::Rename => items[current] = name,
+                    }
+                    editing = None;
+                }
+                KeyCode::Char(c) => input.push(c),
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                _ => {}
+            }
+            continue;
+        }
+
+        let editable = current >= fixed && current < items.len();
+        let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+        // Shift+Up/Down (or K/J) carries the entry along, below the fixed ones
+        let target = match key.code {
+            KeyCode::Up if shift => current.checked_sub(1),
+            KeyCode::Char('K') => current.checked_sub(1),
+            KeyCode::Down if shift => Some(current + 1),
+            KeyCode::Char('J') => Some(current + 1),
+            _ => None,
+        };
+        if let Some(target) = target {
+            if editable && target >= fixed && target < items.len() {
+                items.swap(current, target);
+                current = target;
+            }
+            continue;
+        }
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+            KeyCode::Enter | KeyCode::Char('s') => return Ok(Some(items)),
+            KeyCode::Up => current = current.saturating_sub(1),
+            KeyCode::Down => current = (current + 1).min(items.len().saturating_sub(1)),
+            KeyCode::Char('a') => editing = Some((Edit::Add, String::new())),
+            KeyCode::Char('r') if editable => editing = Some((Edit::Rename, items[current].clone())),
+            // There's always a type to pick
+            KeyCode::Char('d') | KeyCode::Delete if editable && items.len() > 1 => {
+                items.remove(current);
+                current = current.min(items.len() - 1);
+            }
+            _ => {}
+        }
+    }
+}
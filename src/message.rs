@@ -0,0 +1,301 @@
+use crate::config::{self, BreakingPosition, Config};
+use crate::git::GitError;
+use crate::state::{AppState, Prefill};
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::sync::OnceLock;
+
+// The structured commit message; the final text is always rendered from this
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct CommitMessage {
+    #[serde(rename = "type")]
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub subject: String,
+    pub body: String,
+    pub breaking: Option<String>,
+    pub bang: bool, // `!` after the type and scope
+    pub footers: Vec<String>,
+}
+
+impl CommitMessage {
+    pub fn from_state(state: &AppState) -> Self {
+        // The body is the finished lines plus whatever is still being typed
+        let mut body_lines = state.body_lines.clone();
+        if !state.body.is_empty() {
+            body_lines.push(state.body.clone());
+        }
+
+        CommitMessage {
+            commit_type: state.chosen_type.clone(),
+            scope: state.chosen_scope.clone().filter(|s| !s.is_empty()),
+            subject: state.subject.clone(),
+            body: body_lines.join("\n"),
+            breaking: Some(state.breaking.trim().to_string()).filter(|b| !b.is_empty()),
+            bang: state.bang,
+            footers: state.issues
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .chain(state.smart_commit.footer())
+                .collect(),
+        }
+    }
+
+    // Take the pre-filled answers as final, for headless use
+    pub fn from_prefill(prefill: &Prefill) -> Self {
+        CommitMessage {
+            commit_type: prefill.commit_type.clone(),
+            scope: prefill.scope.clone().filter(|s| !s.is_empty()),
+            subject: prefill.subject.clone().unwrap_or_default(),
+            body: prefill.body.clone().unwrap_or_default().trim().to_string(),
+            breaking: prefill.breaking.clone().filter(|b| !b.trim().is_empty()),
+            bang: prefill.bang,
+            footers: prefill.issue
+                .as_deref()
+                .unwrap_or_default()
+                .lines()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty())
+                .collect(),
+        }
+    }
+
+    // `type(scope): subject`, or `type: subject` without a scope; just
+    // `type(scope)` when a type's rules let the subject be empty. A `bang`
+    // goes right before the colon: `type(scope)!: subject`.
+    pub fn header(&self) -> String {
+        let bang = if self.bang { "!" } else { "" };
+        if self.subject.trim().is_empty() {
+            return match (&self.commit_type, &self.scope) {
+                (Some(ty), Some(scope)) => format!("{}({}){}", ty, scope, bang),
+                (Some(ty), None) => format!("{}{}", ty, bang),
+                (None, _) => String::new(),
+            };
+        }
+        match (&self.commit_type, &self.scope) {
+            (Some(ty), Some(scope)) => format!("{}({}){}: {}", ty, scope, bang, self.subject),
+            (Some(ty), None) => format!("{}{}: {}", ty, bang, self.subject),
+            (None, _) => String::new(),
+        }
+    }
+
+    // The footer block, one trailer per line with BREAKING CHANGE first or
+    // last; a trailer given twice is written once
+    pub fn trailers(&self, position: BreakingPosition) -> Vec<String> {
+        let breaking = self.breaking.iter().map(|b| format!("BREAKING CHANGE: {}", b));
+        let ordered: Vec<String> = match position {
+            BreakingPosition::First => breaking.chain(self.footers.iter().cloned()).collect(),
+            BreakingPosition::Last => self.footers.iter().cloned().chain(breaking).collect(),
+        };
+        let mut trailers: Vec<String> = Vec::new();
+        for trailer in ordered {
+            if !trailers.contains(&trailer) {
+                trailers.push(trailer);
+            }
+        }
+        trailers
+    }
+
+    pub fn format(&self) -> String {
+        self.layout(config::default_breaking_position())
+    }
+
+    // Paragraphs are separated by one blank line; git wants a trailing newline.
+    // The trailers share the last paragraph, so `git interpret-trailers` finds them.
+    fn layout(&self, position: BreakingPosition) -> String {
+        let mut paragraphs: Vec<String> = Vec::new();
+        let header = self.header();
+        if !header.is_empty() {
+            paragraphs.push(header);
+        }
+        if !self.body.is_empty() {
+            paragraphs.push(self.body.clone());
+        }
+        let trailers = self.trailers(position);
+        if !trailers.is_empty() {
+            paragraphs.push(trailers.join("\n"));
+        }
+
+        let mut result = paragraphs.join("\n\n");
+        result.push('\n');
+        result
+    }
+
+    // `interpret_trailers`: git adds the footers. BREAKING CHANGE isn't a token
+    // git accepts (it has a space), so it's put into git's trailer block
+    // afterwards. None without footers or when git fails.
+    fn layout_with_git(&self, position: BreakingPosition) -> Option<String> {
+        self.layout_with_trailers(position, |text, footers| crate::git::interpret_trailers(text, footers).ok())
+    }
+
+    // `layout_with_git` with whatever adds the footers to a message
+    fn layout_with_trailers(
+        &self,
+        position: BreakingPosition,
+        interpret: impl Fn(&str, &[String]) -> Option<String>,
+    ) -> Option<String> {
+        let footers = CommitMessage { breaking: None, ..self.clone() }.trailers(position);
+        if footers.is_empty() {
+            return None;
+        }
+        let without = CommitMessage { breaking: None, footers: Vec::new(), ..self.clone() }.layout(position);
+        // git reads the end of the message, so it gets a stand-in header: typing
+        // the subject reuses git's last answer instead of running it again
+        let header = self.header();
+        let mut text = match without.strip_prefix(header.as_str()) {
+            Some(rest) if !header.is_empty() => {
+                let answer = interpret(&format!("-{}", rest), &footers)?;
+                format!("{}{}", header, answer.strip_prefix('-')?)
+            }
+            _ => interpret(&without, &footers)?,
+        };
+        if let Some(breaking) = &self.breaking {
+            let breaking = format!("BREAKING CHANGE: {}\n", breaking);
+            match position {
+                BreakingPosition::First => {
+                    let block = text.trim_end().rfind("\n\n").map_or(0, |at| at + 2);
+                    text.insert_str(block, &breaking);
+                }
+                BreakingPosition::Last => text.push_str(&breaking),
+            }
+        }
+        Some(text)
+    }
+
+    // The final text as configured: `message_template` if set, else `format()`
+    pub fn format_with(&self, config: &Config) -> String {
+        let message = self.with_default_subject(config);
+        let position = config.breaking_position.unwrap_or_else(config::default_breaking_position);
+        match &config.message_template {
+            Some(template) => message.render(template, position),
+            None if config.interpret_trailers.unwrap_or_else(config::default_interpret_trailers) => {
+                message.layout_with_git(position).unwrap_or_else(|| message.layout(position))
+            }
+            None => message.layout(position),
+        }
+    }
+
+    // An empty subject gets the type's `default_subject`, if it has one
+    pub fn with_default_subject(&self, config: &Config) -> CommitMessage {
+        let rules = config.type_rules(self.commit_type.as_deref());
+        match rules.and_then(|r| r.default_subject.clone()) {
+            Some(subject) if self.subject.trim().is_empty() => CommitMessage { subject, ..self.clone() },
+            _ => self.clone(),
+        }
+    }
+
+    // Fill in a `message_template`; unknown placeholders are left as written
+    pub fn render(&self, template: &str, position: BreakingPosition) -> String {
+        static PLACEHOLDER: OnceLock<Regex> = OnceLock::new();
+        let placeholder = PLACEHOLDER.get_or_init(|| Regex::new(r"\{(\w+)\}").unwrap());
+
+        let footers = self.trailers(position);
+        let ty = self.commit_type.clone().unwrap_or_default();
+        let scope = self.scope.clone().unwrap_or_default();
+
+        let filled = placeholder.replace_all(template, |caps: &Captures| match &caps[1] {
+            "type" => ty.clone(),
+            "TYPE" => ty.to_uppercase(),
+            "scope" => scope.clone(),
+            "SCOPE" => scope.to_uppercase(),
+            "scope_paren" => self.scope.as_ref().map(|s| format!("({})", s)).unwrap_or_default(),
+            "bang" => if self.bang { "!".to_string() } else { String::new() },
+            "subject" => self.subject.clone(),
+            "body" => self.body.clone(),
+            "breaking" => self.breaking.clone().unwrap_or_default(),
+            "footers" => footers.join("\n"),
+            _ => caps[0].to_string(),
+        });
+
+        // Empty placeholders leave blank runs behind; keep single blank lines between paragraphs
+        let mut result = String::new();
+        let mut blank = false;
+        for line in filled.trim().lines().map(|l| l.trim_end()) {
+            if line.is_empty() {
+                blank = true;
+                continue;
+            }
+            if blank && !result.is_empty() {
+                result.push('\n');
+            }
+            blank = false;
+            result.push_str(line);
+            result.push('\n');
+        }
+        result
+    }
+}
+
+// `--json` output; on abort the fields hold whatever had been entered so far
+#[derive(Debug, Serialize)]
+pub struct JsonOutput<'a> {
+    #[serde(flatten)]
+    pub message: &'a CommitMessage,
+    pub aborted: bool,
+}
+
+// `--json` output when a git command failed before there was a message
+#[derive(Debug, Serialize)]
+pub struct JsonError<'a> {
+    pub error: &'a GitError,
+    pub aborted: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    // `git interpret-trailers` away from the user's own trailer.* settings
+    fn default_git_trailers(message: &str, footers: &[String]) -> Option<String> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut file, message.as_bytes()).unwrap();
+        let mut command = Command::new("git");
+        command.env("GIT_CONFIG_GLOBAL", "/dev/null").env("GIT_CONFIG_NOSYSTEM", "1").arg("interpret-trailers");
+        for footer in footers {
+            command.args(["--trailer", footer]);
+        }
+        let output = command.arg(file.path()).output().unwrap();
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        Some(String::from_utf8(output.stdout).unwrap())
+    }
+
+    #[test]
+    fn git_lays_out_the_trailers_as_the_default_layout_does() {
+        let message = CommitMessage {
+            commit_type: Some("feat".to_string()),
+            subject: "add export".to_string(),
+            body: "Writes CSV.".to_string(),
+            breaking: Some("drops XML".to_string()),
+            footers: vec!["Refs: #1".to_string(), "Closes: #2".to_string(), "Refs: #1".to_string()],
+            ..CommitMessage::default()
+        };
+        for position in [BreakingPosition::First, BreakingPosition::Last] {
+            for message in [message.clone(), CommitMessage { body: String::new(), ..message.clone() }] {
+                assert_eq!(message.layout_with_trailers(position, default_git_trailers), Some(message.layout(position)));
+            }
+        }
+    }
+
+    #[test]
+    fn the_conventional_template_renders_as_the_default_layout() {
+        let config = Config {
+            message_template: Some("{type}{scope_paren}{bang}: {subject}\n\n{body}\n\n{footers}".to_string()),
+            ..Config::default()
+        };
+        let message = CommitMessage {
+            commit_type: Some("feat".to_string()),
+            scope: Some("api".to_string()),
+            subject: "add export".to_string(),
+            body: "Writes CSV.".to_string(),
+            breaking: Some("drops XML".to_string()),
+            footers: vec!["Refs: #1".to_string()],
+            ..CommitMessage::default()
+        };
+        // The footer alone marks it breaking; `!` is only there when it was typed
+        for message in [message.clone(), CommitMessage { bang: true, ..message }] {
+            assert_eq!(message.format_with(&config), message.format_with(&Config::default()));
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use crate::suggest::StagedFile;
+use std::fs;
+use std::path::Path;
+
+// A workspace member offered as a scope
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub name: String,
+    pub path: String, // Directory relative to the repository root, `/`-separated
+}
+
+// Cargo `[workspace] members`, package.json `workspaces` (array or
+// `{ packages = [...] }`) and any extra globs from config, in that order
+pub fn detect_packages(root: &Path, extra_globs: &[String]) -> Vec<Package> {
+    let mut packages = Vec::new();
+    for pattern in cargo_members(root).into_iter().chain(npm_workspaces(root)).chain(extra_globs.iter().cloned()) {
+        for path in expand(root, &pattern) {
+            if packages.iter().any(|p: &Package| p.path == path) {
+                continue;
+            }
+            let name = package_name(&root.join(&path)).unwrap_or_else(|| dir_name(&path));
+            packages.push(Package { name, path });
+        }
+    }
+    packages
+}
+
+fn cargo_members(root: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(root.join("Cargo.toml")) else { return vec![] };
+    let Ok(manifest) = content.parse::<toml::Table>() else { return vec![] };
+    manifest.get("workspace")
+        .and_then(|w| w.get("members"))
+        .and_then(|m| m.as_array())
+        .map(|members| members.iter().filter_map(|m| m.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn npm_workspaces(root: &Path) -> Vec<String> {
+    let Ok(content) = fs::read_to_string(root.join("package.json")) else { return vec![] };
+    let Ok(manifest) = serde_json::from_str::<serde_json::Value>(&content) else { return vec![] };
+    let workspaces = match manifest.get("workspaces") {
+        Some(serde_json::Value::Object(w)) => w.get("packages"),
+        other => other,
+    };
+    workspaces
+        .and_then(|w| w.as_array())
+        .map(|patterns| patterns.iter().filter_map(|p| p.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+// Directories matching `pattern` under `root`, relative to it
+fn expand(root: &Path, pattern: &str) -> Vec<String> {
+    let full = root.join(pattern.trim_end_matches('/'));
+    let Ok(paths) = glob::glob(&full.to_string_lossy()) else { return vec![] };
+    let mut dirs: Vec<String> = paths
+        .filter_map(Result::ok)
+        .filter(|p| p.is_dir())
+        .filter_map(|p| p.strip_prefix(root).ok().map(|rel| rel.to_string_lossy().replace('\\', "/")))
+        .filter(|rel| !rel.is_empty())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+// The name from the member's own manifest; npm `@org/name` becomes `name`
+fn package_name(dir: &Path) -> Option<String> {
+    if let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) {
+        let manifest = content.parse::<toml::Table>().ok()?;
+        return manifest.get("package")?.get("name")?.as_str().map(String::from);
+    }
+    let content = fs::read_to_string(dir.join("package.json")).ok()?;
+    let manifest = serde_json::from_str::<serde_json::Value>(&content).ok()?;
+    let name = manifest.get("name")?.as_str()?;
+    Some(name.rsplit('/').next().unwrap_or(name).to_string())
+}
+
+fn dir_name(path: &str) -> String {
+    path.rsplit('/').next().unwrap_or(path).to_string()
+}
+
+// The package holding more than half of the staged files, if any
+pub fn majority_package<'a>(packages: &'a [Package], files: &[StagedFile]) -> Option<&'a Package> {
+    let owner = |file: &StagedFile| {
+        // Nested members: the deepest matching directory owns the file
+        packages.iter()
+            .filter(|p| file.path.starts_with(&format!("{}/", p.path)))
+            .max_by_key(|p| p.path.len())
+    };
+    let (package, count) = packages.iter()
+        .map(|p| (p, files.iter().filter(|f| owner(f) == Some(p)).count()))
+        .max_by_key(|(_, count)| *count)?;
+    (count * 2 > files.len()).then_some(package)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_majority_package_holds_more_than_half_of_the_files() {
+        let package = |name: &str, path: &str| Package { name: name.into(), path: path.into() };
+        let packages = [package("core", "crates/core"), package("cli", "crates/cli"), package("core-macros", "crates/core/macros")];
+        let files = |paths: &[&str]| -> Vec<StagedFile> {
+            paths.iter().map(|path| StagedFile { status: 'M', path: path.to_string() }).collect()
+        };
+
+        let majority = majority_package(&packages, &files(&["crates/cli/src/main.rs", "crates/cli/Cargo.toml", "README.md"]));
+        assert_eq!(majority.map(|p| p.name.as_str()), Some("cli"));
+        // Exactly half isn't more than half
+        assert_eq!(majority_package(&packages, &files(&["crates/cli/src/main.rs", "README.md"])), None);
+        // The nested member owns its files, not the package around it
+        let nested = files(&["crates/core/macros/src/lib.rs", "crates/core/macros/Cargo.toml", "crates/core/src/lib.rs"]);
+        assert_eq!(majority_package(&packages, &nested).map(|p| p.name.as_str()), Some("core-macros"));
+        // `crates/cli-old` isn't inside `crates/cli`
+        assert_eq!(majority_package(&packages, &files(&["crates/cli-old/main.rs"])), None);
+        assert_eq!(majority_package(&packages, &[]), None);
+    }
+}
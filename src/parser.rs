@@ -0,0 +1,106 @@
+use crate::state::Prefill;
+use regex::Regex;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+// A commit message split into its conventional-commit parts
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParsedCommit {
+    pub commit_type: Option<String>, // None when the header isn't conventional
+    pub scope: Option<String>,
+    pub bang: bool, // `feat!:` style breaking marker
+    pub subject: String,
+    pub body: String,
+    pub breaking: Option<String>, // BREAKING CHANGE footer description
+    pub footers: Vec<String>,     // All other trailers, as written
+}
+
+fn header_regex() -> &'static Regex {
+    static HEADER: OnceLock<Regex> = OnceLock::new();
+    HEADER.get_or_init(|| {
+        Regex::new(r"^(?P<type>[A-Za-z][\w-]*)(?:\((?P<scope>[^()]*)\))?(?P<bang>!)?: (?P<subject>.*)$").unwrap()
+    })
+}
+
+// Where each part of a conventional header is, as byte ranges of the header
+// as written (so it can be highlighted without re-composing it)
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeaderParts {
+    pub commit_type: Range<usize>,
+    pub scope: Option<Range<usize>>, // Without the parentheses
+    pub bang: Option<Range<usize>>,
+    pub subject: Range<usize>,
+}
+
+// None when `header` isn't `type(scope)!: subject`
+pub fn header_parts(header: &str) -> Option<HeaderParts> {
+    let caps = header_regex().captures(header)?;
+    Some(HeaderParts {
+        commit_type: caps.name("type")?.range(),
+        scope: caps.name("scope").map(|m| m.range()),
+        bang: caps.name("bang").map(|m| m.range()),
+        subject: caps.name("subject")?.range(),
+    })
+}
+
+fn footer_regex() -> &'static Regex {
+    static FOOTER: OnceLock<Regex> = OnceLock::new();
+    FOOTER.get_or_init(|| Regex::new(r"^(BREAKING CHANGE|BREAKING-CHANGE|[\w-]+)(: | #)").unwrap())
+}
+
+pub fn parse_message(message: &str) -> ParsedCommit {
+    let mut parsed = ParsedCommit::default();
+
+    // Paragraphs are separated by blank lines; the first line is always the header
+    let mut lines = message.trim().lines();
+    let header = lines.next().unwrap_or_default().trim();
+    match header_regex().captures(header) {
+        Some(caps) => {
+            parsed.commit_type = Some(caps["type"].to_string());
+            parsed.scope = caps.name("scope").map(|m| m.as_str().to_string()).filter(|s| !s.is_empty());
+            parsed.bang = caps.name("bang").is_some();
+            parsed.subject = caps["subject"].trim().to_string();
+        }
+        None => parsed.subject = header.to_string(),
+    }
+
+    let rest: Vec<&str> = lines.collect();
+    let rest = rest.join("\n");
+    let mut paragraphs: Vec<&str> = rest.split("\n\n").map(|p| p.trim_matches('\n')).filter(|p| !p.trim().is_empty()).collect();
+
+    // The last paragraph is the footer block only if every line in it is a trailer
+    if let Some(last) = paragraphs.last() {
+        if last.lines().all(|line| footer_regex().is_match(line)) {
+            for line in last.lines() {
+                if let Some(desc) = line.strip_prefix("BREAKING CHANGE: ").or_else(|| line.strip_prefix("BREAKING-CHANGE: ")) {
+                    parsed.breaking = Some(desc.trim().to_string());
+                } else {
+                    parsed.footers.push(line.to_string());
+                }
+            }
+            paragraphs.pop();
+        }
+    }
+    parsed.body = paragraphs.join("\n\n");
+
+    parsed
+}
+
+impl ParsedCommit {
+    // Reuse only the type and scope, or the whole message structure
+    pub fn to_prefill(&self, full: bool) -> Prefill {
+        let mut prefill = Prefill {
+            commit_type: self.commit_type.clone(),
+            scope: self.scope.clone(),
+            ..Prefill::default()
+        };
+        if full {
+            prefill.subject = Some(self.subject.clone());
+            prefill.body = Some(self.body.clone()).filter(|b| !b.is_empty());
+            prefill.breaking = self.breaking.clone();
+            prefill.issue = Some(self.footers.join("\n")).filter(|f| !f.is_empty());
+            prefill.bang = self.bang;
+        }
+        prefill
+    }
+}
@@ -0,0 +1,69 @@
+use crate::config::{self, Config};
+use crate::state::Prefill;
+use crate::suggest::StagedFile;
+use glob::{MatchOptions, Pattern};
+use std::collections::BTreeMap;
+
+// `*` stays within a directory; `**` crosses them
+const OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+// What the longest matching pattern names for `path`
+fn rule_for<'a>(rules: &'a BTreeMap<String, String>, path: &str) -> Option<&'a String> {
+    rules
+        .iter()
+        .filter(|(pattern, _)| Pattern::new(pattern).is_ok_and(|glob| glob.matches_with(path, OPTIONS)))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, value)| value)
+}
+
+// The value every staged file's rule agrees on; None when any file matches no
+// rule, or two files disagree
+pub fn infer(rules: &BTreeMap<String, String>, files: &[StagedFile]) -> Option<String> {
+    let mut values = files.iter().map(|file| rule_for(rules, &file.path));
+    let first = values.next()??;
+    values.all(|value| value == Some(first)).then(|| first.clone())
+}
+
+// The scope from `scope_rules` and the type from `path_types`
+pub fn infer_prefill(config: &Config, files: &[StagedFile]) -> Prefill {
+    let scope_rules = config.scope_rules.clone().unwrap_or_else(config::default_scope_rules);
+    let path_types = config.path_types.clone().unwrap_or_else(config::default_path_types);
+    Prefill {
+        commit_type: infer(&path_types, files),
+        scope: infer(&scope_rules, files),
+        ..Prefill::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_rules_pick_the_scope_and_type_every_staged_file_agrees_on() {
+        let files = |paths: &[&str]| -> Vec<StagedFile> {
+            paths.iter().map(|path| StagedFile { status: 'M', path: path.to_string() }).collect()
+        };
+        let rules = |pairs: &[(&str, &str)]| Some(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect());
+        let config = Config {
+            scope_rules: rules(&[("src/**", "core"), ("src/tui/**", "tui"), ("docs/**", "docs")]),
+            path_types: rules(&[("docs/**", "docs"), ("*.md", "docs")]),
+            ..Config::default()
+        };
+
+        let tui = infer_prefill(&config, &files(&["src/tui/app.rs", "src/tui/steps/body.rs"]));
+        assert_eq!((tui.commit_type, tui.scope.as_deref()), (None, Some("tui")));
+        let docs = infer_prefill(&config, &files(&["docs/guide.md", "README.md"]));
+        assert_eq!(docs.commit_type.as_deref(), Some("docs"));
+        assert_eq!(docs.scope, None); // README.md has no scope rule
+        let mixed = infer_prefill(&config, &files(&["src/tui/app.rs", "src/git.rs"]));
+        assert_eq!(mixed.scope, None);
+        // `*` doesn't cross directories
+        assert_eq!(infer_prefill(&config, &files(&["docs/notes/todo.md"])).commit_type.as_deref(), Some("docs"));
+        assert_eq!(infer_prefill(&config, &files(&["src/notes.md"])).commit_type, None);
+    }
+}
@@ -0,0 +1,88 @@
+use crate::answers::Answers;
+use std::{fs, path::Path};
+
+// A series of commits to make one after another (`commiTUI plan apply`), e.g.
+//   [[commit]]
+//   paths = ["src/lexer.rs", "src/parser.rs"]
+//   type = "refactor"
+//   scope = "parser"
+//   subject = "move the lexer into its own module"
+// `paths` are staged (relative to where commiTUI runs, as with `git add`); the
+// other keys are those of an answers file, so a complete message opens on the
+// preview, ready to confirm or edit.
+#[derive(Debug, Clone, Default)]
+pub struct Plan {
+    pub commits: Vec<PlannedCommit>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PlannedCommit {
+    pub paths: Vec<String>,
+    pub answers: Answers,
+}
+
+impl Plan {
+    pub fn load(path: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut table: toml::Table = toml::from_str(&fs::read_to_string(path)?)?;
+        let Some(toml::Value::Array(tables)) = table.remove("commit") else {
+            return Err(format!("{} plans no commits (add [[commit]] tables).", path.display()).into());
+        };
+        if let Some(key) = table.keys().next() {
+            return Err(format!("{}: unknown key `{}` (only [[commit]] tables go here).", path.display(), key).into());
+        }
+        // Each table is an answers file plus `paths`, and as strict about typos
+        let mut commits = Vec::new();
+        for (index, mut commit) in tables.into_iter().enumerate() {
+            let invalid = |e: &dyn std::fmt::Display| format!("{}: commit {}: {}", path.display(), index + 1, e.to_string().trim_end());
+            let paths: Vec<String> = match commit.as_table_mut().and_then(|commit| commit.remove("paths")) {
+                Some(paths) => paths.try_into().map_err(|e| invalid(&e))?,
+                None => return Err(invalid(&"`paths` to stage are missing").into()),
+            };
+            let answers = commit.try_into().map_err(|e| invalid(&e))?;
+            // Once committed, a path has nothing left to stage for a later commit
+            for path in &paths {
+                if let Some(earlier) = commits.iter().position(|c: &PlannedCommit| c.paths.contains(path)) {
+                    return Err(invalid(&format!("`{}` is already planned in commit {}", path, earlier + 1)).into());
+                }
+            }
+            commits.push(PlannedCommit { paths, answers });
+        }
+        Ok(Plan { commits })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn load(content: &str) -> Result<Plan, String> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        write!(file, "{}", content).unwrap();
+        Plan::load(file.path()).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn commits_load_with_their_paths_and_answers() {
+        let plan = load(
+            "[[commit]]\npaths = [\"src/lexer.rs\"]\ntype = \"refactor\"\nsubject = \"split the lexer\"\n\n\
+             [[commit]]\npaths = [\"README.md\"]\ntype = \"docs\"\n",
+        )
+        .unwrap();
+        assert_eq!(plan.commits.len(), 2);
+        assert_eq!(plan.commits[0].paths, ["src/lexer.rs"]);
+        let prefill = plan.commits[0].answers.to_prefill();
+        assert_eq!((prefill.commit_type.as_deref(), prefill.subject.as_deref()), (Some("refactor"), Some("split the lexer")));
+    }
+
+    #[test]
+    fn mistakes_are_refused_with_the_commit_they_are_in() {
+        assert!(load("").unwrap_err().contains("plans no commits"));
+        assert!(load("[[commit]]\ntype = \"fix\"\n").unwrap_err().contains("commit 1: `paths` to stage are missing"));
+        assert!(load("title = \"x\"\n[[commit]]\npaths = [\"a\"]\n").unwrap_err().contains("unknown key `title`"));
+        let typo = load("[[commit]]\npaths = [\"a\"]\n\n[[commit]]\npaths = [\"b\"]\nsubjet = \"x\"\n").unwrap_err();
+        assert!(typo.contains("commit 2") && typo.contains("subjet"), "{}", typo);
+        let twice = load("[[commit]]\npaths = [\"a\", \"b\"]\n\n[[commit]]\npaths = [\"b\"]\n").unwrap_err();
+        assert!(twice.contains("commit 2: `b` is already planned in commit 1"), "{}", twice);
+    }
+}
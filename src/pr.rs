@@ -0,0 +1,143 @@
+use crate::git::LogEntry;
+use crate::message::CommitMessage;
+use crate::parser::parse_message;
+use crate::stats::CommitStats;
+
+// What `commiTUI pr` suggests for a branch's pull request
+#[derive(Debug, Clone, PartialEq)]
+pub struct PullRequest {
+    pub title: String,
+    pub body: String, // Markdown
+}
+
+// Section headings for the usual types; any other type is its own heading
+fn heading(ty: &str) -> String {
+    match ty {
+        "feat" => "Features",
+        "fix" => "Bug Fixes",
+        "perf" => "Performance",
+        "refactor" => "Refactoring",
+        "docs" => "Documentation",
+        "test" => "Tests",
+        "build" => "Build",
+        "ci" => "CI",
+        "style" => "Style",
+        "chore" => "Chores",
+        "revert" => "Reverts",
+        other => other,
+    }
+    .to_string()
+}
+
+// From the dominant type (and scope, if most commits share one) with the
+// subject of the first such commit; `commits` are oldest first
+pub fn suggested_title(commits: &[LogEntry]) -> String {
+    let header = |entry: &LogEntry| entry.message.lines().next().unwrap_or_default().to_string();
+    let stats = CommitStats::from_log(commits);
+    match (commits, stats.types.first()) {
+        ([single], _) => header(single),
+        (_, Some((ty, _))) => {
+            let scope = stats.scopes.first().filter(|(_, count)| *count as usize * 2 > stats.conventional).map(|(s, _)| s);
+            let subject = commits
+                .iter()
+                .map(|c| parse_message(&c.message))
+                .find(|p| p.commit_type.as_ref() == Some(ty) && (scope.is_none() || p.scope.as_ref() == scope))
+                .map(|p| p.subject)
+                .unwrap_or_default();
+            match scope {
+                Some(scope) => format!("{}({}): {}", ty, scope, subject),
+                None => format!("{}: {}", ty, subject),
+            }
+        }
+        (_, None) => commits.first().map(header).unwrap_or_default(),
+    }
+}
+
+// The suggested title; the body lists every commit grouped by type, in
+// `types` order, breaking changes first and non-conventional commits last
+pub fn describe(commits: &[LogEntry], types: &[String]) -> PullRequest {
+    let parsed: Vec<_> = commits.iter().map(|c| (c, parse_message(&c.message))).collect();
+    let header = |entry: &LogEntry| entry.message.lines().next().unwrap_or_default().to_string();
+
+    let stats = CommitStats::from_log(commits);
+    let title = suggested_title(commits);
+
+    // Listed types first, then the rest as they appear
+    let mut order: Vec<&String> = types.iter().filter(|t| stats.types.iter().any(|(s, _)| s == *t)).collect();
+    for (_, p) in &parsed {
+        if let Some(ty) = &p.commit_type {
+            if !order.contains(&ty) {
+                order.push(ty);
+            }
+        }
+    }
+
+    let short = |entry: &LogEntry| entry.sha[..entry.sha.len().min(7)].to_string();
+    let mut sections: Vec<String> = Vec::new();
+    let breaking: Vec<String> = parsed
+        .iter()
+        .filter_map(|(entry, p)| {
+            let description = p.breaking.clone().or_else(|| p.bang.then(|| p.subject.clone()))?;
+            Some(format!("- {} ({})", description, short(entry)))
+        })
+        .collect();
+    if !breaking.is_empty() {
+        sections.push(format!("## Breaking Changes\n\n{}", breaking.join("\n")));
+    }
+    for ty in order {
+        let items: Vec<String> = parsed
+            .iter()
+            .filter(|(_, p)| p.commit_type.as_ref() == Some(ty))
+            .map(|(entry, p)| match &p.scope {
+                Some(scope) => format!("- **{}:** {} ({})", scope, p.subject, short(entry)),
+                None => format!("- {} ({})", p.subject, short(entry)),
+            })
+            .collect();
+        sections.push(format!("## {}\n\n{}", heading(ty), items.join("\n")));
+    }
+    let other: Vec<String> = parsed
+        .iter()
+        .filter(|(_, p)| p.commit_type.is_none())
+        .map(|(entry, _)| format!("- {} ({})", header(entry), short(entry)))
+        .collect();
+    if !other.is_empty() {
+        sections.push(format!("## Other\n\n{}", other.join("\n")));
+    }
+
+    PullRequest { title, body: sections.join("\n\n") + "\n" }
+}
+
+// One message for squash-merging the branch: the suggested title, each
+// commit's header as a bullet with its body indented below, every breaking
+// change, and the trailers without duplicates
+pub fn squash_message(commits: &[LogEntry]) -> String {
+    let header = suggested_title(commits);
+    let title = parse_message(&header);
+    let mut body = Vec::new();
+    let mut breaking = Vec::new();
+    let mut footers: Vec<String> = Vec::new();
+    for entry in commits {
+        let parsed = parse_message(&entry.message);
+        body.push(format!("* {}", entry.message.lines().next().unwrap_or_default()));
+        body.extend(parsed.body.lines().map(|line| if line.is_empty() { String::new() } else { format!("  {}", line) }));
+        breaking.extend(parsed.breaking.or_else(|| parsed.bang.then(|| parsed.subject.clone())));
+        for footer in parsed.footers {
+            if !footers.contains(&footer) {
+                footers.push(footer);
+            }
+        }
+    }
+    let message = CommitMessage {
+        commit_type: title.commit_type,
+        scope: title.scope,
+        subject: title.subject,
+        body: body.join("\n"),
+        breaking: (!breaking.is_empty()).then(|| breaking.join("; ")),
+        bang: false,
+        footers,
+    };
+    match message.commit_type {
+        Some(_) => message.format(),
+        None => format!("{}\n\n{}", header, message.format()), // No conventional commits to take a type from
+    }
+}
@@ -0,0 +1,280 @@
+use crate::config::{self, Config};
+use crate::git::{
+    conflicted_files, git_dir, is_detached_head, staged_changes, staged_diff, staged_size, Identity, StagedChange,
+};
+use crate::i18n::{t, tf};
+use glob::Pattern;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Repository states worth a warning before starting a commit
+#[derive(Debug, Clone, PartialEq)]
+pub enum RepoWarning {
+    DetachedHead,
+    Merge,
+    Rebase,
+    CherryPick,
+    Revert,
+    Conflicts(Vec<String>),
+}
+
+impl RepoWarning {
+    pub fn describe(&self) -> String {
+        match self {
+            RepoWarning::DetachedHead => t("preflight.detached_head").to_string(),
+            RepoWarning::Merge => t("preflight.merge").to_string(),
+            RepoWarning::Rebase => t("preflight.rebase").to_string(),
+            RepoWarning::CherryPick => t("preflight.cherry_pick").to_string(),
+            RepoWarning::Revert => t("preflight.revert").to_string(),
+            RepoWarning::Conflicts(files) => tf("preflight.conflicts", &[("files", &files.join(", "))]),
+        }
+    }
+}
+
+pub fn check_repo() -> Vec<RepoWarning> {
+    let mut warnings = Vec::new();
+    if is_detached_head() {
+        warnings.push(RepoWarning::DetachedHead);
+    }
+    if let Some(dir) = git_dir() {
+        if dir.join("MERGE_HEAD").exists() {
+            warnings.push(RepoWarning::Merge);
+        }
+        if dir.join("rebase-merge").exists() || dir.join("rebase-apply").exists() {
+            warnings.push(RepoWarning::Rebase);
+        }
+        if dir.join("CHERRY_PICK_HEAD").exists() {
+            warnings.push(RepoWarning::CherryPick);
+        }
+        if dir.join("REVERT_HEAD").exists() {
+            warnings.push(RepoWarning::Revert);
+        }
+    }
+    let conflicts = conflicted_files();
+    if !conflicts.is_empty() {
+        warnings.push(RepoWarning::Conflicts(conflicts));
+    }
+    warnings
+}
+
+// The message git prepared for an in-progress merge/cherry-pick/revert, without comments
+pub fn merge_message(comment_char: char) -> Option<String> {
+    prepared_message(&git_dir()?, comment_char)
+}
+
+// MERGE_MSG is only git's while one of those is under way: a stale one, left by
+// an aborted or hand-finished operation, isn't picked up
+fn prepared_message(git_dir: &Path, comment_char: char) -> Option<String> {
+    if !["MERGE_HEAD", "CHERRY_PICK_HEAD", "REVERT_HEAD"].iter().any(|head| git_dir.join(head).exists()) {
+        return None;
+    }
+    let content = fs::read_to_string(git_dir.join("MERGE_MSG")).ok()?;
+    let message = content
+        .lines()
+        .filter(|line| !line.starts_with(comment_char))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let message = message.trim();
+    (!message.is_empty()).then(|| message.to_string())
+}
+
+// The `protected_branches` pattern `branch` matches, if any
+pub fn protected_branch(config: &Config, branch: &str) -> Option<String> {
+    let patterns = config.protected_branches.clone().unwrap_or_else(config::default_protected_branches);
+    patterns.into_iter().find(|pattern| Pattern::new(pattern).is_ok_and(|glob| glob.matches(branch)))
+}
+
+// Why a commit signed with `gpg.format = ssh` would fail once git runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum SigningProblem {
+    NoSshKey,
+    SshKeyMissing(String),
+}
+
+impl SigningProblem {
+    pub fn describe(&self) -> String {
+        match self {
+            SigningProblem::NoSshKey => t("preflight.ssh_no_key").to_string(),
+            SigningProblem::SshKeyMissing(path) => tf("preflight.ssh_key_missing", &[("path", path)]),
+        }
+    }
+}
+
+// SSH signing needs `user.signingkey` (a key file, or the public key itself
+// as "key::ssh-..." with the private half in ssh-agent) or a command that
+// picks one. OpenPGP and X.509 keys live in their own keyrings, so those
+// aren't checked.
+pub fn check_signing(identity: &Identity) -> Option<SigningProblem> {
+    if identity.signing_format != "ssh" {
+        return None;
+    }
+    let Some(key) = &identity.signing_key else {
+        return identity.ssh_key_command.is_none().then_some(SigningProblem::NoSshKey);
+    };
+    if key.starts_with("key::") || key.starts_with("ssh-") {
+        return None;
+    }
+    let path = match key.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir().map_or_else(|| PathBuf::from(key), |home| home.join(rest)),
+        None => PathBuf::from(key),
+    };
+    (!path.exists()).then(|| SigningProblem::SshKeyMissing(key.clone()))
+}
+
+// Staged content that probably shouldn't be committed
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentWarning {
+    BlockedPath { path: String, pattern: String },
+    TooLarge { path: String, size_kb: u64 },
+    Secret { path: String, line: usize, pattern: String },
+    TooBig { files: usize, lines: usize },
+}
+
+impl ContentWarning {
+    pub fn describe(&self) -> String {
+        match self {
+            ContentWarning::BlockedPath { path, pattern } => {
+                tf("preflight.blocked_path", &[("path", path), ("pattern", pattern)])
+            }
+            ContentWarning::TooLarge { path, size_kb } => {
+                tf("preflight.too_large", &[("path", path), ("size", &size_kb.to_string())])
+            }
+            ContentWarning::Secret { path, line, pattern } => {
+                tf("preflight.secret", &[("path", path), ("line", &line.to_string()), ("pattern", pattern)])
+            }
+            ContentWarning::TooBig { files, lines } => {
+                tf("preflight.too_big", &[("files", &files.to_string()), ("lines", &lines.to_string())])
+            }
+        }
+    }
+}
+
+// A `blocked_paths` entry against a repo-relative path: "dir/" matches a
+// directory anywhere, a pattern with a slash the whole path, anything else
+// the file name
+pub fn path_blocked(pattern: &str, path: &str) -> bool {
+    let (dirs, name) = path.rsplit_once('/').unwrap_or(("", path));
+    if let Some(dir) = pattern.strip_suffix('/') {
+        let Ok(dir) = Pattern::new(dir) else { return false };
+        return !dirs.is_empty() && dirs.split('/').any(|d| dir.matches(d));
+    }
+    let Ok(glob) = Pattern::new(pattern) else { return false };
+    if pattern.contains('/') {
+        glob.matches(path)
+    } else {
+        glob.matches(name)
+    }
+}
+
+// Added lines of a `--unified=0` diff matching any of `patterns`, as (path, line, pattern)
+pub fn find_secrets(diff: &str, patterns: &[Regex]) -> Vec<(String, usize, String)> {
+    let mut found = Vec::new();
+    let mut path = String::new();
+    let mut line = 0;
+    for text in diff.lines() {
+        if let Some(new) = text.strip_prefix("+++ ") {
+            path = new.strip_prefix("b/").unwrap_or(new).to_string();
+        } else if let Some(header) = text.strip_prefix("@@ ") {
+            // @@ -a,b +c,d @@: added lines start at c
+            line = header
+                .split(' ')
+                .find_map(|part| part.strip_prefix('+'))
+                .and_then(|range| range.split(',').next()?.parse().ok())
+                .unwrap_or(0);
+        } else if let Some(added) = text.strip_prefix('+') {
+            if let Some(pattern) = patterns.iter().find(|p| p.is_match(added)) {
+                found.push((path.clone(), line, pattern.as_str().to_string()));
+            }
+            line += 1;
+        }
+    }
+    found
+}
+
+// Files and changed lines (added plus removed) when either is over its
+// `max_commit_files`/`max_commit_lines` limit
+pub fn commit_too_big(changes: &[StagedChange], config: &Config) -> Option<ContentWarning> {
+    let max_files = config.max_commit_files.unwrap_or_else(config::default_max_commit_files);
+    let max_lines = config.max_commit_lines.unwrap_or_else(config::default_max_commit_lines);
+    let files = changes.len();
+    let lines = changes.iter().map(|c| c.added.unwrap_or(0) + c.removed.unwrap_or(0)).sum();
+    let over = |count: usize, max: usize| max > 0 && count > max;
+    (over(files, max_files) || over(lines, max_lines)).then_some(ContentWarning::TooBig { files, lines })
+}
+
+// The configured content checks over what's staged (deleted files are fine to commit)
+pub fn check_staged_content(config: &Config) -> Vec<ContentWarning> {
+    let mut warnings = Vec::new();
+    let Ok(changes) = staged_changes() else { return warnings };
+    let blocked = config.blocked_paths.clone().unwrap_or_else(config::default_blocked_paths);
+    let max_kb = config.max_file_size_kb.unwrap_or_else(config::default_max_file_size_kb);
+    if config.block_large_commits.unwrap_or_else(config::default_block_large_commits) {
+        warnings.extend(commit_too_big(&changes, config));
+    }
+    for change in changes.iter().filter(|c| c.status != 'D') {
+        if let Some(pattern) = blocked.iter().find(|p| path_blocked(p, &change.path)) {
+            warnings.push(ContentWarning::BlockedPath { path: change.path.clone(), pattern: pattern.clone() });
+        }
+        if max_kb > 0 {
+            let size_kb = staged_size(&change.path).unwrap_or(0) / 1024;
+            if size_kb > max_kb {
+                warnings.push(ContentWarning::TooLarge { path: change.path.clone(), size_kb });
+            }
+        }
+    }
+
+    // Bad patterns were already reported with the config problems
+    let patterns: Vec<Regex> = config
+        .secret_patterns
+        .clone()
+        .unwrap_or_else(config::default_secret_patterns)
+        .iter()
+        .filter_map(|p| Regex::new(p).ok())
+        .collect();
+    if !patterns.is_empty() {
+        let diff = staged_diff().unwrap_or_default();
+        for (path, line, pattern) in find_secrets(&diff, &patterns) {
+            warnings.push(ContentWarning::Secret { path, line, pattern });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commits_over_the_size_limits_are_flagged() {
+        let change = |path: &str, added| StagedChange { status: 'M', path: path.into(), added: Some(added), removed: Some(1) };
+        let staged = vec![change("a.rs", 10), change("b.rs", 20), change("logo.png", 0)];
+
+        let config = Config { max_commit_files: Some(2), ..Config::default() };
+        assert_eq!(commit_too_big(&staged, &config), Some(ContentWarning::TooBig { files: 3, lines: 33 }));
+        let config = Config { max_commit_files: Some(0), max_commit_lines: Some(30), ..Config::default() };
+        assert!(commit_too_big(&staged, &config).is_some());
+        assert_eq!(commit_too_big(&staged, &Config::default()), None);
+    }
+
+    #[test]
+    fn merge_msg_counts_only_while_an_operation_is_under_way() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("MERGE_MSG"), "Merge branch 'topic'\n\n# Conflicts:\n#\tsrc/app.rs\n").unwrap();
+        assert_eq!(prepared_message(dir.path(), '#'), None);
+        for head in ["MERGE_HEAD", "CHERRY_PICK_HEAD", "REVERT_HEAD"] {
+            fs::write(dir.path().join(head), "0123456789abcdef\n").unwrap();
+            assert_eq!(prepared_message(dir.path(), '#').as_deref(), Some("Merge branch 'topic'"));
+            fs::remove_file(dir.path().join(head)).unwrap();
+        }
+    }
+
+    #[test]
+    fn protected_branches_match_by_glob() {
+        let config = Config { protected_branches: Some(vec!["main".into(), "release/*".into()]), ..Config::default() };
+        assert_eq!(protected_branch(&config, "main").as_deref(), Some("main"));
+        assert_eq!(protected_branch(&config, "release/1.4").as_deref(), Some("release/*"));
+        assert_eq!(protected_branch(&config, "feat/main-menu"), None);
+        assert_eq!(protected_branch(&Config::default(), "main"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::config::{self, Config};
+use crate::message::CommitMessage;
+use regex::Regex;
+use std::sync::OnceLock;
+
+fn version_regex() -> &'static Regex {
+    static VERSION: OnceLock<Regex> = OnceLock::new();
+    VERSION.get_or_init(|| Regex::new(r"\bv?(\d+)\.(\d+)\.(\d+)([-+][0-9A-Za-z.-]+)?\b").unwrap())
+}
+
+// Whether committing `message` should offer a tag (see `release_types`)
+pub fn is_release(message: &CommitMessage, config: &Config) -> bool {
+    let Some(ty) = &message.commit_type else { return false };
+    let scoped = message.scope.as_ref().map(|scope| format!("{}({})", ty, scope));
+    config
+        .release_types
+        .clone()
+        .unwrap_or_else(config::default_release_types)
+        .iter()
+        .any(|t| t == ty || Some(t) == scoped.as_ref())
+}
+
+// Tag name for a release commit: the version in the subject if there is one
+// (`chore(release): v1.2.0`), otherwise the latest tag bumped by what the
+// message says changed (major if breaking, minor for feat, patch otherwise).
+// Before 1.0 a breaking change only bumps the minor version, and a pre-release
+// tag (`v1.3.0-rc.1`) is followed by the version it led up to.
+pub fn suggested_tag(message: &CommitMessage, latest: Option<&str>) -> String {
+    if let Some(found) = version_regex().find(&message.subject) {
+        let version = found.as_str();
+        return if version.starts_with('v') { version.to_string() } else { format!("v{}", version) };
+    }
+
+    let Some(caps) = latest.and_then(|tag| version_regex().captures(tag)) else {
+        return "v0.1.0".to_string();
+    };
+    let part = |i: usize| caps[i].parse::<u64>().unwrap_or(0);
+    let (major, minor, patch) = (part(1), part(2), part(3));
+    if caps.get(4).is_some_and(|suffix| suffix.as_str().starts_with('-')) {
+        return format!("v{}.{}.{}", major, minor, patch);
+    }
+    let breaking = message.breaking.is_some() || message.bang;
+    if breaking && major > 0 {
+        format!("v{}.0.0", major + 1)
+    } else if breaking || message.commit_type.as_deref() == Some("feat") {
+        format!("v{}.{}.0", major, minor + 1)
+    } else {
+        format!("v{}.{}.{}", major, minor, patch + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(commit_type: &str, subject: &str, breaking: bool) -> CommitMessage {
+        CommitMessage {
+            commit_type: Some(commit_type.into()),
+            subject: subject.into(),
+            breaking: breaking.then(|| "drops the v1 API".into()),
+            ..CommitMessage::default()
+        }
+    }
+
+    #[test]
+    fn the_latest_tag_is_bumped_by_what_changed() {
+        let fix = message("fix", "handle empty input", false);
+        let feat = message("feat", "add export", false);
+        let breaking = message("feat", "replace the API", true);
+        assert_eq!(suggested_tag(&fix, Some("v1.2.3")), "v1.2.4");
+        assert_eq!(suggested_tag(&feat, Some("v1.2.3")), "v1.3.0");
+        assert_eq!(suggested_tag(&breaking, Some("1.2.3")), "v2.0.0");
+        assert_eq!(suggested_tag(&CommitMessage { bang: true, ..fix.clone() }, Some("v1.2.3")), "v2.0.0");
+        assert_eq!(suggested_tag(&fix, None), "v0.1.0");
+        assert_eq!(suggested_tag(&message("chore", "release 2.0.0-beta.1", false), Some("v1.2.3")), "v2.0.0-beta.1");
+    }
+
+    #[test]
+    fn zero_versions_and_pre_releases() {
+        // Before 1.0 the minor version is the one that signals breaking changes
+        assert_eq!(suggested_tag(&message("feat", "replace the API", true), Some("v0.4.2")), "v0.5.0");
+        assert_eq!(suggested_tag(&message("fix", "handle empty input", false), Some("v0.4.2")), "v0.4.3");
+        // A release candidate is followed by the release itself
+        assert_eq!(suggested_tag(&message("fix", "handle empty input", false), Some("v1.3.0-rc.1")), "v1.3.0");
+        assert_eq!(suggested_tag(&message("feat", "add export", true), Some("v2.0.0-rc.2")), "v2.0.0");
+        // Build metadata isn't a pre-release
+        assert_eq!(suggested_tag(&message("fix", "handle empty input", false), Some("v1.3.0+build.7")), "v1.3.1");
+    }
+}
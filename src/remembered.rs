@@ -0,0 +1,165 @@
+use crate::git::{current_branch, git_dir};
+use crate::state::Prefill;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Per-repository lists under .git/commitui/, one entry per line: never
+// committed, and apart from the hand-written config
+fn path(name: &str) -> Option<PathBuf> {
+    Some(git_dir()?.join("commitui").join(name))
+}
+
+fn read(name: &str) -> Vec<String> {
+    let Some(content) = path(name).and_then(|path| fs::read_to_string(path).ok()) else { return Vec::new() };
+    content.lines().map(str::trim).filter(|line| !line.is_empty()).map(str::to_string).collect()
+}
+
+fn write(name: &str, entries: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    save(name, entries.iter().map(|entry| format!("{}\n", entry)).collect())
+}
+
+// The per-branch issues, draft and selection are kept as JSON instead
+fn write_json(name: &str, value: &impl Serialize) -> Result<(), Box<dyn std::error::Error>> {
+    save(name, serde_json::to_string_pretty(value)? + "\n")
+}
+
+fn save(name: &str, content: String) -> Result<(), Box<dyn std::error::Error>> {
+    let path = path(name).ok_or("not inside a git repository")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(&path, content)?;
+    Ok(())
+}
+
+// Custom scopes the user asked to keep (Ctrl+K on the scope input)
+pub fn load() -> Vec<String> {
+    read("scopes")
+}
+
+// Add `scope` to the kept ones (once)
+pub fn remember(scope: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let mut scopes = load();
+    if scopes.iter().any(|s| s == scope) {
+        return Ok(());
+    }
+    scopes.push(scope.to_string());
+    write("scopes", &scopes)
+}
+
+// Scopes pinned to the top of the list (p on the scope list), in pinning order
+pub fn pinned() -> Vec<String> {
+    read("pinned")
+}
+
+// Pin `scope`, or unpin it if it was; returns the new pins
+pub fn toggle_pin(scope: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut pins = pinned();
+    match pins.iter().position(|s| s == scope) {
+        Some(i) => {
+            pins.remove(i);
+        }
+        None => pins.push(scope.to_string()),
+    }
+    write("pinned", &pins)?;
+    Ok(pins)
+}
+
+// Issue references last committed on each branch, to start the next commit there with
+fn branch_issues() -> BTreeMap<String, String> {
+    path("branch-issues.json")
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub fn issues_for(branch: &str) -> Option<String> {
+    branch_issues().remove(branch)
+}
+
+// An empty `issues` keeps what was there: one commit without a ticket doesn't
+// mean the branch has none
+pub fn remember_issues(branch: &str, issues: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let issues = issues.trim();
+    let mut all = branch_issues();
+    if issues.is_empty() || all.get(branch).is_some_and(|known| known == issues) {
+        return Ok(());
+    }
+    all.insert(branch.to_string(), issues.to_string());
+    write_json("branch-issues.json", &all)
+}
+
+// The answers of a session that was locked for being idle or killed, with when
+// (seconds since the epoch) and on which branch
+#[derive(Serialize, Deserialize)]
+pub struct Draft {
+    pub saved_at: u64,
+    pub branch: Option<String>,
+    pub answers: Prefill,
+}
+
+impl Draft {
+    pub fn age(&self) -> Duration {
+        Duration::from_secs(now().saturating_sub(self.saved_at))
+    }
+}
+
+// Picked up by the next session, which asks before resuming it
+pub fn save_draft(answers: &Prefill) -> Result<(), Box<dyn std::error::Error>> {
+    let draft = Draft { saved_at: now(), branch: current_branch(), answers: answers.clone() };
+    write_json("draft.json", &draft)
+}
+
+// The saved draft, which is used up by reading it
+pub fn take_draft() -> Option<Draft> {
+    let path = path("draft.json")?;
+    let draft = fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok());
+    let _ = fs::remove_file(&path);
+    draft
+}
+
+// The session carried on after all
+pub fn clear_draft() {
+    if let Some(path) = path("draft.json") {
+        let _ = fs::remove_file(path);
+    }
+}
+
+// Type and scope of a wizard that was quit, with when (seconds since the epoch)
+#[derive(Serialize, Deserialize)]
+struct Selection {
+    saved_at: u64,
+    commit_type: Option<String>,
+    scope: Option<String>,
+    #[serde(default)]
+    bang: bool,
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |since| since.as_secs())
+}
+
+// Keep the selections of a wizard that was just quit (lighter than a draft:
+// what was typed isn't kept)
+pub fn save_selection(answers: &Prefill) -> Result<(), Box<dyn std::error::Error>> {
+    if answers.commit_type.is_none() && answers.scope.is_none() {
+        return Ok(());
+    }
+    let selection = Selection {
+        saved_at: now(),
+        commit_type: answers.commit_type.clone(),
+        scope: answers.scope.clone(),
+        bang: answers.bang,
+    };
+    write_json("selection.json", &selection)
+}
+
+// The kept selections if they're younger than `window`; used up by reading them
+pub fn take_selection(window: Duration) -> Option<Prefill> {
+    let path = path("selection.json")?;
+    let selection: Option<Selection> = fs::read_to_string(&path).ok().and_then(|content| serde_json::from_str(&content).ok());
+    let _ = fs::remove_file(&path);
+    let selection = selection.filter(|s| !window.is_zero() && now().saturating_sub(s.saved_at) <= window.as_secs())?;
+    Some(Prefill { commit_type: selection.commit_type, scope: selection.scope, bang: selection.bang, ..Prefill::default() })
+}
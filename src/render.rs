@@ -0,0 +1,47 @@
+use crate::app::App;
+use crate::steps;
+use commitui::i18n::t;
+use commitui::state::Step;
+use ratatui::{
+    Frame,
+    text::Line,
+    style::{Style, Color},
+    layout::Rect,
+};
+
+// Which screens the wizard draws: one per step with the breadcrumb on top (the
+// default), or `layout = "form"` with every field at once. Either way `App::draw`
+// adds the overlays (jobs, notices, popups) on top.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum View {
+    Steps,
+    Form, // The preview is still its own step
+}
+
+impl View {
+    // Where the wizard is now
+    pub fn of(app: &App) -> Self {
+        if app.in_form() { View::Form } else { View::Steps }
+    }
+
+    // The top line: where the user is, or what the keys do
+    pub fn header(self, app: &App) -> Line<'static> {
+        match self {
+            View::Steps => app.breadcrumb(),
+            View::Form => Line::styled(t("hints.form"), Style::default().fg(Color::Cyan)),
+        }
+    }
+
+    // Everything below it
+    pub fn draw(self, app: &App, f: &mut Frame, area: Rect) {
+        match (self, &app.state.step) {
+            (View::Form, _) => steps::form::draw(app, f, area),
+            (View::Steps, Step::Type) => steps::commit_type::draw(app, f, area),
+            (View::Steps, Step::Scope) => steps::scope::draw(app, f, area),
+            (View::Steps, Step::Subject) => steps::subject::draw(app, f, area),
+            (View::Steps, Step::Body) => steps::body::draw(app, f, area),
+            (View::Steps, Step::Breaking) => steps::breaking::draw(app, f, area),
+            (View::Steps, Step::Preview) => steps::preview::draw(app, f, area),
+        }
+    }
+}
@@ -0,0 +1,65 @@
+use commitui::config::Config;
+use commitui::git::{commit_message, rev_parse};
+use commitui::parser::parse_message;
+use commitui::state::{AppState, Step};
+use commitui::validation::ValidationError;
+use crate::app::App;
+use crate::reword::lint;
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{backend::Backend, Terminal};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// The commit on the preview step in review mode: its short hash for the title,
+// its message as written, and every rule that message breaks, listed under it
+pub struct Review {
+    pub sha: String,
+    pub message: String,
+    pub issues: Vec<ValidationError>,
+}
+
+// `commiTUI show <rev>`: someone's commit message through the preview, checked
+// against the current rules; nothing can be edited or committed
+pub fn review_commit(config: &Config, rev: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let sha = rev_parse(rev).map_err(|_| format!("Unknown commit '{}'.", rev))?;
+    let message = commit_message(&sha)?;
+    let mut terminal = setup_terminal()?;
+    let result = review_screen(&mut terminal, &mut CrosstermEvents, config, &sha, &message);
+    restore_terminal(&mut terminal)?;
+    result
+}
+
+pub fn review_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    config: &Config,
+    sha: &str,
+    message: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut state = AppState::new(config, parse_message(message).to_prefill(true));
+    state.step = Step::Preview;
+    let mut app = App::new(config, &mut state, None, &[]);
+    app.review = Some(Review {
+        sha: sha[..sha.len().min(7)].to_string(),
+        message: message.to_string(),
+        issues: lint(message, config),
+    });
+
+    loop {
+        terminal.draw(|f| {
+            app.draw(f);
+            degrade(f);
+        })?;
+
+        // Keys never reach the wizard: only leaving is possible
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Enter | KeyCode::Char('q') => return Ok(()),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(()),
+                _ => {}
+            }
+        }
+    }
+}
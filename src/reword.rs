@@ -0,0 +1,200 @@
+use commitui::config::Config;
+use commitui::git::{diff_stat, range_commits, range_merges, rev_parse, rewrite_messages, LogEntry};
+use commitui::i18n::{t, tf};
+use commitui::message::CommitMessage;
+use commitui::parser::parse_message;
+use commitui::state::AppState;
+use commitui::validation::{validate_message, ValidationError};
+use crate::tui::{build_message, degrade, poll_event, restore_terminal, run_wizard, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// `commiTUI reword <base>..HEAD`: review each commit in turn, then rewrite the
+// branch once at the end (nothing changes if the user backs out).
+pub fn reword_range(config: &mut Config, range: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (commits, stats) = load_range(range)?;
+    let mut messages: Vec<String> = commits.iter().map(|c| c.message.clone()).collect();
+
+    let mut terminal = setup_terminal()?;
+    let result = reword_screen(&mut terminal, &mut CrosstermEvents, config, &commits, &stats, &mut messages);
+    restore_terminal(&mut terminal)?;
+    if !result? {
+        eprintln!("Aborted.");
+        std::process::exit(1);
+    }
+
+    match rewrite(&commits, &messages)? {
+        Some(summary) => println!("{}", summary),
+        None => println!("No messages changed."),
+    }
+    Ok(())
+}
+
+// The same review from the preview step (R), on the terminal the wizard owns.
+// Returns what happened, for the wizard's notice.
+pub fn reword_from_wizard<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    config: &mut Config,
+    range: &str,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let (commits, stats) = load_range(range)?;
+    let mut messages: Vec<String> = commits.iter().map(|c| c.message.clone()).collect();
+    let approved = reword_screen(terminal, events, config, &commits, &stats, &mut messages)?;
+    terminal.clear()?;
+    if !approved {
+        return Ok(None);
+    }
+    rewrite(&commits, &messages)
+}
+
+// Commits in `<base>..HEAD` (or a bare base) with their diff stats
+fn load_range(range: &str) -> Result<(Vec<LogEntry>, Vec<String>), Box<dyn std::error::Error>> {
+    let range = match range.split_once("..") {
+        Some((_, tip)) => {
+            // Only the current branch can be rewritten in place
+            if !tip.is_empty() && rev_parse(tip)? != rev_parse("HEAD")? {
+                return Err(format!("The range must end at HEAD (got '{}').", tip).into());
+            }
+            range.to_string()
+        }
+        None => format!("{}..HEAD", range),
+    };
+    if !range_merges(&range)?.is_empty() {
+        return Err(format!("{} contains merge commits, which can't be reworded.", range).into());
+    }
+    let commits = range_commits(&range)?;
+    if commits.is_empty() {
+        return Err(format!("No commits in {}.", range).into());
+    }
+    let stats: Vec<String> = commits.iter().map(|c| diff_stat(&c.sha).unwrap_or_default()).collect();
+    Ok((commits, stats))
+}
+
+// Rewrite the branch with the edited messages; None if nothing changed
+fn rewrite(commits: &[LogEntry], messages: &[String]) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let changed = commits.iter().zip(messages).filter(|(c, m)| c.message.trim() != m.trim()).count();
+    if changed == 0 {
+        return Ok(None);
+    }
+    let head = rewrite_messages(commits, messages)?;
+    Ok(Some(format!("Reworded {} commit(s); HEAD is now {}.", changed, &head[..head.len().min(7)])))
+}
+
+// How many commits between `base` and HEAD break the rules; None if `base`
+// doesn't exist (no remote yet, a different default branch, ...)
+pub fn count_violations(base: &str, config: &Config) -> Option<usize> {
+    let commits = range_commits(&format!("{}..HEAD", base)).ok()?;
+    Some(commits.iter().filter(|c| !lint(&c.message, config).is_empty()).count())
+}
+
+// Rule violations for a raw message; non-conventional headers fail the type check
+pub fn lint(message: &str, config: &Config) -> Vec<ValidationError> {
+    let prefill = parse_message(message).to_prefill(true);
+    validate_message(&CommitMessage::from_prefill(&prefill), config)
+}
+
+// One commit at a time: approve it as is, or fix it in the wizard.
+// `messages` holds the edited messages; returns false if the user aborted.
+pub fn reword_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    config: &mut Config,
+    commits: &[LogEntry],
+    stats: &[String],
+    messages: &mut [String],
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut current: usize = 0;
+
+    loop {
+        let entry = &commits[current];
+        let message = &messages[current];
+        let issues = lint(message, config);
+        let edited = entry.message.trim() != message.trim();
+        let issue_height = issues.len().clamp(1, 5) as u16 + 2;
+        let stat_height = stats[current].lines().count().min(10) as u16 + 2;
+
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(5),               // Message
+                    Constraint::Length(issue_height), // Lint results
+                    Constraint::Length(stat_height),  // Diff summary
+                    Constraint::Length(1),            // Key hints
+                ])
+                .split(f.size());
+
+            let mut title = tf("reword.commit", &[
+                ("current", &(current + 1).to_string()),
+                ("total", &commits.len().to_string()),
+                ("sha", &entry.sha[..entry.sha.len().min(7)]),
+            ]);
+            if edited {
+                title.push_str(t("reword.edited"));
+            }
+            let border = if issues.is_empty() { Color::Green } else { Color::Yellow };
+            let paragraph = Paragraph::new(message.as_str())
+                .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(border)))
+                .wrap(Wrap { trim: false });
+            f.render_widget(paragraph, chunks[0]);
+
+            let lines: Vec<Line> = if issues.is_empty() {
+                vec![Line::from(Span::styled(t("reword.clean"), Style::default().fg(Color::Green)))]
+            } else {
+                issues
+                    .iter()
+                    .map(|issue| Line::from(Span::styled(
+                        format!("{}: {}", issue.field, issue.message),
+                        Style::default().fg(Color::Red),
+                    )))
+                    .collect()
+            };
+            let lint_block = Paragraph::new(lines).block(Block::default().title(t("reword.lint")).borders(Borders::ALL));
+            f.render_widget(lint_block, chunks[1]);
+
+            let stat = Paragraph::new(stats[current].as_str())
+                .block(Block::default().title(t("reword.changes")).borders(Borders::ALL))
+                .style(Style::default().add_modifier(Modifier::DIM));
+            f.render_widget(stat, chunks[2]);
+
+            let hints = Paragraph::new(t("hints.reword"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[3]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(false),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(false),
+                KeyCode::Enter | KeyCode::Char('a') => {
+                    if current + 1 == commits.len() {
+                        return Ok(true);
+                    }
+                    current += 1;
+                }
+                KeyCode::Char('b') | KeyCode::Left => current = current.saturating_sub(1),
+                KeyCode::Char('e') => {
+                    // Start from the message as it stands; quitting the wizard keeps it
+                    let mut state = AppState::new(config, parse_message(&messages[current]).to_prefill(true));
+                    if run_wizard(terminal, events, config, &mut state, None, &[])? {
+                        messages[current] = build_message(&state, config).trim_end().to_string();
+                    }
+                    terminal.clear()?;
+                }
+                _ => {}
+            }
+        }
+    }
+}
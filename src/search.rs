@@ -0,0 +1,93 @@
+use crate::parser::ParsedCommit;
+
+// What `commiTUI search` filters on: `type:`/`t:` and `scope:`/`s:` words narrow
+// it to exact types and scopes (any of several), everything else is fuzzy text
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Query {
+    pub types: Vec<String>,
+    pub scopes: Vec<String>,
+    pub words: Vec<String>,
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Self {
+        let mut query = Query::default();
+        for word in input.split_whitespace() {
+            match word.split_once(':') {
+                Some(("type" | "t", value)) if !value.is_empty() => query.types.push(value.to_lowercase()),
+                Some(("scope" | "s", value)) if !value.is_empty() => query.scopes.push(value.to_lowercase()),
+                _ => query.words.push(word.to_lowercase()),
+            }
+        }
+        query
+    }
+
+    // None when `commit` doesn't match; otherwise higher is a better match
+    pub fn score(&self, commit: &ParsedCommit) -> Option<i64> {
+        let matches = |wanted: &[String], value: &Option<String>| {
+            wanted.is_empty() || value.as_ref().is_some_and(|value| wanted.contains(&value.to_lowercase()))
+        };
+        if !matches(&self.types, &commit.commit_type) || !matches(&self.scopes, &commit.scope) {
+            return None;
+        }
+        // The header counts double: that's what a commit is remembered by
+        let header = format!(
+            "{} {} {}",
+            commit.commit_type.as_deref().unwrap_or_default(),
+            commit.scope.as_deref().unwrap_or_default(),
+            commit.subject,
+        )
+        .to_lowercase();
+        let rest = format!("{}\n{}", commit.body, commit.footers.join("\n")).to_lowercase();
+        self.words.iter().try_fold(0, |total, word| {
+            let score = fuzzy_score(word, &header).map(|score| score * 2).max(fuzzy_score(word, &rest))?;
+            Some(total + score)
+        })
+    }
+}
+
+// `needle`'s characters in order somewhere in `haystack` (both lowercase).
+// Runs of consecutive characters and matches at word starts score extra, so
+// "lgn" finds "login" but "login" itself ranks first. Each place the first
+// character occurs is tried, keeping the best.
+pub fn fuzzy_score(needle: &str, haystack: &str) -> Option<i64> {
+    let chars: Vec<char> = haystack.chars().collect();
+    let needle: Vec<char> = needle.chars().collect();
+    let first = *needle.first()?;
+    (0..chars.len())
+        .filter(|&start| chars[start] == first)
+        .filter_map(|start| score_from(&needle, &chars, start))
+        .max()
+}
+
+// Greedy match of `needle` with its first character at `start`
+fn score_from(needle: &[char], chars: &[char], start: usize) -> Option<i64> {
+    let mut score = 0;
+    let mut from = start;
+    let mut previous: Option<usize> = None;
+    for wanted in needle {
+        let at = from + chars[from..].iter().position(|c| c == wanted)?;
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == at) {
+            score += 3;
+        }
+        if at == 0 || !chars[at - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous = Some(at);
+        from = at + 1;
+    }
+    Some(score)
+}
+
+// Indices of the commits matching `query`, best first; equally good ones keep
+// their `git log` order (newest first)
+pub fn search(query: &Query, commits: &[ParsedCommit]) -> Vec<usize> {
+    let mut scored: Vec<(usize, i64)> = commits
+        .iter()
+        .enumerate()
+        .filter_map(|(i, commit)| query.score(commit).map(|score| (i, score)))
+        .collect();
+    scored.sort_by_key(|(i, score)| (-score, *i));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
@@ -0,0 +1,119 @@
+use commitui::clipboard;
+use commitui::git::{range_commits, rev_parse};
+use commitui::i18n::{t, tf};
+use commitui::pr::squash_message;
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Style, Color, Modifier},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// `commiTUI squash`: one message for the whole branch, pruned line by line,
+// printed (or copied) for GitHub's squash-merge box
+pub fn squash(base: &str, copy: bool) -> Result<(), Box<dyn std::error::Error>> {
+    if rev_parse(base).is_err() {
+        return Err(format!("Base branch '{}' not found; pass --base or set base_branch.", base).into());
+    }
+    let commits = range_commits(&format!("{}..HEAD", base))?;
+    if commits.is_empty() {
+        return Err(format!("No commits between {} and HEAD.", base).into());
+    }
+    let message = squash_message(&commits);
+    let lines: Vec<&str> = message.lines().collect();
+
+    let mut terminal = setup_terminal()?;
+    let result = prune_screen(&mut terminal, &mut CrosstermEvents, &lines);
+    restore_terminal(&mut terminal)?;
+    let Some(kept) = result? else {
+        eprintln!("Aborted.");
+        std::process::exit(1);
+    };
+
+    let text = pruned(&lines, &kept);
+    if copy {
+        clipboard::copy(&text)?;
+        eprintln!("Copied the squash message to the clipboard.");
+    } else {
+        print!("{}", text);
+    }
+    Ok(())
+}
+
+// The kept lines, without the blank lines that pruning left doubled or trailing
+pub fn pruned(lines: &[&str], kept: &[bool]) -> String {
+    let mut text: Vec<&str> = Vec::new();
+    for (line, _) in lines.iter().zip(kept).filter(|(_, keep)| **keep) {
+        if line.is_empty() && text.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        text.push(line);
+    }
+    while text.last().is_some_and(|last| last.is_empty()) {
+        text.pop();
+    }
+    text.join("\n") + "\n"
+}
+
+// Every line of the message, each of which can be dropped (the header stays).
+// Returns which lines to keep, or None if the user aborted.
+pub fn prune_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    lines: &[&str],
+) -> Result<Option<Vec<bool>>, Box<dyn std::error::Error>> {
+    let mut kept = vec![true; lines.len()];
+    let mut current: usize = 0;
+
+    loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),    // Message lines
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let items: Vec<ListItem> = lines
+                .iter()
+                .zip(&kept)
+                .map(|(line, keep)| {
+                    let style = if *keep { Style::default() } else { Style::default().fg(Color::DarkGray).add_modifier(Modifier::CROSSED_OUT) };
+                    ListItem::new(line.to_string()).style(style)
+                })
+                .collect();
+            let dropped = kept.iter().filter(|keep| !**keep).count();
+            let mut list_state = ListState::default();
+            list_state.select(Some(current));
+            let list = List::new(items)
+                .block(Block::default().title(tf("titles.squash", &[("dropped", &dropped.to_string())])).borders(Borders::ALL))
+                .highlight_style(Style::default().bg(Color::Blue))
+                .highlight_symbol(">> ");
+            f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+            let hints = Paragraph::new(t("hints.squash"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[1]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Down => current = (current + 1).min(lines.len() - 1),
+                KeyCode::Up => current = current.saturating_sub(1),
+                KeyCode::Char(' ') | KeyCode::Char('d') if current > 0 => kept[current] = !kept[current],
+                KeyCode::Enter => return Ok(Some(kept)),
+                _ => {}
+            }
+        }
+    }
+}
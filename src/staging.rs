@@ -0,0 +1,127 @@
+use commitui::git::{apply_cached, unstaged_diff, untracked_diff};
+use commitui::hunks::{build_patch, parse_diff, FileDiff};
+use commitui::i18n::{t, tf};
+use crate::tui::{degrade, poll_event, restore_terminal, setup_terminal, CrosstermEvents, EventSource};
+use ratatui::{
+    backend::Backend,
+    Terminal,
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+    style::{Style, Color},
+    layout::{Layout, Constraint, Direction},
+};
+use crossterm::event::{Event, KeyCode, KeyEventKind, KeyModifiers};
+
+// `commiTUI --patch`: pick unstaged hunks to add before the wizard starts;
+// untracked files are offered whole, marked as new. Returns false if the user aborted.
+pub fn stage_hunks() -> Result<bool, Box<dyn std::error::Error>> {
+    let files = parse_diff(&(unstaged_diff()? + &untracked_diff()?));
+    if files.is_empty() {
+        return Ok(true); // Nothing unstaged to pick from
+    }
+    let mut terminal = setup_terminal()?;
+    let result = hunk_screen(&mut terminal, &mut CrosstermEvents, &files);
+    restore_terminal(&mut terminal)?;
+    let Some(selected) = result? else { return Ok(false) };
+
+    let patch = build_patch(&files, &selected);
+    if !patch.is_empty() {
+        apply_cached(&patch)?;
+    }
+    Ok(true)
+}
+
+// Every hunk of every file in one list, the current one shown in full below.
+// Returns which hunks to stage, per file, or None if the user aborted.
+pub fn hunk_screen<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    files: &[FileDiff],
+) -> Result<Option<Vec<Vec<bool>>>, Box<dyn std::error::Error>> {
+    // (file, hunk) for each row of the list
+    let rows: Vec<(usize, usize)> = files
+        .iter()
+        .enumerate()
+        .flat_map(|(f, file)| (0..file.hunks.len()).map(move |h| (f, h)))
+        .collect();
+    let mut selected: Vec<Vec<bool>> = files.iter().map(|file| vec![false; file.hunks.len()]).collect();
+    let mut current: usize = 0;
+
+    loop {
+        let (file, hunk) = rows[current];
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Percentage(40), // Hunk list
+                    Constraint::Min(3),         // Current hunk
+                    Constraint::Length(1),      // Key hints
+                ])
+                .split(f.size());
+
+            let items: Vec<ListItem> = rows
+                .iter()
+                .map(|&(f, h)| {
+                    let hunk = &files[f].hunks[h];
+                    let mark = if selected[f][h] { "[x]" } else { "[ ]" };
+                    let new_file = if files[f].new_file { t("text.new_file") } else { "" };
+                    ListItem::new(format!(
+                        "{} {}{}  {}  +{} -{}",
+                        mark, files[f].path, new_file, hunk.range(), hunk.added, hunk.removed
+                    ))
+                })
+                .collect();
+            let count = selected.iter().flatten().filter(|s| **s).count();
+            let title = tf("titles.hunks", &[("count", &count.to_string()), ("total", &rows.len().to_string())]);
+            let mut list_state = ListState::default();
+            list_state.select(Some(current));
+            let list = List::new(items)
+                .block(Block::default().title(title).borders(Borders::ALL))
+                .highlight_style(Style::default().bg(Color::Blue))
+                .highlight_symbol(">> ");
+            f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+            let lines: Vec<Line> = files[file].hunks[hunk]
+                .text
+                .lines()
+                .map(|line| {
+                    let color = match line.chars().next() {
+                        Some('+') => Color::Green,
+                        Some('-') => Color::Red,
+                        Some('@') => Color::Cyan,
+                        _ => Color::Reset,
+                    };
+                    Line::from(Span::styled(line, Style::default().fg(color)))
+                })
+                .collect();
+            let hunk_view = Paragraph::new(lines)
+                .block(Block::default().title(files[file].path.as_str()).borders(Borders::ALL));
+            f.render_widget(hunk_view, chunks[1]);
+
+            let hints = Paragraph::new(t("hints.stage"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[2]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(terminal, events)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(None),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(None),
+                KeyCode::Down => current = (current + 1).min(rows.len() - 1),
+                KeyCode::Up => current = current.saturating_sub(1),
+                KeyCode::Char(' ') => selected[file][hunk] = !selected[file][hunk],
+                // The whole file: all on unless it already was
+                KeyCode::Char('a') => {
+                    let all = selected[file].iter().all(|s| *s);
+                    selected[file].iter_mut().for_each(|s| *s = !all);
+                }
+                KeyCode::Enter => return Ok(Some(selected)),
+                _ => {}
+            }
+        }
+    }
+}
@@ -1,4 +1,11 @@
-#[derive(Debug, Clone, PartialEq)]
+use crate::ai::AiSuggestion;
+use crate::config::{Config, PanePosition, ScopeEntry};
+use crate::remembered;
+use crate::validation::{validate_breaking, validate_scope, validate_subject};
+use serde::{Deserialize, Serialize};
+
+// Declared in wizard order, so steps compare by position
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub enum Step {
     Type,
     Scope,
@@ -8,6 +15,167 @@ pub enum Step {
     Preview,
 }
 
+impl Step {
+    // The name used for this step in config files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Step::Type => "type",
+            Step::Scope => "scope",
+            Step::Subject => "subject",
+            Step::Body => "body",
+            Step::Breaking => "breaking",
+            Step::Preview => "preview",
+        }
+    }
+
+    // Listed in `hidden_steps`: the wizard passes through without asking
+    pub fn is_hidden(&self, config: &Config) -> bool {
+        config.hidden_steps.as_ref().is_some_and(|steps| steps.iter().any(|s| s == self.name()))
+    }
+
+    fn next(&self) -> Step {
+        match self {
+            Step::Type => Step::Scope,
+            Step::Scope => Step::Subject,
+            Step::Subject => Step::Body,
+            Step::Body => Step::Breaking,
+            Step::Breaking | Step::Preview => Step::Preview,
+        }
+    }
+
+    fn previous(&self) -> Step {
+        match self {
+            Step::Type | Step::Scope => Step::Type,
+            Step::Subject => Step::Scope,
+            Step::Body => Step::Subject,
+            Step::Breaking => Step::Body,
+            Step::Preview => Step::Breaking,
+        }
+    }
+}
+
+// The fields of the single-screen form (`layout = "form"`), in Tab order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FormField {
+    Type,
+    Scope,
+    Subject,
+    Body,
+    Breaking,
+    Issues,
+}
+
+impl FormField {
+    pub const ALL: [FormField; 6] = [
+        FormField::Type,
+        FormField::Scope,
+        FormField::Subject,
+        FormField::Body,
+        FormField::Breaking,
+        FormField::Issues,
+    ];
+
+    // The wizard step asking the same question; None for the preview's issues
+    pub fn step(self) -> Option<Step> {
+        match self {
+            FormField::Type => Some(Step::Type),
+            FormField::Scope => Some(Step::Scope),
+            FormField::Subject => Some(Step::Subject),
+            FormField::Body => Some(Step::Body),
+            FormField::Breaking => Some(Step::Breaking),
+            FormField::Issues => None,
+        }
+    }
+
+    pub fn from_step(step: &Step) -> FormField {
+        match step {
+            Step::Type => FormField::Type,
+            Step::Scope => FormField::Scope,
+            Step::Subject => FormField::Subject,
+            Step::Body => FormField::Body,
+            Step::Breaking => FormField::Breaking,
+            Step::Preview => FormField::Issues,
+        }
+    }
+
+    // Fields of hidden steps aren't shown
+    pub fn is_hidden(self, config: &Config) -> bool {
+        self.step().is_some_and(|step| step.is_hidden(config))
+    }
+}
+
+// Jira smart-commit inputs, in the order the preview steps through them
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SmartCommit {
+    pub ticket: String,
+    pub time: String,
+    pub comment: String,
+}
+
+impl SmartCommit {
+    pub const FIELDS: usize = 3;
+
+    pub fn field(&self, index: usize) -> &String {
+        match index {
+            0 => &self.ticket,
+            1 => &self.time,
+            _ => &self.comment,
+        }
+    }
+
+    pub fn field_mut(&mut self, index: usize) -> &mut String {
+        match index {
+            0 => &mut self.ticket,
+            1 => &mut self.time,
+            _ => &mut self.comment,
+        }
+    }
+
+    // `PROJ-123 #time 2h #comment fixed race`; nothing without a ticket
+    pub fn footer(&self) -> Option<String> {
+        let ticket = self.ticket.trim();
+        if ticket.is_empty() {
+            return None;
+        }
+        let mut footer = ticket.to_string();
+        if !self.time.trim().is_empty() {
+            footer.push_str(&format!(" #time {}", self.time.trim()));
+        }
+        if !self.comment.trim().is_empty() {
+            footer.push_str(&format!(" #comment {}", self.comment.trim()));
+        }
+        Some(footer)
+    }
+}
+
+// Values the wizard starts with (from the branch name, a template subcommand, ...)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Prefill {
+    pub commit_type: Option<String>,
+    pub scope: Option<String>,
+    pub subject: Option<String>,
+    pub body: Option<String>,
+    pub breaking: Option<String>,
+    pub issue: Option<String>,
+    #[serde(default)]
+    pub bang: bool, // `type!:`, kept when rewording or reusing such a header
+}
+
+impl Prefill {
+    // Fields set in `self` win; anything missing falls back to `other`
+    pub fn or(self, other: Prefill) -> Prefill {
+        Prefill {
+            commit_type: self.commit_type.or(other.commit_type),
+            scope: self.scope.or(other.scope),
+            subject: self.subject.or(other.subject),
+            body: self.body.or(other.body),
+            breaking: self.breaking.or(other.breaking),
+            issue: self.issue.or(other.issue),
+            bang: self.bang || other.bang,
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppState {
     pub step: Step,
@@ -20,13 +188,298 @@ pub struct AppState {
     pub chosen_scope: Option<String>,
 
     pub subject: String,
+    pub suggestions: Vec<String>, // Subject candidates; the popup is open while non-empty
+    pub selected_suggestion: usize,
 
     pub body: String,
     pub body_lines: Vec<String>,
     pub in_body: bool,
 
+    pub has_breaking: bool, // Answered yes to "Are there breaking changes?"
     pub breaking: String,
+    pub bang: bool, // `type!:` in the header, toggled with `!` on the type step and preview
 
     pub issues: String,
     pub focus_issues: bool,
-}
\ No newline at end of file
+    pub selected_keyword: usize, // In the issue keyword popup, open while a keyword is being typed
+    pub smart_commit: SmartCommit,
+    pub smart_commit_field: Option<usize>, // The smart-commit input being typed into, if any
+    pub override_field: Option<usize>, // The --author (0) or --date (1) input being typed into, if any
+    pub return_to_preview: bool, // Set while editing a step picked from the preview
+
+    pub live_preview: bool, // The Ctrl+P message pane is open
+    pub input_rejected: bool, // The last key was refused by an input filter
+
+    pub ai_suggestion: Option<Result<AiSuggestion, String>>, // Overlay on Subject/Body while Some
+
+    pub commit_error: Option<String>, // git's output from the last failed commit, shown on Preview
+    pub head_message: Option<String>, // HEAD's message, compared with the preview while Some
+    pub copy_requested: bool, // Confirmed with `c`: copy the message instead of committing
+    pub pinned_scopes: Vec<String>, // Listed first on the Scope step (p toggles)
+    pub draft_restored: bool, // Pre-filled from the draft a locked or killed session left; said once
+    pub form_field: FormField, // The field being typed into with `layout = "form"`
+    pub list_filter: Option<String>, // Typed after `/` in a compact type or scope list
+}
+
+impl AppState {
+    pub fn new(config: &Config, defaults: Prefill) -> Self {
+        let mut state = AppState {
+            step: Step::Type,
+            selected_type: 0,
+            chosen_type: None,
+
+            selected_scope: 0,
+            custom_scope: String::new(),
+            focus_input: false, // For custom scope input
+            chosen_scope: None,
+
+            subject: String::new(),
+            suggestions: vec![],
+            selected_suggestion: 0,
+
+            body: String::new(),
+            body_lines: vec![],
+            in_body: false, // Special flag for multi-line body
+
+            has_breaking: false,
+            breaking: String::new(),
+            bang: false,
+
+            issues: String::new(),
+            focus_issues: false, // Specific for issues field in preview
+            selected_keyword: 0,
+            smart_commit: SmartCommit::default(),
+            smart_commit_field: None,
+            override_field: None,
+            return_to_preview: false,
+
+            live_preview: config.preview_pane.unwrap_or_else(crate::config::default_preview_pane) != PanePosition::Hidden,
+            input_rejected: false,
+
+            ai_suggestion: None,
+
+            commit_error: None,
+            head_message: None,
+            copy_requested: false,
+            pinned_scopes: remembered::pinned(),
+            draft_restored: false,
+            form_field: FormField::Type,
+            list_filter: None,
+        };
+        state.apply_prefill(config, defaults);
+        state.pass_hidden_steps(config, true);
+        state
+    }
+
+    // Everything typed so far, as the answers to start the next session with
+    pub fn draft(&self) -> Prefill {
+        let mut body = self.body_lines.clone();
+        if !self.body.is_empty() {
+            body.push(self.body.clone());
+        }
+        let text = |value: &str| Some(value.to_string()).filter(|value| !value.trim().is_empty());
+        Prefill {
+            commit_type: self.chosen_type.clone(),
+            scope: self.chosen_scope.clone().or_else(|| text(&self.custom_scope)),
+            subject: text(&self.subject),
+            body: text(&body.join("\n")),
+            breaking: text(&self.breaking).filter(|_| self.has_breaking),
+            issue: text(&self.issues),
+            bang: self.bang,
+        }
+    }
+
+    // Move off hidden steps in the direction the user was going, giving each
+    // the answer it has when not asked: the first configured type, no scope,
+    // no breaking change (a hidden body keeps whatever was pre-filled).
+    pub fn pass_hidden_steps(&mut self, config: &Config, mut forward: bool) {
+        if !self.step.is_hidden(config) {
+            return;
+        }
+        while self.step.is_hidden(config) {
+            match self.step {
+                Step::Type => {
+                    self.chosen_type = config.types.as_ref().and_then(|types| types.first().cloned());
+                    forward = true; // Nothing before it to go back to
+                }
+                Step::Scope => {
+                    self.chosen_scope = None;
+                    self.custom_scope.clear();
+                }
+                Step::Breaking => {
+                    self.has_breaking = false;
+                    self.breaking.clear();
+                }
+                Step::Body => {}
+                Step::Subject | Step::Preview => return, // Always asked
+            }
+            self.step = if forward { self.step.next() } else { self.step.previous() };
+        }
+        self.focus_step(config);
+    }
+
+    // The scope picker rows, with the pinned scopes moved up right after the
+    // first one ("no scope") and any group they emptied left out
+    pub fn scope_entries(&self, config: &Config) -> Vec<ScopeEntry> {
+        let entries = config.scope_entries();
+        let Some((first, rest)) = entries.split_first() else { return entries };
+        let pinned: Vec<ScopeEntry> = self.pinned_scopes
+            .iter()
+            .map(|scope| ScopeEntry::Scope(scope.clone()))
+            .filter(|pin| rest.contains(pin))
+            .collect();
+        let rest: Vec<&ScopeEntry> = rest.iter().filter(|e| !pinned.contains(e)).collect();
+        let rest = rest.iter().enumerate().filter(|(i, e)| {
+            !matches!(e, ScopeEntry::Header(_)) || matches!(rest.get(i + 1), Some(ScopeEntry::Scope(_)))
+        });
+        std::iter::once(first.clone()).chain(pinned).chain(rest.map(|(_, e)| (*e).clone())).collect()
+    }
+
+    // Focus as if the current step had been reached normally
+    fn focus_step(&mut self, config: &Config) {
+        match self.step {
+            Step::Scope => {
+                let allow_custom = config.allow_custom_scope.unwrap_or_else(crate::config::default_allow_custom_scope);
+                self.focus_input = allow_custom && (!self.custom_scope.is_empty() || self.scope_entries(config).is_empty());
+            }
+            Step::Preview => self.focus_issues = false,
+            Step::Type => {}
+            _ => self.focus_input = true,
+        }
+    }
+
+    // After the config was reloaded mid-wizard: keep every answer that still
+    // passes and send the user back to the first answered step that doesn't.
+    pub fn revalidate(&mut self, config: &Config) {
+        let types_slice = config.types.as_deref().unwrap_or(&[]);
+        let scope_entries = self.scope_entries(config);
+
+        // The lists may have shrunk or been reordered under the selections
+        self.selected_type = self.chosen_type.as_ref()
+            .and_then(|ty| types_slice.iter().position(|t| t == ty))
+            .unwrap_or(self.selected_type.min(types_slice.len().saturating_sub(1)));
+        self.selected_scope = self.chosen_scope.as_deref()
+            .and_then(|scope| scope_entries.iter().position(|e| e.scope() == Some(scope)))
+            .unwrap_or(self.selected_scope.min(scope_entries.len().saturating_sub(1)));
+
+        let invalid = if self.chosen_type.as_ref().is_some_and(|ty| !types_slice.contains(ty)) {
+            self.chosen_type = None;
+            Some(Step::Type)
+        } else if validate_scope(self.chosen_type.as_deref(), self.chosen_scope.as_deref(), config).is_some() {
+            // Offer the rejected scope for editing rather than dropping it
+            if let Some(scope) = self.chosen_scope.take() {
+                if !scope_entries.iter().any(|e| e.scope() == Some(scope.as_str())) {
+                    self.custom_scope = scope;
+                }
+            }
+            Some(Step::Scope)
+        } else if validate_subject(self.chosen_type.as_deref(), &self.subject, config).is_some() {
+            Some(Step::Subject)
+        } else if self.has_breaking && validate_breaking(&self.breaking, config).is_some() {
+            Some(Step::Breaking)
+        } else {
+            None
+        };
+
+        // Only answers already given count; the current step is checked on Enter anyway
+        let answered = |step: &Step| *step < self.step || (self.return_to_preview && *step != self.step);
+        if let Some(step) = invalid.filter(answered) {
+            self.return_to_preview |= self.step == Step::Preview;
+            self.step = step;
+            self.focus_step(config);
+        }
+        if self.step.is_hidden(config) {
+            self.pass_hidden_steps(config, true);
+        }
+    }
+
+    // Defaults only pre-select; every step is still confirmed by the user.
+    // Fields the prefill doesn't set are left as they are.
+    pub fn apply_prefill(&mut self, config: &Config, prefill: Prefill) {
+        let types_slice = config.types.as_deref().unwrap_or(&[]);
+        let scope_entries = self.scope_entries(config);
+
+        if let Some(idx) = prefill.commit_type.and_then(|ty| types_slice.iter().position(|t| *t == ty)) {
+            self.selected_type = idx;
+        }
+        if let Some(scope) = prefill.scope {
+            match scope_entries.iter().position(|e| e.scope() == Some(scope.as_str())) {
+                Some(idx) => {
+                    self.selected_scope = idx;
+                    self.custom_scope.clear();
+                }
+                None => self.custom_scope = scope, // Not in the list, offer it as a custom scope
+            }
+        }
+        if let Some(subject) = prefill.subject {
+            self.subject = subject;
+        }
+        if let Some(body) = prefill.body {
+            self.body_lines = body.lines().map(|l| l.to_string()).collect();
+            self.body.clear();
+        }
+        self.bang |= prefill.bang;
+        if let Some(breaking) = prefill.breaking {
+            self.has_breaking = !breaking.trim().is_empty();
+            self.breaking = breaking;
+        }
+        if let Some(issue) = prefill.issue {
+            self.issues = issue;
+        }
+    }
+
+    // Move past every leading step that already has an acceptable answer, so
+    // scripted answers only leave the remaining questions (or just the preview).
+    pub fn skip_answered(&mut self, config: &Config, answers: &Prefill) {
+        self.skip_answered_steps(config, answers);
+        self.pass_hidden_steps(config, true);
+    }
+
+    fn skip_answered_steps(&mut self, config: &Config, answers: &Prefill) {
+        let types_slice = config.types.as_deref().unwrap_or(&[]);
+
+        match &answers.commit_type {
+            Some(ty) if types_slice.contains(ty) => self.chosen_type = Some(ty.clone()),
+            _ if Step::Type.is_hidden(config) => {} // Already the first type
+            _ => return,
+        }
+        self.step = Step::Scope;
+        self.focus_input = !self.custom_scope.is_empty()
+            && config.allow_custom_scope.unwrap_or_else(crate::config::default_allow_custom_scope);
+
+        // Hidden steps count as answered
+        let scope = match &answers.scope {
+            _ if Step::Scope.is_hidden(config) => String::new(),
+            Some(scope) => scope.clone(),
+            None => return,
+        };
+        let scope = Some(scope.trim().to_string()).filter(|s| !s.is_empty());
+        if validate_scope(self.chosen_type.as_deref(), scope.as_deref(), config).is_some() {
+            return;
+        }
+        self.chosen_scope = scope;
+        self.step = Step::Subject;
+        self.focus_input = true;
+
+        if answers.subject.is_none() || validate_subject(self.chosen_type.as_deref(), &self.subject, config).is_some() {
+            return;
+        }
+        self.step = Step::Body;
+        self.in_body = false;
+
+        if answers.body.is_none() && !Step::Body.is_hidden(config) {
+            return;
+        }
+        self.step = Step::Breaking;
+
+        if answers.breaking.is_none() && !Step::Breaking.is_hidden(config) {
+            return;
+        }
+        if self.has_breaking && validate_breaking(&self.breaking, config).is_some() {
+            self.focus_input = true;
+            return;
+        }
+        self.step = Step::Preview;
+        self.focus_issues = false;
+    }
+}
@@ -0,0 +1,82 @@
+use crate::git::LogEntry;
+use crate::parser::parse_message;
+use std::collections::HashMap;
+
+// Aggregates for `commiTUI stats`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CommitStats {
+    pub total: usize,
+    pub conventional: usize,
+    pub types: Vec<(String, u64)>,  // Most used first
+    pub scopes: Vec<(String, u64)>, // Most used first
+    pub average_subject_length: f64, // Of the whole header line, as git shows it
+}
+
+impl CommitStats {
+    pub fn from_log(entries: &[LogEntry]) -> Self {
+        let mut types: HashMap<String, u64> = HashMap::new();
+        let mut scopes: HashMap<String, u64> = HashMap::new();
+        let mut conventional = 0;
+        let mut header_chars = 0;
+
+        for entry in entries {
+            header_chars += entry.message.lines().next().unwrap_or_default().chars().count();
+            let parsed = parse_message(&entry.message);
+            let Some(ty) = parsed.commit_type else { continue };
+            conventional += 1;
+            *types.entry(ty).or_default() += 1;
+            if let Some(scope) = parsed.scope {
+                *scopes.entry(scope).or_default() += 1;
+            }
+        }
+
+        CommitStats {
+            total: entries.len(),
+            conventional,
+            types: ranked(types),
+            scopes: ranked(scopes),
+            average_subject_length: if entries.is_empty() { 0.0 } else { header_chars as f64 / entries.len() as f64 },
+        }
+    }
+
+    // Share of conventional commits, 0-100
+    pub fn compliance(&self) -> u16 {
+        if self.total == 0 {
+            return 0;
+        }
+        (self.conventional * 100 / self.total) as u16
+    }
+}
+
+// Highest count first; ties alphabetically so the order is stable
+fn ranked(counts: HashMap<String, u64>) -> Vec<(String, u64)> {
+    let mut ranked: Vec<(String, u64)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn non_conventional_commits_count_only_towards_the_total() {
+        let entry = |message: &str| LogEntry { sha: "0123456".into(), message: message.into() };
+        let stats = CommitStats::from_log(&[
+            entry("feat(api): add export"),
+            entry("fix(api): handle empty input\n\nBody."),
+            entry("fix: typo"),
+            entry("Update README"),
+            entry("Merge branch 'topic'"),
+        ]);
+        assert_eq!((stats.total, stats.conventional), (5, 3));
+        assert_eq!(stats.compliance(), 60);
+        assert_eq!(stats.types, [("fix".to_string(), 2), ("feat".to_string(), 1)]);
+        assert_eq!(stats.scopes, [("api".to_string(), 2)]);
+        // Headers only: "feat(api): add export" is 21 characters
+        let headers = [21, 28, 9, 13, 20];
+        assert_eq!(stats.average_subject_length, headers.iter().sum::<usize>() as f64 / 5.0);
+
+        assert_eq!(CommitStats::from_log(&[]).compliance(), 0);
+    }
+}
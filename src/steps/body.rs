@@ -0,0 +1,221 @@
+use crate::app::{Action, App};
+use crate::steps;
+use commitui::config;
+use commitui::state::{AppState, Step};
+use commitui::i18n::{t, tf};
+use commitui::validation::body_line_warning;
+use ratatui::{
+    Frame,
+    widgets::{Block, Paragraph, Wrap},
+    style::{Style, Color},
+    layout::Rect,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// Leave the body empty and move on
+pub fn skip(app: &mut App) -> Action {
+    let state = &mut *app.state;
+    state.body_lines.clear();
+    state.body.clear();
+    state.step = Step::Breaking;
+    state.focus_input = true; // Start breaking changes input focused
+    Action::Continue
+}
+
+// Start an empty body from the chosen type's template; its last line stays
+// open for typing, so e.g. a trailing "- " leaves the cursor after the bullet
+pub fn apply_template(app: &mut App) {
+    let state = &mut *app.state;
+    if !state.body_lines.is_empty() {
+        return;
+    }
+    let template = state.chosen_type.as_ref()
+        .and_then(|ty| app.config.body_template.as_ref()?.get(ty));
+    if let Some(template) = template {
+        let mut lines: Vec<String> = template.split('\n').map(|l| l.to_string()).collect();
+        state.body = lines.pop().unwrap_or_default();
+        state.body_lines = lines;
+    }
+}
+
+const BULLET: &str = "- ";
+
+// Ctrl+L: make the line a `- ` list item, or a plain line again (indentation stays)
+pub fn toggle_bullet(line: &mut String) {
+    let indent = line.len() - line.trim_start().len();
+    if line[indent..].starts_with(BULLET) {
+        line.replace_range(indent..indent + BULLET.len(), "");
+    } else {
+        line.insert_str(indent, BULLET);
+    }
+}
+
+// Enter on a list item: the line to start the next one with. An item left
+// empty ends the list instead, so a second Enter finishes the body as usual.
+fn next_line(line: &mut String) -> String {
+    let indent = line.len() - line.trim_start().len();
+    if !line[indent..].starts_with(BULLET) {
+        return String::new();
+    }
+    if line[indent..].trim().len() == BULLET.trim().len() {
+        line.clear();
+        return String::new();
+    }
+    format!("{}{}", &line[..indent], BULLET)
+}
+
+// Finish the (non-empty) line being typed and start the next
+pub fn new_line(body_lines: &mut Vec<String>, line: &mut String) {
+    let next = next_line(line);
+    if !line.is_empty() {
+        body_lines.push(std::mem::replace(line, next));
+    }
+}
+
+// Ctrl+W: rewrap the paragraph being typed (the lines since the last blank
+// one) to `width` columns, like vim's `gq`. Each list item is wrapped on its
+// own, its continuation lines lined up under the text after the bullet; a
+// word longer than `width` (a URL, say) gets a line to itself.
+pub fn reflow(body_lines: &mut Vec<String>, line: &mut String, width: usize) {
+    let start = body_lines.iter().rposition(|l| l.trim().is_empty()).map_or(0, |i| i + 1);
+    let mut paragraph: Vec<String> = body_lines.drain(start..).collect();
+    paragraph.push(std::mem::take(line));
+
+    // (first line's prefix, the following lines' prefix, words)
+    let mut items: Vec<(String, String, Vec<&str>)> = Vec::new();
+    for text in &paragraph {
+        let indent = &text[..text.len() - text.trim_start().len()];
+        let rest = &text[indent.len()..];
+        match (rest.strip_prefix(BULLET), items.last_mut()) {
+            (None, Some((_, _, words))) => words.extend(rest.split_whitespace()),
+            (None, None) => items.push((indent.to_string(), indent.to_string(), rest.split_whitespace().collect())),
+            (Some(item), _) => items.push((
+                format!("{}{}", indent, BULLET),
+                format!("{}{}", indent, " ".repeat(BULLET.len())),
+                item.split_whitespace().collect(),
+            )),
+        }
+    }
+
+    let mut lines = Vec::new();
+    for (first, hanging, words) in items {
+        let mut current = first;
+        let mut started = false;
+        for word in words {
+            if started && current.chars().count() + 1 + word.chars().count() > width {
+                lines.push(std::mem::replace(&mut current, hanging.clone()));
+                started = false;
+            }
+            if started {
+                current.push(' ');
+            }
+            current.push_str(word);
+            started = true;
+        }
+        lines.push(current);
+    }
+    *line = lines.pop().unwrap_or_default();
+    body_lines.extend(lines);
+}
+
+// Ctrl+W with the configured width
+pub fn reflow_body(config: &config::Config, state: &mut AppState) {
+    let width = config.body_max_line_length.unwrap_or_else(config::default_body_max_line_length);
+    reflow(&mut state.body_lines, &mut state.body, width);
+}
+
+// The first overlong line, with the key that fixes it
+pub fn line_warning(app: &App) -> Option<String> {
+    let state = &*app.state;
+    let body: Vec<&str> = state.body_lines.iter().chain([&state.body]).map(String::as_str).collect();
+    body_line_warning(&body.join("\n"), app.config).map(|msg| format!("{}{}", msg, t("text.reflow")))
+}
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    let state = &*app.state;
+    let block = if state.focus_input {
+        Block::default()
+            .title(tf("titles.body_editing", &[("skip", app.skip_hint())]))
+            .borders(steps::borders(app))
+            .border_style(steps::input_border(app, Color::Green))
+    } else {
+        Block::default()
+            .title(tf("titles.body", &[("skip", app.skip_hint())]))
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(Color::Green))
+    };
+    let body_text = if state.body_lines.is_empty() && state.body.is_empty() {
+        t("text.empty").to_string()
+    } else {
+        let mut all = state.body_lines.join("\n");
+        if !state.body.is_empty() {
+            if !all.is_empty() {
+                all.push('\n');
+            }
+            all.push_str(&state.body);
+        }
+        all
+    };
+    let paragraph = Paragraph::new(body_text.as_str()) // Use as_str() here
+        .block(block)
+        .style(Style::default().fg(Color::Yellow))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+
+    if let Some(warning) = line_warning(app) {
+        let warn = Paragraph::new(warning)
+            .block(Block::default().borders(steps::borders(app)).title(t("titles.warning")))
+            .style(Style::default().fg(Color::Yellow));
+        let height = steps::box_height(app, 1);
+        let warn_area = Rect {
+            x: area.x,
+            y: area.y + area.height.saturating_sub(height),
+            width: area.width,
+            height,
+        };
+        f.render_widget(warn, warn_area);
+    }
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    let state = &mut *app.state;
+    // `q` for quit is handled globally
+    if state.focus_input { // Body input focused
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = false; // Switch to navigation mode for body
+            }
+            KeyCode::Enter if state.body.is_empty() => {
+                state.step = Step::Breaking;
+                state.focus_input = true; // Start breaking changes input focused
+            }
+            KeyCode::Enter => new_line(&mut state.body_lines, &mut state.body),
+            KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => toggle_bullet(&mut state.body),
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => reflow_body(app.config, state),
+            KeyCode::Char(c) => {
+                state.input_rejected = !steps::type_char(app.config, "body", &mut state.body, c);
+            }
+            KeyCode::Backspace => {
+                state.body.pop();
+            }
+            _ => {}
+        }
+    } else { // Navigation mode for body
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = true; // Switch to body input
+            }
+            KeyCode::Char('b') | KeyCode::Left => {
+                state.step = Step::Subject;
+                state.focus_input = true; // Return to subject input focus
+            }
+            KeyCode::Enter => {
+                // If enter is pressed in nav mode, it should still move forward.
+                state.step = Step::Breaking;
+                state.focus_input = true;
+            }
+            _ => {}
+        }
+    }
+    Action::Continue
+}
@@ -0,0 +1,128 @@
+use crate::app::{Action, App};
+use crate::steps;
+use commitui::state::Step;
+use commitui::i18n::{t, tf};
+use commitui::validation::validate_breaking;
+use ratatui::{
+    Frame,
+    widgets::{Block, Paragraph},
+    style::{Style, Color},
+    layout::Rect,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+// No breaking change: leave the description empty and move on
+pub fn skip(app: &mut App) -> Action {
+    let state = &mut *app.state;
+    state.has_breaking = false;
+    state.breaking.clear();
+    state.step = Step::Preview;
+    state.focus_issues = false;
+    Action::Continue
+}
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    let state = &*app.state;
+
+    // First the yes/no question; the description input only after a yes
+    if !state.has_breaking {
+        let question = Paragraph::new(t("text.breaking_question"))
+            .block(Block::default()
+                .title(tf("titles.breaking_question", &[("skip", app.skip_hint())]))
+                .borders(steps::borders(app))
+                .border_style(Style::default().fg(Color::Red)))
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(question, area);
+        return;
+    }
+
+    let block = if state.focus_input {
+        Block::default()
+            .title(tf("titles.breaking_editing", &[("skip", app.skip_hint())]))
+            .borders(steps::borders(app))
+            .border_style(steps::input_border(app, Color::Red))
+    } else {
+        Block::default()
+            .title(tf("titles.breaking", &[("skip", app.skip_hint())]))
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(Color::Red))
+    };
+    let paragraph = Paragraph::new(state.breaking.as_str())
+        .block(block)
+        .style(Style::default().fg(Color::Red));
+    f.render_widget(paragraph, area);
+
+    if let Some(msg) = validate_breaking(&state.breaking, app.config) {
+        let warn = Paragraph::new(msg)
+            .block(Block::default().borders(steps::borders(app)).title(t("titles.validation_error")))
+            .style(Style::default().fg(Color::Red));
+        let height = steps::box_height(app, 1);
+        let warn_area = Rect {
+            x: area.x,
+            y: area.y + area.height.saturating_sub(height),
+            width: area.width,
+            height,
+        };
+        f.render_widget(warn, warn_area);
+    }
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    let described = validate_breaking(&app.state.breaking, app.config).is_none();
+    let state = &mut *app.state;
+    // `q` for quit is handled globally
+    if !state.has_breaking { // Yes/no question
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Char('Y') => {
+                state.has_breaking = true;
+                state.focus_input = true; // Straight into the description
+            }
+            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Enter => {
+                return skip(app);
+            }
+            KeyCode::Char('b') | KeyCode::Left => {
+                state.step = Step::Body;
+                state.focus_input = true; // Return to body input focus
+            }
+            _ => {}
+        }
+        return Action::Continue;
+    }
+
+    // A "yes" needs a valid description before moving on
+    if state.focus_input { // Breaking changes input focused
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = false; // Switch to navigation mode for breaking
+            }
+            KeyCode::Enter if described => {
+                state.step = Step::Preview;
+                state.focus_issues = false; // Start preview with issues not focused
+            }
+            KeyCode::Char(c) => {
+                state.input_rejected = !steps::type_char(app.config, "breaking", &mut state.breaking, c);
+            }
+            KeyCode::Backspace => {
+                state.breaking.pop();
+            }
+            _ => {}
+        }
+    } else { // Navigation mode for breaking
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = true; // Switch to breaking changes input
+            }
+            KeyCode::Char('b') | KeyCode::Left => {
+                // Back to the yes/no question
+                state.has_breaking = false;
+                state.breaking.clear();
+            }
+            KeyCode::Enter if described => {
+                state.step = Step::Preview;
+                state.focus_issues = false;
+            }
+            _ => {}
+        }
+    }
+    Action::Continue
+}
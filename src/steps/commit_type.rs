@@ -0,0 +1,100 @@
+use crate::app::{Action, App};
+use commitui::config::{default_allow_custom_scope, Config};
+use commitui::git::recent_commits;
+use commitui::i18n::t;
+use commitui::state::{AppState, Step};
+use crate::history::HISTORY_LIMIT;
+use crate::jobs::JobResult;
+use crate::manage::ManagedList;
+use crate::steps;
+use ratatui::{
+    Frame,
+    widgets::{Block, List, ListItem, ListState},
+    style::{Style, Color},
+    layout::Rect,
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+// Restore selected_type based on chosen_type when navigating back here
+pub fn restore_selection(app: &mut App) {
+    app.state.selected_type = app.config.types.as_ref()
+        .and_then(|types_vec| types_vec.iter().position(|t| Some(t) == app.state.chosen_type.as_ref()))
+        .unwrap_or(0);
+}
+
+// A subject that is empty or still an untouched template follows the type's template
+pub fn follow_subject_template(config: &Config, state: &mut AppState) {
+    if let (Some(templates), Some(ty)) = (config.subject_template.as_ref(), state.chosen_type.as_ref()) {
+        if state.subject.is_empty() || templates.values().any(|t| *t == state.subject) {
+            state.subject = templates.get(ty).cloned().unwrap_or_default();
+        }
+    }
+}
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    // Get types slice, defaulting to empty if config.types is None
+    let types_slice = app.config.types.as_deref().unwrap_or(&[]);
+    let items: Vec<ListItem> = types_slice
+        .iter()
+        .map(|ty| ListItem::new(ty.as_str())) // ty is &String, as_str() makes &str
+        .collect();
+    let names: Vec<Option<&str>> = types_slice.iter().map(|ty| Some(ty.as_str())).collect();
+    let (items, highlight) = steps::filter_rows(app, items, &names, app.state.selected_type);
+    let mut list_state = ListState::default();
+    list_state.select(highlight);
+    let mut title = steps::list_title(app, t("titles.type"));
+    if app.state.bang {
+        title.push_str(t("text.bang_marked"));
+    }
+    let list = List::new(items)
+        .block(Block::default().title(title).borders(steps::borders(app)))
+        .highlight_style(Style::default().bg(Color::Blue))
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, area, &mut list_state);
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    let config = app.config;
+    let names: Vec<Option<&str>> = config.types.iter().flatten().map(|ty| Some(ty.as_str())).collect();
+    if steps::handle_filter_key(app, key, &names, |state| &mut state.selected_type) {
+        return Action::Continue;
+    }
+    let state = &mut *app.state;
+    // Only 'q' quits here, Esc/Ctrl+C are handled globally
+    if key.code == KeyCode::Char('q') && key.modifiers.is_empty() {
+        return Action::Quit;
+    }
+    // Type selection doesn't have a separate "input mode"
+    match key.code {
+        KeyCode::Down => {
+            // Use .map_or(0, |v| v.len()) to get length safely from Option<Vec<String>>
+            let types_len = app.config.types.as_ref().map_or(0, |v| v.len());
+            state.selected_type = (state.selected_type + 1).min(types_len.saturating_sub(1));
+        }
+        KeyCode::Up => {
+            state.selected_type = state.selected_type.saturating_sub(1);
+        }
+        KeyCode::Enter => {
+            // Make sure config.types is Some before indexing
+            if let Some(types_vec) = app.config.types.as_ref() {
+                state.chosen_type = Some(types_vec[state.selected_type].clone());
+            }
+            follow_subject_template(app.config, state);
+            state.step = Step::Scope;
+            // Start scope list focused, unless a custom scope was pre-filled
+            state.focus_input = !state.custom_scope.is_empty()
+                && app.config.allow_custom_scope.unwrap_or_else(default_allow_custom_scope);
+        }
+        // Reuse a previous commit as a template
+        KeyCode::Char('h') => {
+            app.start_job(t("jobs.history"), || {
+                // An empty list if git log fails
+                JobResult::History(recent_commits(HISTORY_LIMIT).unwrap_or_default())
+            });
+        }
+        KeyCode::Char('m') => return Action::Manage(ManagedList::Types),
+        KeyCode::Char('!') => state.bang = !state.bang,
+        _ => {}
+    }
+    Action::Continue
+}
@@ -0,0 +1,257 @@
+use crate::app::{Action, App};
+use crate::steps::{self, body, commit_type, scope};
+use commitui::i18n::t;
+use commitui::state::{AppState, FormField, Step};
+use commitui::validation::{validate_breaking, validate_issues, validate_scope};
+use ratatui::{
+    Frame,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap},
+    style::{Style, Color},
+    layout::{Layout, Constraint, Direction, Rect},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// The fields shown with this config, in Tab order
+fn fields(app: &App) -> Vec<FormField> {
+    FormField::ALL.into_iter().filter(|field| !field.is_hidden(app.config)).collect()
+}
+
+// Give the form what the wizard's pickers would have: the highlighted type,
+// and the listed scope as text. Called whenever the form (re)opens.
+pub fn enter(app: &mut App) {
+    let entries = app.state.scope_entries(app.config);
+    let fields = fields(app);
+    let state = &mut *app.state;
+    if state.chosen_type.is_none() {
+        state.chosen_type = app.config.types.as_ref().and_then(|types| types.get(state.selected_type).cloned());
+        commit_type::follow_subject_template(app.config, state);
+    }
+    if state.custom_scope.is_empty() && state.selected_scope > 0 {
+        state.custom_scope = entries.get(state.selected_scope).and_then(|e| e.scope()).unwrap_or_default().to_string();
+    }
+    if !fields.contains(&state.form_field) {
+        state.form_field = fields[0];
+    }
+    sync(state);
+}
+
+// The answers the preview and validation read, kept in step with the inputs
+fn sync(state: &mut AppState) {
+    state.chosen_scope = Some(state.custom_scope.trim().to_string()).filter(|s| !s.is_empty());
+    state.has_breaking = !state.breaking.trim().is_empty();
+}
+
+// The text keys are typed into (for snippet hints); the type is picked, not typed
+pub fn active_input(state: &AppState) -> Option<&str> {
+    match state.form_field {
+        FormField::Type => None,
+        FormField::Scope => Some(&state.custom_scope),
+        FormField::Subject => Some(&state.subject),
+        FormField::Body => Some(&state.body),
+        FormField::Breaking => Some(&state.breaking),
+        FormField::Issues => Some(&state.issues),
+    }
+}
+
+// Why the field can't be confirmed as it is; an empty breaking change just means none
+fn field_error(app: &App, field: FormField) -> Option<String> {
+    let state = &*app.state;
+    match field {
+        FormField::Type => state.chosen_type.is_none().then(|| t("validation.type_required").to_string()),
+        FormField::Scope => validate_scope(state.chosen_type.as_deref(), state.chosen_scope.as_deref(), app.config),
+        FormField::Subject => app.subject_check().0,
+        FormField::Body => None,
+        FormField::Breaking if !state.has_breaking => None,
+        FormField::Breaking => validate_breaking(&state.breaking, app.config),
+        FormField::Issues => validate_issues(&state.issues, app.config),
+    }
+}
+
+fn title(field: FormField) -> &'static str {
+    match field {
+        FormField::Type => t("form.type"),
+        FormField::Scope => t("form.scope"),
+        FormField::Subject => t("form.subject"),
+        FormField::Body => t("form.body"),
+        FormField::Breaking => t("form.breaking"),
+        FormField::Issues => t("form.issues"),
+    }
+}
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    let state = &*app.state;
+    let fields = fields(app);
+    let mut constraints: Vec<Constraint> = fields
+        .iter()
+        .map(|field| if *field == FormField::Body { Constraint::Min(steps::box_height(app, 1)) } else { Constraint::Length(steps::box_height(app, 1)) })
+        .collect();
+    constraints.push(Constraint::Length(1)); // The focused field's error
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints(constraints)
+        .split(area);
+
+    let body = || {
+        let mut lines = state.body_lines.clone();
+        lines.push(state.body.clone());
+        lines.join("\n")
+    };
+    for (field, &chunk) in fields.iter().zip(chunks.iter()) {
+        let focused = *field == state.form_field;
+        let color = if field_error(app, *field).is_some() {
+            Color::Red
+        } else if focused {
+            Color::Green
+        } else {
+            Color::DarkGray
+        };
+        let border = if focused { steps::input_border(app, color) } else { Style::default().fg(color) };
+        let text = match field {
+            FormField::Type => state.chosen_type.clone().unwrap_or_default(),
+            FormField::Scope => state.custom_scope.clone(),
+            FormField::Subject => state.subject.clone(),
+            FormField::Body => body(),
+            FormField::Breaking => state.breaking.clone(),
+            FormField::Issues => state.issues.clone(),
+        };
+        let paragraph = Paragraph::new(text)
+            .block(Block::default().title(title(*field)).borders(steps::borders(app)).border_style(border))
+            .style(Style::default().fg(Color::Yellow))
+            .wrap(Wrap { trim: false });
+        f.render_widget(paragraph, chunk);
+    }
+
+    if let Some(error) = field_error(app, state.form_field) {
+        f.render_widget(Paragraph::new(error).style(Style::default().fg(Color::Red)), chunks[fields.len()]);
+    } else if let Some(warning) = body::line_warning(app).filter(|_| state.form_field == FormField::Body) {
+        f.render_widget(Paragraph::new(warning).style(Style::default().fg(Color::Yellow)), chunks[fields.len()]);
+    }
+
+    let Some(index) = fields.iter().position(|field| *field == state.form_field) else { return };
+    let chunk = chunks[index];
+    match state.form_field {
+        FormField::Type => draw_type_dropdown(app, f, area, chunk),
+        FormField::Body => {
+            let y = (chunk.y + 1 + state.body_lines.len() as u16).min(chunk.y + chunk.height.saturating_sub(2));
+            f.set_cursor(chunk.x + 1 + state.body.chars().count() as u16, y);
+        }
+        _ => {
+            let text = active_input(state).unwrap_or_default();
+            let x = (chunk.x + 1 + text.chars().count() as u16).min(chunk.x + chunk.width.saturating_sub(2));
+            f.set_cursor(x, chunk.y + 1);
+        }
+    }
+}
+
+// The type list, open under the Type field while it has the focus
+fn draw_type_dropdown(app: &App, f: &mut Frame, area: Rect, field: Rect) {
+    let types = app.config.types.as_deref().unwrap_or(&[]);
+    let top = field.y + field.height;
+    let bottom = area.y + area.height;
+    if types.is_empty() || top >= bottom {
+        return;
+    }
+    let popup = Rect {
+        x: field.x + 1,
+        y: top,
+        width: field.width.saturating_sub(2).min(30),
+        height: (types.len() as u16 + 2).min(bottom - top),
+    };
+    let items: Vec<ListItem> = types.iter().map(|ty| ListItem::new(ty.as_str())).collect();
+    let mut list_state = ListState::default();
+    list_state.select(Some(app.state.selected_type));
+    let list = List::new(items)
+        .block(Block::default().borders(Borders::ALL).border_style(Style::default().fg(Color::Green)))
+        .highlight_style(Style::default().bg(Color::Blue))
+        .highlight_symbol(">> ");
+    f.render_widget(Clear, popup);
+    f.render_stateful_widget(list, popup, &mut list_state);
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    let fields = fields(app);
+    let entries = app.state.scope_entries(app.config);
+    let types_len = app.config.types.as_ref().map_or(0, |types| types.len());
+    let position = fields.iter().position(|field| *field == app.state.form_field).unwrap_or(0);
+    let state = &mut *app.state;
+    match (state.form_field, key.code) {
+        (_, KeyCode::Tab) => state.form_field = fields[(position + 1) % fields.len()],
+        (_, KeyCode::BackTab) => state.form_field = fields[(position + fields.len() - 1) % fields.len()],
+        (FormField::Type, KeyCode::Up | KeyCode::Down) if types_len > 0 => {
+            state.selected_type = if key.code == KeyCode::Up {
+                state.selected_type.saturating_sub(1)
+            } else {
+                (state.selected_type + 1).min(types_len - 1)
+            };
+            state.chosen_type = app.config.types.as_ref().and_then(|types| types.get(state.selected_type).cloned());
+            commit_type::follow_subject_template(app.config, state);
+        }
+        // The listed scopes, "no scope" first, fill the input
+        (FormField::Scope, KeyCode::Up | KeyCode::Down) => {
+            let direction = if key.code == KeyCode::Up { -1 } else { 1 };
+            state.selected_scope = scope::next_selectable_scope(&entries, state.selected_scope, direction);
+            state.custom_scope = match state.selected_scope {
+                0 => String::new(),
+                i => entries.get(i).and_then(|e| e.scope()).unwrap_or_default().to_string(),
+            };
+        }
+        // An empty line stays, between paragraphs
+        (FormField::Body, KeyCode::Enter) if state.body.is_empty() => state.body_lines.push(String::new()),
+        (FormField::Body, KeyCode::Enter) => body::new_line(&mut state.body_lines, &mut state.body),
+        (FormField::Body, KeyCode::Char('l')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            body::toggle_bullet(&mut state.body);
+        }
+        (FormField::Body, KeyCode::Char('w')) if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            body::reflow_body(app.config, state);
+        }
+        // Back into the line above once this one is empty
+        (FormField::Body, KeyCode::Backspace) if state.body.is_empty() => {
+            state.body = state.body_lines.pop().unwrap_or_default();
+        }
+        (_, KeyCode::Enter) => {
+            sync(state);
+            return submit(app, &fields);
+        }
+        (FormField::Type, _) => {}
+        (field, KeyCode::Char(c)) => {
+            let (name, text) = match field {
+                FormField::Scope => ("scope", &mut state.custom_scope),
+                FormField::Subject => ("subject", &mut state.subject),
+                FormField::Body => ("body", &mut state.body),
+                FormField::Breaking => ("breaking", &mut state.breaking),
+                _ => ("issues", &mut state.issues),
+            };
+            state.input_rejected = !steps::type_char(app.config, name, text, c);
+        }
+        (field, KeyCode::Backspace) => {
+            let text = match field {
+                FormField::Scope => &mut state.custom_scope,
+                FormField::Subject => &mut state.subject,
+                FormField::Body => &mut state.body,
+                FormField::Breaking => &mut state.breaking,
+                _ => &mut state.issues,
+            };
+            text.pop();
+        }
+        _ => {}
+    }
+    sync(state);
+    Action::Continue
+}
+
+// Enter: on to the preview, or to the first field that isn't right yet
+fn submit(app: &mut App, fields: &[FormField]) -> Action {
+    if let Some(field) = fields.iter().find(|field| field_error(app, **field).is_some()) {
+        app.state.form_field = *field;
+        return Action::Continue;
+    }
+    let state = &mut *app.state;
+    // Enter at the end of the body leaves empty lines behind
+    while state.body.is_empty() && state.body_lines.last().is_some_and(|line| line.is_empty()) {
+        state.body_lines.pop();
+    }
+    state.step = Step::Preview;
+    state.focus_issues = false;
+    state.return_to_preview = false;
+    Action::Continue
+}
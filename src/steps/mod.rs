@@ -0,0 +1,168 @@
+// One module per wizard step: each draws its screen and handles its keys.
+// Overlays and global hotkeys live in `App`, which dispatches here.
+pub mod commit_type;
+pub mod scope;
+pub mod subject;
+pub mod body;
+pub mod breaking;
+pub mod preview;
+// Every step but the preview on one screen, for `layout = "form"`
+pub mod form;
+
+use crate::app::App;
+use commitui::config::Config;
+use commitui::i18n::{t, tf};
+use commitui::state::AppState;
+use regex::Regex;
+use ratatui::{
+    Frame,
+    widgets::{Block, Borders, Clear, ListItem, Paragraph},
+    style::{Style, Color, Modifier},
+    layout::Rect,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+// Type a character into a text field, expanding any snippet it completes.
+// Returns false if the field's `input_filters` entry rejects the character.
+pub fn type_char(config: &Config, field: &str, text: &mut String, c: char) -> bool {
+    let filter = config.input_filters.as_ref().and_then(|filters| filters.get(field));
+    if let Some(Ok(re)) = filter.map(|f| Regex::new(&format!("^(?:{})$", f))) {
+        if !re.is_match(c.encode_utf8(&mut [0; 4])) {
+            return false;
+        }
+    }
+
+    text.push(c);
+    let Some(snippets) = config.snippets.as_ref() else { return true };
+    if let Some((key, expansion)) = snippets.iter().find(|(key, _)| !key.is_empty() && text.ends_with(key.as_str())) {
+        text.truncate(text.len() - key.len());
+        text.push_str(expansion);
+    }
+    true
+}
+
+// Below `compact_height` rows the steps drop their borders, so a box only
+// costs its title row; popups keep theirs to stand out
+pub fn borders(app: &App) -> Borders {
+    if app.compact() { Borders::NONE } else { Borders::ALL }
+}
+
+// Rows a box with `lines` of content takes, title (and borders) included
+pub fn box_height(app: &App, lines: u16) -> u16 {
+    if app.compact() { lines + 1 } else { lines + 2 }
+}
+
+// Rows of a list the `/` filter lets through: the pickable ones (`names` has
+// None for group headers) whose name contains it, or every row while unfiltered
+pub fn matching(names: &[Option<&str>], filter: Option<&str>) -> Vec<usize> {
+    let filter = filter.map(|filter| filter.to_lowercase());
+    names.iter()
+        .enumerate()
+        .filter(|(_, name)| match &filter {
+            Some(filter) => name.is_some_and(|name| name.to_lowercase().contains(filter.as_str())),
+            None => true,
+        })
+        .map(|(i, _)| i)
+        .collect()
+}
+
+// In compact mode `/` starts narrowing a list down by name and Backspace past
+// the start stops. Returns whether the key went to the filter; Enter only does
+// while nothing matches.
+pub fn handle_filter_key(
+    app: &mut App,
+    key: KeyEvent,
+    names: &[Option<&str>],
+    selected: fn(&mut AppState) -> &mut usize,
+) -> bool {
+    if !app.compact() {
+        return false;
+    }
+    let state = &mut *app.state;
+    let Some(filter) = state.list_filter.as_mut() else {
+        if key.code == KeyCode::Char('/') {
+            state.list_filter = Some(String::new());
+            return true;
+        }
+        return false;
+    };
+    match key.code {
+        KeyCode::Backspace if filter.is_empty() => {
+            state.list_filter = None;
+            return true;
+        }
+        KeyCode::Backspace => {
+            filter.pop();
+        }
+        KeyCode::Char(c) if !key.modifiers.contains(KeyModifiers::CONTROL) => filter.push(c),
+        KeyCode::Up | KeyCode::Down | KeyCode::Enter => {}
+        _ => return false,
+    }
+    let matches = matching(names, state.list_filter.as_deref());
+    let selected = selected(state);
+    // The highlight stays on a match, moving between matches only
+    let at = matches.iter().position(|i| i == selected);
+    *selected = match (key.code, at) {
+        (KeyCode::Up, Some(at)) => matches[at.saturating_sub(1)],
+        (KeyCode::Down, Some(at)) => matches[(at + 1).min(matches.len() - 1)],
+        (_, Some(_)) => *selected,
+        (_, None) => matches.first().copied().unwrap_or(*selected),
+    };
+    key.code != KeyCode::Enter || matches.is_empty()
+}
+
+// A list's rows as the `/` filter leaves them, and where the highlight is among them
+pub fn filter_rows<'a>(app: &App, rows: Vec<ListItem<'a>>, names: &[Option<&str>], selected: usize) -> (Vec<ListItem<'a>>, Option<usize>) {
+    let matches = matching(names, app.state.list_filter.as_deref());
+    let highlight = matches.iter().position(|i| *i == selected);
+    let rows = rows.into_iter().enumerate().filter(|(i, _)| matches.contains(i)).map(|(_, row)| row).collect();
+    (rows, highlight)
+}
+
+// A list's title: the filter while one is typed, and in compact mode how to
+// start one up front, where a short terminal's narrow title still shows it
+pub fn list_title(app: &App, title: &str) -> String {
+    match &app.state.list_filter {
+        Some(filter) => tf("text.list_filter", &[("filter", filter)]),
+        None if app.compact() => format!("{}{}", t("text.list_filter_hint"), title),
+        None => title.to_string(),
+    }
+}
+
+// Border for the focused input; flashes after a key was rejected by its filter
+pub fn input_border(app: &App, color: Color) -> Style {
+    if app.state.input_rejected {
+        Style::default().fg(Color::Red).add_modifier(Modifier::REVERSED)
+    } else {
+        Style::default().fg(color)
+    }
+}
+
+// Snippets the word being typed could still become, shown in the corner
+pub fn draw_snippet_hints(app: &App, f: &mut Frame, area: Rect, text: &str) {
+    let Some(snippets) = app.config.snippets.as_ref() else { return };
+    let word = text.rsplit(char::is_whitespace).next().unwrap_or("");
+    if word.is_empty() {
+        return;
+    }
+    let lines: Vec<String> = snippets.iter()
+        .filter(|(key, _)| key.starts_with(word))
+        .map(|(key, expansion)| format!("{} → {}", key, expansion))
+        .collect();
+    if lines.is_empty() {
+        return;
+    }
+
+    let width = (lines.iter().map(|l| l.chars().count()).max().unwrap_or(0) as u16 + 2).min(area.width);
+    let height = (lines.len() as u16 + 2).min(area.height);
+    let popup = Rect {
+        x: area.x + area.width - width,
+        y: area.y + area.height - height,
+        width,
+        height,
+    };
+    let paragraph = Paragraph::new(lines.join("\n"))
+        .block(Block::default().title(t("titles.snippets")).borders(Borders::ALL).border_style(Style::default().fg(Color::Cyan)));
+    f.render_widget(Clear, popup);
+    f.render_widget(paragraph, popup);
+}
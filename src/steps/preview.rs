@@ -0,0 +1,761 @@
+use crate::app::{Action, App};
+use crate::review::Review;
+use crate::steps::{self, commit_type, scope};
+use crate::tui::build_message;
+use commitui::git::{commit_message, Identity};
+use commitui::config::{self, Config, Convention};
+use commitui::i18n::{t, tf};
+use commitui::message::CommitMessage;
+use commitui::parser::header_parts;
+use commitui::preflight::{check_signing, commit_too_big, SigningProblem};
+use commitui::validation::{message_size_warning, validate_bang, validate_issues, validate_lengths, validate_smart_commit};
+use commitui::state::{SmartCommit, Step};
+use ratatui::{
+    Frame,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
+    layout::{Layout, Constraint, Direction, Rect},
+};
+use crossterm::event::{KeyCode, KeyEvent};
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    let (state, git_comments) = (&*app.state, app.git_comments);
+    // The identity row, or a placeholder while the repository is still being read
+    let area = if app.identity.is_some() || app.loading.is_some() {
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Min(0)])
+            .split(area);
+        match &app.identity {
+            Some(identity) => draw_identity(app, identity, f, rows[0]),
+            None => f.render_widget(Paragraph::new(t("text.reading_repo")).style(Style::default().fg(Color::DarkGray)), rows[0]),
+        }
+        rows[1]
+    } else {
+        area
+    };
+    // Git's status comments get a panel only when running as GIT_EDITOR
+    let comments_height = if git_comments.is_empty() { 0 } else { steps::box_height(app, git_comments.len() as u16).min(10) };
+    // Output of the last failed commit attempt, if we came back here after one
+    let error_height = state.commit_error.as_ref().map_or(0, |e| steps::box_height(app, e.lines().count() as u16).min(10));
+    // A reviewed commit is shown as written, not as the wizard would rebuild it
+    let full_preview = app.review.as_ref().map_or_else(|| build_message(state, app.config), |review| review.message.clone());
+    // Nothing can be typed into a reviewed commit, so it has no input rows
+    let editable = app.review.is_none();
+    let diff = state.head_message.as_deref().map(|head| line_diff(head, &full_preview));
+    let diff_height = diff.as_ref().map_or(0, |lines| steps::box_height(app, lines.len() as u16).min(12));
+    // Over a server-side limit (or a `!` missing its footer): an error blocks
+    // confirming, the size only warns
+    let length_problem = validate_lengths(&full_preview, app.config)
+        .or_else(|| bang_error(app))
+        .map(|error| (error, Color::Red))
+        .or_else(|| message_size_warning(&full_preview, app.config).map(|warning| (warning, Color::Yellow)));
+    // Atomic commits are the point: a huge one gets a nudge to split it
+    let too_big = commit_too_big(&app.staged, app.config);
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Min(5),
+            Constraint::Length(diff_height),
+            Constraint::Length(comments_height),
+            Constraint::Length(error_height),
+            Constraint::Length(if editable { steps::box_height(app, state.issues.split('\n').count().min(4) as u16) } else { 0 }), // One line per footer
+            Constraint::Length(if editable && app.config.jira.is_some() { steps::box_height(app, 1) } else { 0 }), // Smart commit inputs
+            Constraint::Length(if show_overrides(app) { steps::box_height(app, 1) } else { 0 }), // --author/--date
+            Constraint::Length(app.published.is_some() as u16), // Amend warning
+            Constraint::Length(length_problem.is_some() as u16), // Header/footer/size limits
+            Constraint::Length(too_big.is_some() as u16), // max_commit_files/max_commit_lines
+            Constraint::Length((app.branch_violations > 0) as u16), // Earlier commits off the rules
+            Constraint::Length(1), // Commit options status line
+            Constraint::Length(app.review.as_ref().map_or(0, |review| steps::box_height(app, review.issues.len().max(1) as u16))), // Rules a reviewed commit breaks
+        ])
+        .split(area);
+
+    let paragraph = Paragraph::new(styled_message(app, &full_preview))
+        .block(Block::default()
+            .title(match &app.review {
+                Some(review) => tf("titles.review", &[("sha", &review.sha)]),
+                None => t("titles.preview").to_string(),
+            })
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(Color::Green)))
+        .style(Style::default().fg(Color::Yellow))
+        .wrap(Wrap { trim: false });
+    // Compact: no room for the staged files next to the message
+    if app.staged.is_empty() || app.compact() {
+        f.render_widget(paragraph, chunks[0]);
+    } else {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[0]);
+        f.render_widget(paragraph, columns[0]);
+        draw_staged_files(app, f, columns[1]);
+    }
+
+    if let Some(diff) = diff {
+        let lines: Vec<Line> = diff
+            .into_iter()
+            .map(|(mark, line)| {
+                let color = match mark {
+                    '-' => Color::Red,
+                    '+' => Color::Green,
+                    _ => Color::DarkGray,
+                };
+                Line::from(Span::styled(format!("{} {}", mark, line), Style::default().fg(color)))
+            })
+            .collect();
+        let diff_paragraph = Paragraph::new(lines)
+            .block(Block::default().title(t("titles.head_diff")).borders(steps::borders(app)));
+        f.render_widget(diff_paragraph, chunks[1]);
+    }
+
+    if !git_comments.is_empty() {
+        let comments_paragraph = Paragraph::new(git_comments.join("\n"))
+            .block(Block::default().title(t("titles.git_comments")).borders(steps::borders(app)))
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(comments_paragraph, chunks[2]);
+    }
+
+    if let Some(ref error) = state.commit_error {
+        let error_paragraph = Paragraph::new(error.as_str())
+            .block(Block::default().title(t("titles.commit_failed")).borders(steps::borders(app)))
+            .style(Style::default().fg(Color::Red))
+            .wrap(Wrap { trim: false });
+        f.render_widget(error_paragraph, chunks[3]);
+    }
+
+    if editable {
+        draw_issues(app, f, area, chunks[4]);
+    }
+
+    if editable && app.config.jira.is_some() {
+        draw_smart_commit(app, f, chunks[5]);
+    }
+
+    if show_overrides(app) {
+        draw_overrides(app, f, chunks[6]);
+    }
+
+    if let Some(remote) = &app.published {
+        let warning = Paragraph::new(tf("text.published_amend", &[("remote", remote)]))
+            .style(Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
+        f.render_widget(warning, chunks[7]);
+    }
+
+    if let Some((problem, color)) = length_problem {
+        f.render_widget(Paragraph::new(problem).style(Style::default().fg(color)), chunks[8]);
+    }
+
+    if let Some(too_big) = too_big {
+        f.render_widget(Paragraph::new(too_big.describe()).style(Style::default().fg(Color::Yellow)), chunks[9]);
+    }
+
+    if app.branch_violations > 0 {
+        let base = app.config.base_branch.clone().unwrap_or_else(config::default_base_branch);
+        let warning = Paragraph::new(tf("text.branch_violations", &[
+            ("count", &app.branch_violations.to_string()),
+            ("base", &base),
+        ]))
+        .style(Style::default().fg(Color::Yellow));
+        f.render_widget(warning, chunks[10]);
+    }
+
+    if let Some(review) = &app.review {
+        draw_review(app, review, f, chunks[12]);
+    }
+
+    if let Some(options) = app.options.as_deref() {
+        let on_off = |enabled: bool| if enabled { t("text.on") } else { t("text.off") };
+        let mut options_line = tf(
+            "text.commit_options",
+            &[("gpg", on_off(options.gpg_sign)), ("signoff", on_off(options.signoff))],
+        );
+        if app.unstaged {
+            options_line.push_str(&tf("text.stash_option", &[("stash", on_off(options.stash_unstaged))]));
+        }
+        if options.allow_empty {
+            options_line.push_str(t("text.empty_commit"));
+        }
+        // Skipped hooks are meant to stand out, and to say what the commit will carry
+        let color = match (&options.bypass_trailer, options.no_verify) {
+            (Some(trailer), true) => {
+                options_line.push_str(&tf("text.hooks_bypassed", &[("footer", trailer)]));
+                Color::Yellow
+            }
+            (None, true) => {
+                options_line.push_str(&tf("text.hooks_option", &[("hooks", t("text.off"))]));
+                Color::Yellow
+            }
+            (_, false) => {
+                options_line.push_str(&tf("text.hooks_option", &[("hooks", t("text.on"))]));
+                if options.allow_empty {
+                    Color::Yellow
+                } else if options.gpg_sign {
+                    Color::Green
+                } else {
+                    Color::DarkGray
+                }
+            }
+        };
+        let options_paragraph = Paragraph::new(options_line).style(Style::default().fg(color));
+        f.render_widget(options_paragraph, chunks[11]);
+    }
+}
+
+// The footer input, with the keywords the word being typed could complete to
+// just above it (within `area`)
+fn draw_issues(app: &App, f: &mut Frame, area: Rect, field: Rect) {
+    let state = &*app.state;
+    let input_block = match validate_issues(&state.issues, app.config) {
+        Some(error) => Block::default()
+            .title(error)
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(Color::Red)),
+        None if state.focus_issues => Block::default()
+            .title(t("titles.issues_editing"))
+            .borders(steps::borders(app))
+            .border_style(steps::input_border(app, Color::Green)),
+        None => Block::default()
+            .title(t("titles.issues"))
+            .borders(steps::borders(app)),
+    };
+    let issues_paragraph = Paragraph::new(state.issues.as_str())
+        .block(input_block)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(issues_paragraph, field);
+
+    // Keywords the word being typed could complete to, just above the field
+    let keywords = keyword_candidates(app);
+    if !keywords.is_empty() {
+        let width = (keywords.iter().map(|k| k.chars().count()).max().unwrap_or(0) as u16 + 4)
+            .max(t("titles.issue_keywords").chars().count() as u16 + 2)
+            .min(field.width);
+        let height = (keywords.len() as u16 + 2).min(field.y.saturating_sub(area.y));
+        let popup = Rect { x: field.x, y: field.y - height, width, height };
+        let lines: Vec<Line> = keywords
+            .iter()
+            .enumerate()
+            .map(|(i, keyword)| {
+                let style = if i == state.selected_keyword % keywords.len() {
+                    Style::default().fg(Color::Cyan).add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default().fg(Color::Cyan)
+                };
+                Line::from(Span::styled(format!(" {} ", keyword), style))
+            })
+            .collect();
+        let paragraph = Paragraph::new(lines)
+            .block(Block::default().title(t("titles.issue_keywords")).borders(Borders::ALL));
+        f.render_widget(Clear, popup);
+        f.render_widget(paragraph, popup);
+    }
+}
+
+// What `validate_message` says about a reviewed commit, or that it's clean
+fn draw_review(app: &App, review: &Review, f: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = if review.issues.is_empty() {
+        vec![Line::from(Span::styled(t("reword.clean"), Style::default().fg(Color::Green)))]
+    } else {
+        review
+            .issues
+            .iter()
+            .map(|issue| Line::from(Span::styled(format!("{}: {}", issue.field, issue.message), Style::default().fg(Color::Red))))
+            .collect()
+    };
+    let border = if review.issues.is_empty() { Color::Green } else { Color::Red };
+    let paragraph = Paragraph::new(lines)
+        .block(Block::default()
+            .title(t("reword.lint"))
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(border)))
+        .wrap(Wrap { trim: false });
+    f.render_widget(paragraph, area);
+}
+
+// The message as it will be committed, lightly styled so its structure is
+// easy to check: bold header, marked bullets, dim footers. The lines are the
+// built text's own, so trailer order, dedupe and `interpret_trailers` show
+// as they'll be committed. A `message_template` can put anything anywhere,
+// so its text stays plain.
+fn styled_message<'a>(app: &App, full_preview: &'a str) -> Vec<Line<'a>> {
+    let mut lines = full_preview.lines();
+    let bold = Style::default().add_modifier(Modifier::BOLD);
+    if app.review.is_some() {
+        let header = lines.next().map(|header| header_line(header, app.config).patch_style(bold));
+        return header.into_iter().chain(lines.map(|line| body_line(line.to_string()))).collect();
+    }
+    let message = CommitMessage::from_state(app.state).with_default_subject(app.config);
+    let header = lines.next().map(|header| {
+        if app.config.message_template.is_some() {
+            header_line(header, app.config)
+        } else if message.subject.trim().is_empty() && !header.is_empty() {
+            // `wip(scope)`, as `[type_rules]` allowed: not conventional, but meant to be
+            Line::from(Span::styled(header, bold.fg(Color::Cyan)))
+        } else {
+            header_line(header, app.config).patch_style(bold)
+        }
+    });
+    if app.config.message_template.is_some() {
+        return header.into_iter().chain(lines.map(Line::from)).collect();
+    }
+
+    // The trailers, when there are any, are the last paragraph
+    let position = app.config.breaking_position.unwrap_or_else(config::default_breaking_position);
+    let rest: Vec<&str> = lines.collect();
+    let trailers_from = match message.trailers(position).is_empty() {
+        true => rest.len(),
+        false => rest.iter().rposition(|line| line.is_empty()).map_or(0, |at| at + 1),
+    };
+    let dim = Style::default().add_modifier(Modifier::DIM);
+    let styled = rest.iter().enumerate().map(|(i, line)| match line.strip_prefix("BREAKING CHANGE:") {
+        Some(breaking) if i >= trailers_from => Line::from(vec![
+            Span::styled("BREAKING CHANGE:", bold.fg(Color::Red)),
+            Span::raw(breaking.to_string()),
+        ]),
+        _ if i >= trailers_from => Line::from(Span::styled(line.to_string(), dim)),
+        _ => body_line(line.to_string()),
+    });
+    header.into_iter().chain(styled).collect()
+}
+
+// The header with its type, scope, `!` and subject in their own colours.
+// Under a convention that expects `type(scope): subject`, a header the parser
+// can't split is shown in red; other conventions' headers stay as they are.
+pub fn header_line(header: &str, config: &Config) -> Line<'static> {
+    let Some(parts) = header_parts(header) else {
+        let expected = matches!(config.convention, None | Some(Convention::Conventional) | Some(Convention::Angular));
+        let style = if expected { Style::default().fg(Color::Red).add_modifier(Modifier::UNDERLINED) } else { Style::default() };
+        return Line::from(Span::styled(header.to_string(), style));
+    };
+    let mut colored = vec![(parts.commit_type, Color::Cyan)];
+    colored.extend(parts.scope.map(|scope| (scope, Color::Magenta)));
+    colored.extend(parts.bang.map(|bang| (bang, Color::Red)));
+    colored.push((parts.subject, Color::Yellow));
+
+    // Whatever lies between the parts is punctuation: `(`, `)`, `: `
+    let mut spans = Vec::new();
+    let mut end = 0;
+    for (range, color) in colored {
+        if range.start > end {
+            spans.push(Span::styled(header[end..range.start].to_string(), Style::default().fg(Color::DarkGray)));
+        }
+        end = range.end;
+        spans.push(Span::styled(header[range].to_string(), Style::default().fg(color)));
+    }
+    Line::from(spans)
+}
+
+// A body line, with a list marker ("- ", "* ", "1. ") picked out
+fn body_line(line: String) -> Line<'static> {
+    let indent = line.len() - line.trim_start().len();
+    let rest = &line[indent..];
+    let marker_len = if rest.starts_with("- ") || rest.starts_with("* ") || rest.starts_with("+ ") {
+        2
+    } else {
+        let digits = rest.chars().take_while(char::is_ascii_digit).count();
+        let after = &rest[digits..];
+        if digits > 0 && (after.starts_with(". ") || after.starts_with(") ")) { digits + 2 } else { 0 }
+    };
+    if marker_len == 0 {
+        return Line::from(line);
+    }
+    let (marker, text) = line.split_at(indent + marker_len);
+    Line::from(vec![
+        Span::styled(marker.to_string(), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::raw(text.to_string()),
+    ])
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    // `q` for quit is handled globally
+    if let Some(field) = app.state.smart_commit_field {
+        handle_smart_commit_key(app, key, field);
+        return Action::Continue;
+    }
+    if let Some(field) = app.state.override_field {
+        handle_override_key(app, key, field);
+        return Action::Continue;
+    }
+    let keywords = keyword_candidates(app);
+    // Footers git would get wrong keep the message from being confirmed
+    let issues_error = validate_issues(&app.state.issues, app.config);
+    let smart_commit_error = validate_smart_commit(&app.state.smart_commit, app.config);
+    let length_error = validate_lengths(&build_message(app.state, app.config), app.config).or_else(|| bang_error(app));
+    let state = &mut *app.state;
+    if state.focus_issues { // Issues input focused
+        match key.code {
+            KeyCode::Tab if !keywords.is_empty() => {
+                let keyword = keywords[state.selected_keyword % keywords.len()].clone();
+                complete_keyword(&mut state.issues, &keyword);
+                state.selected_keyword = 0;
+            }
+            KeyCode::Down if !keywords.is_empty() => state.selected_keyword = (state.selected_keyword + 1) % keywords.len(),
+            KeyCode::Up if !keywords.is_empty() => {
+                state.selected_keyword = (state.selected_keyword + keywords.len() - 1) % keywords.len();
+            }
+            KeyCode::Tab => {
+                state.focus_issues = false; // Switch to preview navigation
+            }
+            KeyCode::Enter if issues_error.is_some() => {} // The error is in the field's title
+            KeyCode::Enter if length_error.is_some() => {} // Shown under the field
+            KeyCode::Enter if smart_commit_error.is_some() => {
+                state.focus_issues = false;
+                state.smart_commit_field = Some(0);
+            }
+            KeyCode::Enter if app.loading.is_some() => {} // Whether HEAD is pushed isn't known yet
+            KeyCode::Enter if app.published.is_some() => {
+                state.focus_issues = false; // Rewriting pushed history takes an explicit `y`
+            }
+            KeyCode::Enter => {
+                // Confirm and exit
+                return Action::Confirm;
+            }
+            KeyCode::Char(c) => {
+                state.input_rejected = !steps::type_char(app.config, "issues", &mut state.issues, c);
+                state.selected_keyword = 0;
+            }
+            KeyCode::Backspace => {
+                state.issues.pop();
+                state.selected_keyword = 0;
+            }
+            KeyCode::Left => {
+                state.focus_issues = false; // Leave issue input
+                state.step = Step::Breaking; // Go back
+                state.focus_input = true; // Return to breaking input focus
+            }
+            _ => {}
+        }
+    } else { // Preview navigation
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_issues = true; // Switch to issues input
+            }
+            KeyCode::Char('y') | KeyCode::Enter if issues_error.is_some() => {
+                state.focus_issues = true; // Fix the references first
+            }
+            KeyCode::Char('y') | KeyCode::Enter if smart_commit_error.is_some() => state.smart_commit_field = Some(0),
+            KeyCode::Char('y') | KeyCode::Enter if length_error.is_some() => {} // The server would reject it, or the `!` needs a footer
+            KeyCode::Char('j') if app.config.jira.is_some() => state.smart_commit_field = Some(0),
+            KeyCode::Char('A') if app.options.is_some() => state.override_field = Some(0),
+            // Copy instead of committing (the message stands as shown, like confirming)
+            KeyCode::Char('c') if app.options.is_some() => {
+                state.copy_requested = true;
+                return Action::Confirm;
+            }
+            KeyCode::Char('y') | KeyCode::Enter if app.loading.is_some() => {} // Whether HEAD is pushed isn't known yet
+            KeyCode::Enter if app.published.is_some() => {} // Rewriting pushed history takes an explicit `y`
+            KeyCode::Char('y') | KeyCode::Enter => {
+                // Confirm and exit
+                return Action::Confirm;
+            }
+            KeyCode::Char('R') if app.branch_violations > 0 => {
+                let base = app.config.base_branch.clone().unwrap_or_else(config::default_base_branch);
+                return Action::Reword(format!("{}..HEAD", base));
+            }
+            KeyCode::Char('!') => state.bang = !state.bang,
+            KeyCode::Char('d') => {
+                state.head_message = match state.head_message {
+                    Some(_) => None,
+                    None => Some(commit_message("HEAD").unwrap_or_default()), // No commits yet: all new
+                };
+            }
+            KeyCode::Char('S') => {
+                if let Some(options) = app.options.as_deref_mut() {
+                    options.gpg_sign = !options.gpg_sign;
+                }
+            }
+            KeyCode::Char('s') => {
+                if let Some(options) = app.options.as_deref_mut() {
+                    options.signoff = !options.signoff;
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(options) = app.options.as_deref_mut() {
+                    options.no_verify = !options.no_verify;
+                }
+            }
+            KeyCode::Char('u') if app.unstaged => {
+                if let Some(options) = app.options.as_deref_mut() {
+                    options.stash_unstaged = !options.stash_unstaged;
+                }
+            }
+            KeyCode::Char('b') | KeyCode::Left => {
+                state.step = Step::Breaking; // Go back
+                state.focus_input = true; // Return to breaking input focus
+            }
+            KeyCode::Char(c @ '1'..='5') => jump_to(app, c),
+            _ => {}
+        }
+    }
+    Action::Continue
+}
+
+// `bang_requires_footer` and a `!` without a BREAKING CHANGE description
+fn bang_error(app: &App) -> Option<String> {
+    let message = CommitMessage::from_state(app.state);
+    validate_bang(message.bang, message.breaking.as_deref(), app.config)
+}
+
+// Edit one step directly; confirming it comes straight back here (see `App::handle_key`)
+fn jump_to(app: &mut App, number: char) {
+    let step = match number {
+        '1' => Step::Type,
+        '2' => Step::Scope,
+        '3' => Step::Subject,
+        '4' => Step::Body,
+        _ => Step::Breaking,
+    };
+    if step.is_hidden(app.config) {
+        return;
+    }
+    app.state.return_to_preview = true;
+    app.state.focus_input = true; // Text steps start in their input
+    app.state.step = step;
+    match app.state.step {
+        Step::Type => commit_type::restore_selection(app),
+        Step::Scope => scope::restore_selection(app),
+        _ => {}
+    }
+}
+
+// Line-by-line diff of two messages (longest common subsequence):
+// ' ' for lines in both, '-' only in `old`, '+' only in `new`
+fn line_diff<'a>(old: &'a str, new: &'a str) -> Vec<(char, &'a str)> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    // common[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for (i, a) in old.iter().enumerate().rev() {
+        for (j, b) in new.iter().enumerate().rev() {
+            common[i][j] = if a == b { common[i + 1][j + 1] + 1 } else { common[i + 1][j].max(common[i][j + 1]) };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            lines.push((' ', old[i]));
+            i += 1;
+            j += 1;
+        } else if common[i + 1][j] >= common[i][j + 1] {
+            lines.push(('-', old[i]));
+            i += 1;
+        } else {
+            lines.push(('+', new[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[i..].iter().map(|line| ('-', *line)));
+    lines.extend(new[j..].iter().map(|line| ('+', *line)));
+    lines
+}
+
+// `Author: Name <email> | Signing key: ABC (openpgp)`, red with the reason when
+// the email's domain isn't one of `allowed_email_domains`. The key is red too
+// when signing is on and an SSH key can't be found. An `--author`
+// override in "Name <email>" form is what's shown, and hooks from anywhere but
+// .git/hooks are pointed out.
+fn draw_identity(app: &App, identity: &Identity, f: &mut Frame, area: Rect) {
+    let overridden = app.options.as_deref()
+        .and_then(|options| options.author.as_deref())
+        .and_then(|author| author.trim().strip_suffix('>')?.split_once(" <"));
+    let (name, email) = overridden.unwrap_or((&identity.name, &identity.email));
+    let domains = app.config.allowed_email_domains.clone().unwrap_or_else(config::default_allowed_email_domains);
+    let domain = email.rsplit_once('@').map_or("", |(_, domain)| domain);
+    let allowed = domains.is_empty() || domains.iter().any(|d| d.eq_ignore_ascii_case(domain));
+
+    let author = tf("text.identity", &[("name", name), ("email", email)]);
+    let signing = app.options.as_deref().is_some_and(|options| options.gpg_sign);
+    let problem = check_signing(identity).filter(|_| signing);
+    let key = match &identity.signing_key {
+        Some(key) => tf("text.signing_key", &[("key", key), ("format", &identity.signing_format)]),
+        None => t("text.no_signing_key").to_string(),
+    };
+    let (key, key_color) = match problem {
+        Some(SigningProblem::SshKeyMissing(_)) => (format!("{}{}", key, t("text.key_not_found")), Color::Red),
+        Some(SigningProblem::NoSshKey) => (key, Color::Red),
+        None => (key, Color::DarkGray),
+    };
+    let mut spans = vec![
+        Span::styled(author, Style::default().fg(if allowed { Color::Cyan } else { Color::Red })),
+        Span::styled(format!(" | {}", key), Style::default().fg(key_color)),
+    ];
+    let hooks = app.options.as_deref().and_then(|options| options.hooks_path.as_ref()).or(identity.hooks_path.as_ref());
+    if let Some(hooks) = hooks {
+        spans.push(Span::styled(format!(" | {}", tf("text.hooks", &[("path", hooks)])), Style::default().fg(Color::DarkGray)));
+    }
+    if !allowed {
+        let warning = tf("text.email_not_allowed", &[("domains", &domains.join(", "))]);
+        spans.push(Span::styled(format!(" | {}", warning), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)), area);
+}
+
+// `Ticket: PROJ-1  Time: 2h  Comment: ...`
+fn draw_smart_commit(app: &App, f: &mut Frame, area: Rect) {
+    let state = &*app.state;
+    let labels = [t("text.jira_ticket"), t("text.jira_time"), t("text.jira_comment")];
+    let fields = labels.into_iter().enumerate().map(|(i, label)| {
+        let value = state.smart_commit.field(i);
+        (label, if value.is_empty() { t("text.empty").to_string() } else { value.clone() })
+    });
+    let block = match validate_smart_commit(&state.smart_commit, app.config) {
+        Some(error) => Block::default().title(error).borders(steps::borders(app)).border_style(Style::default().fg(Color::Red)),
+        None if state.smart_commit_field.is_some() => Block::default()
+            .title(t("titles.smart_commit_editing"))
+            .borders(steps::borders(app))
+            .border_style(steps::input_border(app, Color::Green)),
+        None => Block::default().title(t("titles.smart_commit")).borders(steps::borders(app)),
+    };
+    draw_inline_fields(f, area, fields, state.smart_commit_field, block);
+}
+
+// Keys while a smart-commit input has focus: type into it, Tab/Enter onwards
+fn handle_smart_commit_key(app: &mut App, key: KeyEvent, field: usize) {
+    let state = &mut *app.state;
+    if let Some(next) = next_field(key, field, SmartCommit::FIELDS) {
+        state.smart_commit_field = next;
+        return;
+    }
+    match key.code {
+        KeyCode::Char(c) => {
+            state.input_rejected = !steps::type_char(app.config, "jira", state.smart_commit.field_mut(field), c);
+        }
+        KeyCode::Backspace => {
+            state.smart_commit.field_mut(field).pop();
+        }
+        _ => {}
+    }
+}
+
+// A row of `Label: value` inputs on the preview, the one being typed into highlighted
+fn draw_inline_fields<'a>(
+    f: &mut Frame,
+    area: Rect,
+    fields: impl Iterator<Item = (&'a str, String)>,
+    focused: Option<usize>,
+    block: Block,
+) {
+    let mut spans = Vec::new();
+    for (i, (label, value)) in fields.enumerate() {
+        let style = if focused == Some(i) {
+            Style::default().fg(Color::Green).add_modifier(Modifier::UNDERLINED)
+        } else {
+            Style::default().fg(Color::Yellow)
+        };
+        spans.push(Span::styled(format!("{}: ", label), Style::default().fg(Color::DarkGray)));
+        spans.push(Span::styled(value, style));
+        spans.push(Span::raw("  "));
+    }
+    f.render_widget(Paragraph::new(Line::from(spans)).block(block), area);
+}
+
+// Tab/Enter to the next input (past the last one closes the row), Shift+Tab
+// back to the previous one. None for keys that edit rather than move.
+fn next_field(key: KeyEvent, field: usize, count: usize) -> Option<Option<usize>> {
+    match key.code {
+        KeyCode::Tab | KeyCode::Enter => Some(Some(field + 1).filter(|next| *next < count)),
+        KeyCode::BackTab => Some(Some(field.saturating_sub(1))),
+        _ => None,
+    }
+}
+
+// `M src/app.rs +12 -3` per staged file, totals in the title
+fn draw_staged_files(app: &App, f: &mut Frame, area: Rect) {
+    let lines: Vec<Line> = app.staged
+        .iter()
+        .map(|change| {
+            let color = match change.status {
+                'A' => Color::Green,
+                'D' => Color::Red,
+                _ => Color::Yellow,
+            };
+            let mut spans = vec![
+                Span::styled(format!("{} ", change.status), Style::default().fg(color)),
+                Span::raw(change.path.clone()),
+            ];
+            match (change.added, change.removed) {
+                (Some(added), Some(removed)) => {
+                    spans.push(Span::styled(format!(" +{}", added), Style::default().fg(Color::Green)));
+                    spans.push(Span::styled(format!(" -{}", removed), Style::default().fg(Color::Red)));
+                }
+                _ => spans.push(Span::styled(format!(" {}", t("text.binary")), Style::default().fg(Color::DarkGray))),
+            }
+            Line::from(spans)
+        })
+        .collect();
+    let added: usize = app.staged.iter().filter_map(|c| c.added).sum();
+    let removed: usize = app.staged.iter().filter_map(|c| c.removed).sum();
+    let title = tf("titles.staged_files", &[
+        ("count", &app.staged.len().to_string()),
+        ("added", &added.to_string()),
+        ("removed", &removed.to_string()),
+    ]);
+    let paragraph = Paragraph::new(lines).block(Block::default().title(title).borders(steps::borders(app)));
+    f.render_widget(paragraph, area);
+}
+
+// The override panel only takes room once it's been opened or a flag set something
+fn show_overrides(app: &App) -> bool {
+    let Some(options) = app.options.as_deref() else { return false };
+    let set = |value: &Option<String>| value.as_deref().is_some_and(|v| !v.is_empty());
+    app.state.override_field.is_some() || set(&options.author) || set(&options.date)
+}
+
+fn draw_overrides(app: &App, f: &mut Frame, area: Rect) {
+    let Some(options) = app.options.as_deref() else { return };
+    let fields = [(t("text.override_author"), &options.author), (t("text.override_date"), &options.date)]
+        .into_iter()
+        .map(|(label, value)| (label, value.as_deref().filter(|v| !v.is_empty()).unwrap_or(t("text.git_default")).to_string()));
+    let block = if app.state.override_field.is_some() {
+        Block::default()
+            .title(t("titles.overrides_editing"))
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(Color::Green))
+    } else {
+        Block::default().title(t("titles.overrides")).borders(steps::borders(app))
+    };
+    draw_inline_fields(f, area, fields, app.state.override_field, block);
+}
+
+fn handle_override_key(app: &mut App, key: KeyEvent, field: usize) {
+    let Some(options) = app.options.as_deref_mut() else { return };
+    if let Some(next) = next_field(key, field, 2) {
+        app.state.override_field = next;
+        return;
+    }
+    match key.code {
+        KeyCode::Char(c) => options.override_field(field).push(c),
+        KeyCode::Backspace => {
+            options.override_field(field).pop();
+        }
+        _ => {}
+    }
+}
+
+// `issue_keywords` starting with the word at the end of the issues field
+fn keyword_candidates(app: &App) -> Vec<String> {
+    let word = app.state.issues.rsplit(char::is_whitespace).next().unwrap_or("");
+    if !app.state.focus_issues || word.is_empty() || word.contains(':') {
+        return vec![];
+    }
+    let keywords = app.config.issue_keywords.clone().unwrap_or_else(config::default_issue_keywords);
+    keywords.into_iter().filter(|k| k.to_lowercase().starts_with(&word.to_lowercase())).collect()
+}
+
+// Replace the word being typed with `Keyword: `, on a line of its own
+// when the current footer already has something in it
+fn complete_keyword(issues: &mut String, keyword: &str) {
+    let word_len = issues.rsplit(char::is_whitespace).next().unwrap_or("").len();
+    issues.truncate(issues.len() - word_len);
+    let line = issues.rsplit('\n').next().unwrap_or("");
+    if !line.trim().is_empty() {
+        issues.truncate(issues.trim_end().len());
+        issues.push('\n');
+    }
+    issues.push_str(keyword);
+    issues.push_str(": ");
+}
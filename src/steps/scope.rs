@@ -0,0 +1,265 @@
+use crate::app::{Action, App};
+use crate::manage::ManagedList;
+use crate::steps::{self, commit_type};
+use commitui::config::{default_allow_custom_scope, default_remember_scopes, ScopeEntry};
+use commitui::i18n::{t, tf};
+use commitui::remembered;
+use commitui::state::Step;
+use commitui::validation::validate_scope;
+use ratatui::{
+    Frame,
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    style::{Style, Color, Modifier},
+    text::{Line, Span},
+    layout::{Layout, Constraint, Direction, Rect},
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+pub fn is_scope_selectable(entries: &[ScopeEntry], idx: usize) -> bool {
+    entries.get(idx).is_some_and(|e| e.scope().is_some())
+}
+
+// Move the selection over group headers, staying put at either end
+pub fn next_selectable_scope(entries: &[ScopeEntry], mut idx: usize, dir: i32) -> usize {
+    loop {
+        let new_idx = if dir > 0 {
+            if idx + 1 >= entries.len() { return idx; }
+            idx + 1
+        } else {
+            if idx == 0 { return idx; }
+            idx - 1
+        };
+        if is_scope_selectable(entries, new_idx) {
+            return new_idx;
+        }
+        idx = new_idx;
+    }
+}
+
+// Point the list/input back at the chosen scope when returning to this step
+pub fn restore_selection(app: &mut App) {
+    let allow_custom = custom_allowed(app);
+    let entries = app.state.scope_entries(app.config);
+    let state = &mut *app.state;
+    let listed = entries.iter().position(|e| e.scope().is_some() && e.scope() == state.chosen_scope.as_deref());
+    state.focus_input = allow_custom && state.chosen_scope.is_some() && listed.is_none();
+    state.selected_scope = listed.unwrap_or(0);
+    state.custom_scope = state.chosen_scope.clone().unwrap_or_default();
+}
+
+fn custom_allowed(app: &App) -> bool {
+    app.config.allow_custom_scope.unwrap_or_else(default_allow_custom_scope)
+}
+
+// The scope Enter would choose right now, from the input or the list
+fn pending_scope(app: &App) -> Option<String> {
+    let state = &*app.state;
+    if state.focus_input {
+        Some(state.custom_scope.trim().to_string()).filter(|s| !s.is_empty())
+    } else if state.selected_scope == 0 { // "no scope"
+        None
+    } else {
+        state.scope_entries(app.config).get(state.selected_scope).and_then(|e| e.scope()).map(|s| s.to_string())
+    }
+}
+
+// A typed scope that isn't in the list, offered to be kept for next time
+fn rememberable(app: &App) -> Option<String> {
+    if !app.state.focus_input || !app.config.remember_scopes.unwrap_or_else(default_remember_scopes) {
+        return None;
+    }
+    pending_scope(app).filter(|scope| !app.config.is_listed_scope(scope))
+}
+
+fn scope_error(app: &App, scope: Option<&str>) -> Option<String> {
+    validate_scope(app.state.chosen_type.as_deref(), scope, app.config)
+}
+
+// Leave the scope empty and move on (unless this type requires one)
+pub fn skip(app: &mut App) -> Action {
+    if scope_error(app, None).is_some() {
+        return Action::Continue;
+    }
+    let state = &mut *app.state;
+    state.chosen_scope = None;
+    state.custom_scope.clear();
+    state.selected_scope = 0;
+    state.step = Step::Subject;
+    state.focus_input = true; // Start subject input focused
+    Action::Continue
+}
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    let state = &*app.state;
+    let entries = state.scope_entries(app.config);
+    let allow_custom = custom_allowed(app);
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            // Compact: the list scrolls in whatever room is left
+            if app.compact() { Constraint::Min(2) } else { Constraint::Length(steps::box_height(app, entries.len() as u16)) },
+            Constraint::Length(if allow_custom { steps::box_height(app, 1) } else { 0 }), // Custom scope input
+            Constraint::Length(steps::box_height(app, 1)), // Scope policy error
+        ])
+        .split(area);
+
+    let items: Vec<ListItem> = entries
+        .iter()
+        .map(|entry| match entry {
+            ScopeEntry::Header(name) => ListItem::new(format!("── {} ──", name))
+                .style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD)),
+            ScopeEntry::Scope(scope) if state.pinned_scopes.contains(scope) => ListItem::new(Line::from(vec![
+                Span::raw(scope.as_str()),
+                Span::styled(t("text.pinned"), Style::default().fg(Color::DarkGray)),
+            ])),
+            ScopeEntry::Scope(scope) => ListItem::new(scope.as_str()),
+        })
+        .collect();
+    let names: Vec<Option<&str>> = entries.iter().map(|entry| entry.scope()).collect();
+    let (items, highlight) = steps::filter_rows(app, items, &names, state.selected_scope);
+    let mut list_state = ListState::default();
+    list_state.select(highlight);
+    // Without the custom input below, the list carries the key hints
+    let list_title = if allow_custom {
+        t("titles.scope").to_string()
+    } else {
+        tf("titles.scope_hints", &[("skip", app.skip_hint())])
+    };
+    let list = List::new(items)
+        .block(Block::default().title(steps::list_title(app, &list_title)).borders(steps::borders(app)))
+        .highlight_style(Style::default().bg(Color::Blue))
+        .highlight_symbol(">> ");
+    f.render_stateful_widget(list, chunks[0], &mut list_state);
+
+    if !allow_custom {
+        if let Some(msg) = scope_error(app, pending_scope(app).as_deref()) {
+            render_error(app, f, msg, chunks[2]);
+        }
+        return;
+    }
+
+    let input_block = if state.focus_input {
+        Block::default()
+            .title(tf("titles.custom_scope_editing", &[("skip", app.skip_hint())]))
+            .borders(steps::borders(app))
+            .border_style(steps::input_border(app, Color::Green))
+    } else {
+        Block::default()
+            .title(tf("titles.custom_scope", &[("skip", app.skip_hint())]))
+            .borders(steps::borders(app))
+    };
+    let paragraph = Paragraph::new(state.custom_scope.as_str())
+        .block(input_block)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(paragraph, chunks[1]);
+
+    if let Some(msg) = scope_error(app, pending_scope(app).as_deref()) {
+        render_error(app, f, msg, chunks[2]);
+    } else if let Some(scope) = rememberable(app) {
+        let offer = Paragraph::new(tf("text.remember_scope", &[("scope", &scope)]))
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(offer, chunks[2]);
+    }
+}
+
+fn render_error(app: &App, f: &mut Frame, msg: String, area: Rect) {
+    let warn = Paragraph::new(msg)
+        .block(Block::default().borders(steps::borders(app)).title(t("titles.validation_error")))
+        .style(Style::default().fg(Color::Red));
+    f.render_widget(warn, area);
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    if !app.state.focus_input {
+        let entries = app.state.scope_entries(app.config);
+        let names: Vec<Option<&str>> = entries.iter().map(|entry| entry.scope()).collect();
+        if steps::handle_filter_key(app, key, &names, |state| &mut state.selected_scope) {
+            return Action::Continue;
+        }
+    }
+    let scope_ok = scope_error(app, pending_scope(app).as_deref()).is_none();
+    let allow_custom = custom_allowed(app);
+    let entries = app.state.scope_entries(app.config);
+    let offer = rememberable(app);
+    let state = &mut *app.state;
+
+    if state.focus_input { // Custom scope input focused
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = false; // Switch to list
+            }
+            // Ctrl+K: Enter, and keep the scope in the list from now on
+            KeyCode::Char('k') if key.modifiers.contains(KeyModifiers::CONTROL) && scope_ok && offer.is_some() => {
+                let scope = offer.unwrap_or_default();
+                app.notice = Some(match remembered::remember(&scope) {
+                    Ok(()) => tf("text.scope_remembered", &[("scope", &scope)]),
+                    Err(e) => tf("text.save_failed", &[("path", ".git/commitui/scopes"), ("error", &e.to_string())]),
+                });
+                state.chosen_scope = Some(scope);
+                state.step = Step::Subject;
+                state.focus_input = true;
+            }
+            KeyCode::Enter if scope_ok => {
+                if !state.custom_scope.trim().is_empty() {
+                    state.chosen_scope = Some(state.custom_scope.trim().to_string());
+                } else {
+                    state.chosen_scope = None; // If custom input is empty, clear scope
+                }
+                state.step = Step::Subject;
+                state.focus_input = true; // Start subject input focused
+            }
+            KeyCode::Char(c) => {
+                state.input_rejected = !steps::type_char(app.config, "scope", &mut state.custom_scope, c);
+            }
+            KeyCode::Backspace => {
+                state.custom_scope.pop();
+            }
+            _ => {}
+        }
+    } else { // Scope list focused
+        // Only 'q' quits here, Esc/Ctrl+C are handled globally
+        if key.code == KeyCode::Char('q') && key.modifiers.is_empty() {
+            return Action::Quit;
+        }
+        match key.code {
+            KeyCode::Tab if allow_custom => {
+                state.focus_input = true; // Switch to custom input
+            }
+            KeyCode::Down => {
+                state.selected_scope = next_selectable_scope(&entries, state.selected_scope, 1);
+            }
+            KeyCode::Up => {
+                state.selected_scope = next_selectable_scope(&entries, state.selected_scope, -1);
+            }
+            KeyCode::Enter if scope_ok && is_scope_selectable(&entries, state.selected_scope) => {
+                if state.selected_scope == 0 { // "no scope" selected (always at index 0 in default)
+                    state.chosen_scope = None;
+                } else {
+                    state.chosen_scope = entries[state.selected_scope].scope().map(|s| s.to_string());
+                }
+                state.step = Step::Subject;
+                state.focus_input = true; // Start subject input focused
+            }
+            KeyCode::Char('b') | KeyCode::Left => {
+                state.step = Step::Type;
+                commit_type::restore_selection(app);
+            }
+            KeyCode::Char('m') => return Action::Manage(ManagedList::Scopes),
+            // Pin the highlighted scope to the top (or unpin it); the highlight follows it
+            KeyCode::Char('p') if state.selected_scope > 0 && is_scope_selectable(&entries, state.selected_scope) => {
+                let scope = entries[state.selected_scope].scope().unwrap_or_default().to_string();
+                match remembered::toggle_pin(&scope) {
+                    Ok(pins) => {
+                        state.pinned_scopes = pins;
+                        let entries = state.scope_entries(app.config);
+                        state.selected_scope = entries.iter().position(|e| e.scope() == Some(scope.as_str())).unwrap_or(0);
+                    }
+                    Err(e) => app.notice = Some(tf("text.save_failed", &[("path", ".git/commitui/pinned"), ("error", &e.to_string())])),
+                }
+            }
+            _ => {}
+        }
+    }
+    Action::Continue
+}
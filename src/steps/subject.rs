@@ -0,0 +1,184 @@
+use crate::app::{Action, App};
+use crate::steps::{self, scope};
+use crate::jobs::JobResult;
+use crate::tui::centered_rect;
+use commitui::git::{staged_diff, staged_files};
+use commitui::config;
+use commitui::i18n::{t, tf};
+use commitui::state::Step;
+use commitui::suggest::suggest_subjects;
+use commitui::validation::{fix_subject, split_subject};
+use ratatui::{
+    Frame,
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+    style::{Style, Color},
+    layout::Rect,
+};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+pub fn draw(app: &App, f: &mut Frame, area: Rect) {
+    let state = &*app.state;
+    let block = if state.focus_input {
+        Block::default()
+            .title(t("titles.subject_editing"))
+            .borders(steps::borders(app))
+            .border_style(steps::input_border(app, Color::Green))
+    } else {
+        Block::default()
+            .title(t("titles.subject"))
+            .borders(steps::borders(app))
+            .border_style(Style::default().fg(Color::Green))
+    };
+    let paragraph = Paragraph::new(state.subject.as_str())
+        .block(block)
+        .style(Style::default().fg(Color::Yellow));
+    f.render_widget(paragraph, area);
+
+    let (error, warning) = app.subject_check();
+    // Errors block Enter; warnings are only advice
+    let shown_msg = error
+        .map(|msg| match offered_fix(app) {
+            Some((fixed, None)) => format!("{}{}", msg, tf("text.auto_fix", &[("fixed", &fixed)])),
+            Some((fixed, Some(_))) => format!("{}{}", msg, tf("text.auto_split", &[("fixed", &fixed)])),
+            None => msg,
+        })
+        .map(|msg| (msg, t("titles.validation_error"), Color::Red))
+        .or_else(|| warning.map(|msg| (msg, t("titles.warning"), Color::Yellow)));
+    if let Some((msg, title, color)) = shown_msg {
+        let warn = Paragraph::new(msg)
+            .block(Block::default().borders(steps::borders(app)).title(title))
+            .style(Style::default().fg(color));
+        let height = steps::box_height(app, 1);
+        let warn_area = Rect {
+            x: area.x,
+            y: area.y + area.height.saturating_sub(height),
+            width: area.width,
+            height,
+        };
+        f.render_widget(warn, warn_area);
+    }
+
+    if !state.suggestions.is_empty() {
+        let items: Vec<ListItem> = state.suggestions
+            .iter()
+            .map(|s| ListItem::new(s.as_str()))
+            .collect();
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.selected_suggestion));
+        let list = List::new(items)
+            .block(Block::default()
+                .title(t("titles.suggestions"))
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(Color::Cyan)))
+            .highlight_style(Style::default().bg(Color::Blue))
+            .highlight_symbol(">> ");
+        let popup = centered_rect(area, 60, state.suggestions.len() as u16 + 2);
+        f.render_widget(Clear, popup);
+        f.render_stateful_widget(list, popup, &mut list_state);
+    }
+}
+
+// Keys while the Ctrl+G suggestion popup is open
+pub fn handle_suggestion_key(app: &mut App, key: KeyEvent) {
+    let state = &mut *app.state;
+    match key.code {
+        KeyCode::Down => {
+            state.selected_suggestion = (state.selected_suggestion + 1).min(state.suggestions.len() - 1);
+        }
+        KeyCode::Up => {
+            state.selected_suggestion = state.selected_suggestion.saturating_sub(1);
+        }
+        KeyCode::Enter => {
+            // Accept into the input for further editing
+            state.subject = state.suggestions[state.selected_suggestion].clone();
+            state.suggestions.clear();
+            state.focus_input = true;
+        }
+        KeyCode::Esc => {
+            state.suggestions.clear();
+        }
+        _ => {}
+    }
+}
+
+// With `auto_fix`, what Ctrl+F turns an invalid subject into, and the body
+// paragraph an overlong one hands its tail to
+fn offered_fix(app: &App) -> Option<(String, Option<String>)> {
+    if !app.config.auto_fix.unwrap_or_else(config::default_auto_fix) || app.subject_check().0.is_none() {
+        return None;
+    }
+    let commit_type = app.state.chosen_type.as_deref();
+    if let Some((subject, paragraph)) = split_subject(commit_type, &app.state.subject, app.config) {
+        return Some((subject, Some(paragraph)));
+    }
+    fix_subject(commit_type, &app.state.subject, app.config).map(|fixed| (fixed, None))
+}
+
+pub fn handle_key(app: &mut App, key: KeyEvent) -> Action {
+    // `q` for quit is handled globally
+    if key.code == KeyCode::Char('f') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        if let Some((fixed, paragraph)) = offered_fix(app) {
+            let state = &mut *app.state;
+            state.subject = fixed;
+            // The paragraph goes first, apart from whatever body there already is
+            if let Some(paragraph) = paragraph {
+                let mut lines = vec![paragraph];
+                if !state.body_lines.is_empty() || !state.body.is_empty() {
+                    lines.push(String::new());
+                }
+                lines.append(&mut state.body_lines);
+                state.body_lines = lines;
+            }
+        }
+        return Action::Continue;
+    }
+    if key.code == KeyCode::Char('g') && key.modifiers.contains(KeyModifiers::CONTROL) {
+        // Suggest subjects from the staged diff, in either mode
+        app.start_job(t("jobs.suggestions"), || {
+            let files = staged_files().unwrap_or_default();
+            let diff = staged_diff().unwrap_or_default();
+            JobResult::Suggestions(suggest_subjects(&files, &diff))
+        });
+        return Action::Continue;
+    }
+
+    let subject_ok = app.subject_check().0.is_none();
+    let state = &mut *app.state;
+    if state.focus_input { // Subject input focused
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = false; // Switch to navigation mode for subject
+            }
+            KeyCode::Enter if subject_ok => {
+                state.step = Step::Body;
+                state.focus_input = true; // Start body input focused
+                state.in_body = false; // Reset multi-line body state
+            }
+            KeyCode::Char(c) => {
+                state.input_rejected = !steps::type_char(app.config, "subject", &mut state.subject, c);
+            }
+            KeyCode::Backspace => {
+                state.subject.pop();
+            }
+            _ => {}
+        }
+    } else { // Navigation mode for subject
+        match key.code {
+            KeyCode::Tab => {
+                state.focus_input = true; // Switch to subject input
+            }
+            KeyCode::Char('b') | KeyCode::Left => {
+                state.step = Step::Scope;
+                scope::restore_selection(app);
+            }
+            // If enter is pressed in nav mode, it should still move forward if valid.
+            KeyCode::Enter if subject_ok => {
+                state.step = Step::Body;
+                state.focus_input = true;
+                state.in_body = false;
+            }
+            _ => {}
+        }
+    }
+    Action::Continue
+}
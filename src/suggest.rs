@@ -0,0 +1,81 @@
+use regex::Regex;
+use std::path::Path;
+use std::sync::OnceLock;
+
+// A staged file as reported by `git diff --cached --name-status`
+#[derive(Debug, Clone)]
+pub struct StagedFile {
+    pub status: char, // A, M, D, R, ...
+    pub path: String,
+}
+
+fn definition_regex() -> &'static Regex {
+    static DEFINITION: OnceLock<Regex> = OnceLock::new();
+    // Function-ish definitions across the usual languages, only on added lines
+    DEFINITION.get_or_init(|| {
+        Regex::new(r"^\+\s*(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?(?:fn|def|func|function)\s+([A-Za-z_]\w*)").unwrap()
+    })
+}
+
+fn file_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
+
+fn verb_for(status: char) -> &'static str {
+    match status {
+        'A' => "add",
+        'D' => "remove",
+        'R' => "rename",
+        _ => "update",
+    }
+}
+
+// Up to three local, heuristic subject candidates for the staged changes
+pub fn suggest_subjects(files: &[StagedFile], diff: &str) -> Vec<String> {
+    let mut suggestions: Vec<String> = Vec::new();
+
+    // 1. From newly added functions
+    let added: Vec<String> = diff
+        .lines()
+        .filter_map(|line| definition_regex().captures(line))
+        .map(|caps| caps[1].to_string())
+        .collect();
+    match added.as_slice() {
+        [] => {}
+        [one] => suggestions.push(format!("add {} function", one)),
+        [first, second] => suggestions.push(format!("add {} and {}", first, second)),
+        [first, rest @ ..] => suggestions.push(format!("add {} and {} more functions", first, rest.len())),
+    }
+
+    // 2. From what happened to the files
+    match files {
+        [] => {}
+        [file] => suggestions.push(format!("{} {}", verb_for(file.status), file_stem(&file.path))),
+        _ => {
+            let status = files[0].status;
+            let verb = if files.iter().all(|f| f.status == status) { verb_for(status) } else { "update" };
+            suggestions.push(format!("{} {} files", verb, files.len()));
+        }
+    }
+
+    // 3. From the directory most of the changes live in
+    if files.len() > 1 {
+        let dirs: Vec<&str> = files
+            .iter()
+            .filter_map(|f| Path::new(&f.path).parent().and_then(|p| p.to_str()))
+            .filter(|d| !d.is_empty())
+            .collect();
+        if let Some(dir) = dirs.first() {
+            if dirs.iter().all(|d| d == dir) {
+                suggestions.push(format!("update {}", dir));
+            }
+        }
+    }
+
+    suggestions.dedup();
+    suggestions.truncate(3);
+    suggestions
+}
@@ -0,0 +1,69 @@
+use crate::cli::Command;
+use commitui::config::{self, Config};
+use commitui::git::{commit_summary, current_branch, merge_no_commit, recent_commits, revert_no_commit};
+use crate::history::{browse_history, HISTORY_LIMIT};
+use commitui::state::Prefill;
+
+// Stage what a template subcommand commits, after the preflight checks (so an
+// abort there has nothing to undo). Returns the operation `git <op> --abort` undoes.
+pub fn start_template(command: &Command) -> Result<Option<&'static str>, Box<dyn std::error::Error>> {
+    match command {
+        Command::Revert { sha } => {
+            revert_no_commit(sha)?;
+            Ok(Some("revert"))
+        }
+        Command::Merge { branch } => {
+            merge_no_commit(branch)?;
+            Ok(Some("merge"))
+        }
+        _ => Ok(None),
+    }
+}
+
+// Wizard answers for a template subcommand
+pub fn prefill_for(command: &Command, config: &Config) -> Result<Prefill, Box<dyn std::error::Error>> {
+    match command {
+        Command::Revert { sha } => {
+            let (full_sha, subject) = commit_summary(sha)?;
+            Ok(Prefill {
+                commit_type: Some("revert".into()),
+                subject: Some(subject),
+                body: Some(format!("This reverts commit {}.", full_sha)),
+                ..Prefill::default()
+            })
+        }
+        // `merge: feature/x into main`, instead of git's "Merge branch 'feature/x'" as the body
+        Command::Merge { branch } => {
+            let subject = match current_branch() {
+                Some(current) => format!("{} into {}", branch, current),
+                None => branch.clone(),
+            };
+            Ok(Prefill {
+                commit_type: Some(config.merge_type.clone().unwrap_or_else(config::default_merge_type)),
+                subject: Some(subject),
+                body: Some(String::new()),
+                ..Prefill::default()
+            })
+        }
+        Command::Log => {
+            let entries = recent_commits(HISTORY_LIMIT)?;
+            Ok(browse_history(&entries)?.unwrap_or_default())
+        }
+        Command::Release { version } => {
+            let version = if version.starts_with('v') { version.clone() } else { format!("v{}", version) };
+            Ok(Prefill {
+                commit_type: Some("chore".into()),
+                scope: Some("release".into()),
+                subject: Some(version),
+                ..Prefill::default()
+            })
+        }
+        // main stages and pre-fills each planned commit itself
+        Command::Plan { .. } => Ok(Prefill::default()),
+        // Not templates: main runs these instead of the wizard
+        Command::Reword { .. } | Command::Pr { .. } | Command::Squash { .. } | Command::Stats { .. }
+        | Command::Show { .. } | Command::Search { .. } | Command::Fixup { .. } | Command::Help | Command::Config { .. } => {
+            Ok(Prefill::default())
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use serde_json::{json, Value};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::process::{Command, Output};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Longest stdout/stderr kept per git call (diffs can be huge)
+const MAX_OUTPUT: usize = 2000;
+
+// The debug trace: one JSON object per line, appended. Nothing is written
+// unless `init` was called, since the TUI owns the terminal while running.
+static TRACE: OnceLock<Mutex<File>> = OnceLock::new();
+
+// Whether RUST_LOG asks for debug output (`debug`, `trace`, or `commitui=...`)
+pub fn requested_by_env() -> bool {
+    std::env::var("RUST_LOG").is_ok_and(|value| {
+        let value = value.to_lowercase();
+        value.contains("debug") || value.contains("trace") || value.contains("commitui")
+    })
+}
+
+// <cache dir>/commiTUI/trace.log
+pub fn default_path() -> Option<PathBuf> {
+    let mut path = dirs::cache_dir()?;
+    path.push("commiTUI");
+    path.push("trace.log");
+    Some(path)
+}
+
+pub fn init(path: &PathBuf) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let _ = TRACE.set(Mutex::new(file));
+    event("start", json!({ "version": env!("CARGO_PKG_VERSION"), "args": std::env::args().collect::<Vec<_>>() }));
+    Ok(())
+}
+
+pub fn enabled() -> bool {
+    TRACE.get().is_some()
+}
+
+// Record `kind` with its details; a failed write is ignored, tracing must never break a commit
+pub fn event(kind: &str, details: Value) {
+    let Some(file) = TRACE.get() else { return };
+    let time = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs_f64()).unwrap_or(0.0);
+    let mut record = json!({ "time": time, "event": kind });
+    if let (Some(record), Value::Object(details)) = (record.as_object_mut(), details) {
+        record.extend(details);
+    }
+    if let Ok(mut file) = file.lock() {
+        let _ = writeln!(file, "{}", record);
+    }
+}
+
+fn truncated(bytes: &[u8]) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    match text.char_indices().nth(MAX_OUTPUT) {
+        Some((end, _)) => format!("{}… ({} bytes)", &text[..end], bytes.len()),
+        None => text.into_owned(),
+    }
+}
+
+// `Command::output` that also records the invocation and its result
+pub trait TracedCommand {
+    fn traced_output(&mut self) -> io::Result<Output>;
+}
+
+impl TracedCommand for Command {
+    fn traced_output(&mut self) -> io::Result<Output> {
+        let result = self.output();
+        if enabled() {
+            let program = self.get_program().to_string_lossy().into_owned();
+            let args: Vec<String> = self.get_args().map(|a| a.to_string_lossy().into_owned()).collect();
+            match &result {
+                Ok(output) => event("command", json!({
+                    "program": program,
+                    "args": args,
+                    "status": output.status.code(),
+                    "stdout": truncated(&output.stdout),
+                    "stderr": truncated(&output.stderr),
+                })),
+                Err(e) => event("command", json!({ "program": program, "args": args, "error": e.to_string() })),
+            }
+        }
+        result
+    }
+}
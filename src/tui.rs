@@ -1,675 +1,1036 @@
-use crate::config::Config;
-use crate::state::{AppState, Step};
-use crate::validation::validate_subject;
+use commitui::config::{save_list, Config, ConfigProblem, LOCAL_CONFIG};
+use commitui::message::CommitMessage;
+use commitui::git::{CommitOptions, GitError};
+use commitui::i18n::{t, tf};
+use crate::app::{count_branch_violations, Action, App};
+use crate::jobs::JobResult;
+use crate::history::history_screen;
+use crate::manage::{manage_screen, ManagedList};
+use crate::reword::reword_from_wizard;
+use commitui::preflight::{ContentWarning, RepoWarning, SigningProblem};
+use commitui::state::AppState;
+use commitui::{remembered, trace};
+use serde_json::json;
 use ratatui::{
-    backend::CrosstermBackend,
+    backend::{Backend, CrosstermBackend},
+    Frame,
     Terminal,
-    widgets::{Block, Borders, List, ListItem, Paragraph, Wrap},
-    style::{Style, Color},
+    text::Text,
+    widgets::{Block, Borders, Clear, Paragraph, Wrap},
+    style::{Style, Color, Modifier},
     layout::{Layout, Constraint, Direction, Rect},
 };
 use crossterm::{
     event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    cursor::Show,
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
 };
 use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
-fn is_scope_selectable(scopes_slice: &[String], idx: usize) -> bool {
-    let s = &scopes_slice[idx];
-    !s.starts_with('─')
+// A box of the given height, `percent_x` wide, centred in `area` (for popups)
+pub fn centered_rect(area: Rect, percent_x: u16, height: u16) -> Rect {
+    let width = area.width * percent_x / 100;
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
 
-fn next_selectable_scope(scopes_slice: &[String], mut idx: usize, dir: i32) -> usize {
-    loop {
-        let new_idx = if dir > 0 {
-            if idx + 1 >= scopes_slice.len() { return idx; }
-            idx + 1
-        } else {
-            if idx == 0 { return idx; }
-            idx - 1
-        };
-        if is_scope_selectable(scopes_slice, new_idx) {
-            return new_idx;
+// How much the terminal can show; set once at startup by `init_display`.
+// Unset (as in tests) means full colour and Unicode.
+#[derive(Debug, Clone, Copy)]
+pub struct Display {
+    pub color: bool,
+    pub unicode: bool,
+}
+
+static DISPLAY: OnceLock<Display> = OnceLock::new();
+
+// Colour is off with a non-empty NO_COLOR or `color = false`; Unicode is off
+// when the locale isn't UTF-8
+pub fn init_display(config: &Config) {
+    let color = std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+        && config.color.unwrap_or_else(commitui::config::default_color);
+    let _ = DISPLAY.set(Display { color, unicode: locale_is_utf8() });
+}
+
+fn locale_is_utf8() -> bool {
+    // The first of these that is set decides, as for the C library
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+    match locale {
+        Some(locale) => {
+            let locale = locale.to_lowercase();
+            locale.contains("utf-8") || locale.contains("utf8")
+        }
+        None => true, // No locale at all (e.g. Windows): assume a modern terminal
+    }
+}
+
+// Strip colours and/or replace non-ASCII symbols in a finished frame.
+// Called at the end of every draw so individual widgets don't need to care.
+pub fn degrade(f: &mut Frame) {
+    let Some(display) = DISPLAY.get().copied() else { return };
+    if display.color && display.unicode {
+        return;
+    }
+    for cell in f.buffer_mut().content.iter_mut() {
+        if !display.color {
+            cell.set_fg(Color::Reset).set_bg(Color::Reset);
+        }
+        if !display.unicode && !cell.symbol().is_ascii() {
+            let ascii = cell.symbol().chars().next().map_or(' ', ascii_fallback);
+            cell.set_char(ascii);
         }
-        idx = new_idx;
     }
 }
 
-fn step_number(step: &Step) -> usize {
-    match step {
-        Step::Type => 1,
-        Step::Scope => 2,
-        Step::Subject => 3,
-        Step::Body => 4,
-        Step::Breaking => 5,
-        Step::Preview => 6,
+fn ascii_fallback(c: char) -> char {
+    match c {
+        '─' | '━' | '═' | '╌' | '╍' | '┄' | '┅' => '-',
+        '│' | '┃' | '║' | '╎' | '╏' | '┆' | '┇' => '|',
+        '\u{2500}'..='\u{257F}' => '+', // Other box drawing: corners and junctions
+        '▸' | '→' | '»' => '>',
+        '✓' | '•' | '\u{2800}'..='\u{28FF}' => '*', // Braille is the spinner
+        '…' => '.',
+        _ => '?',
     }
 }
 
-pub fn run_tui(config: Config) -> Result<String, Box<dyn std::error::Error>> {
+// The UI is drawn on stderr so stdout stays free for scripted output
+// (e.g. `msg=$(commiTUI --print-message-only)`).
+pub type Tui = Terminal<CrosstermBackend<io::BufWriter<io::Stderr>>>;
+
+pub fn setup_terminal() -> Result<Tui, Box<dyn std::error::Error>> {
     enable_raw_mode()?;
-    let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-
-    let mut state = AppState {
-        step: Step::Type,
-        selected_type: 0,
-        chosen_type: None,
-
-        selected_scope: 0,
-        custom_scope: String::new(),
-        focus_input: false, // For custom scope input
-        chosen_scope: None,
-
-        subject: String::new(),
-        
-        body: String::new(),
-        body_lines: vec![],
-        in_body: false, // Special flag for multi-line body
-
-        breaking: String::new(),
-
-        issues: String::new(),
-        focus_issues: false, // Specific for issues field in preview
-    };
+    let mut stderr = io::BufWriter::new(io::stderr());
+    execute!(stderr, EnterAlternateScreen, SetTitle("commiTUI"))?;
+    watch_suspend(true);
+    let backend = CrosstermBackend::new(stderr);
+    Ok(Terminal::new(backend)?)
+}
+
+pub fn restore_terminal(terminal: &mut Tui) -> Result<(), Box<dyn std::error::Error>> {
+    watch_suspend(false);
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    terminal.show_cursor()?;
+    Ok(())
+}
+
+// Set by a SIGTSTP from outside (`kill -TSTP`); Ctrl+Z itself arrives as a
+// key in raw mode. The handler is only there while a screen owns the
+// terminal, so the signal stops the process as usual the rest of the time.
+static SUSPEND: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+#[cfg(unix)]
+static SUSPEND_HANDLER: Mutex<Option<signal_hook::SigId>> = Mutex::new(None);
 
-    let total_steps = 6;
+fn watch_suspend(on: bool) {
+    #[cfg(unix)]
+    {
+        let flag = SUSPEND.get_or_init(|| Arc::new(AtomicBool::new(false)));
+        let mut handler = SUSPEND_HANDLER.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(id) = handler.take() {
+            signal_hook::low_level::unregister(id);
+        }
+        if on {
+            *handler = signal_hook::flag::register(signal_hook::consts::SIGTSTP, Arc::clone(flag)).ok();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = on;
+}
 
+// Only a real terminal (between `setup_terminal` and `restore_terminal`)
+// can be handed back to the shell; tests never suspend
+fn can_suspend() -> bool {
+    #[cfg(unix)]
+    return SUSPEND_HANDLER.lock().is_ok_and(|handler| handler.is_some());
+    #[cfg(not(unix))]
+    return false;
+}
+
+// Give the terminal back to the shell and stop until `fg`, then take it
+// over again and have the next draw repaint everything
+fn suspend<B: Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stderr(), LeaveAlternateScreen, Show)?;
+    #[cfg(unix)]
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    enable_raw_mode()?;
+    execute!(io::stderr(), EnterAlternateScreen)?;
+    terminal.clear()
+}
+
+// The next event for a screen's loop, with Ctrl+Z (or a SIGTSTP) handled on
+// the way: after resuming it comes back as a resize so the screen redraws
+pub fn poll_event<B: Backend>(terminal: &mut Terminal<B>, events: &mut impl EventSource) -> io::Result<Option<Event>> {
+    let event = events.next_event()?;
+    let ctrl_z = matches!(&event, Some(Event::Key(key))
+        if key.code == KeyCode::Char('z') && key.modifiers.contains(KeyModifiers::CONTROL) && key.kind == KeyEventKind::Press);
+    let signalled = SUSPEND.get().is_some_and(|flag| flag.swap(false, Ordering::Relaxed));
+    if (ctrl_z || signalled) && can_suspend() {
+        suspend(terminal)?;
+        let size = terminal.size()?;
+        return Ok(Some(Event::Resize(size.width, size.height)));
+    }
+    Ok(event)
+}
+
+// Set by SIGTERM or SIGHUP (a dropped SSH connection): the wizard saves a
+// draft and ends at its next tick, so the terminal gets restored. A second
+// signal kills the process as usual.
+static TERMINATE: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+fn watch_signals() {
+    TERMINATE.get_or_init(|| {
+        let flag = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        for signal in [signal_hook::consts::SIGTERM, signal_hook::consts::SIGHUP] {
+            let _ = signal_hook::flag::register_conditional_shutdown(signal, 1, Arc::clone(&flag));
+            let _ = signal_hook::flag::register(signal, Arc::clone(&flag));
+        }
+        flag
+    });
+}
+
+fn terminated() -> bool {
+    TERMINATE.get().is_some_and(|flag| flag.load(Ordering::Relaxed))
+}
+
+// Where key presses come from: the real terminal, or a script in tests
+pub trait EventSource {
+    // Wait briefly for the next event; None if nothing happened in time
+    fn next_event(&mut self) -> io::Result<Option<Event>>;
+}
+
+pub struct CrosstermEvents;
+
+impl EventSource for CrosstermEvents {
+    fn next_event(&mut self) -> io::Result<Option<Event>> {
+        if event::poll(Duration::from_millis(100))? {
+            Ok(Some(event::read()?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+// Returns None if the user quit instead of confirming.
+// `state` is kept by the caller so the wizard can be resumed after a failed commit.
+// `options` is None in editor mode, where git itself owns the commit invocation.
+// `git_comments` are the commented status lines git wrote into COMMIT_EDITMSG.
+pub fn run_tui(
+    config: &mut Config,
+    state: &mut AppState,
+    options: Option<&mut CommitOptions>,
+    git_comments: &[String],
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    watch_signals();
+    let mut terminal = setup_terminal()?;
+    let result = run_wizard(&mut terminal, &mut CrosstermEvents, config, state, options, git_comments);
+    // Restore terminal before returning, even if the wizard failed
+    restore_terminal(&mut terminal)?;
+
+    Ok(result?.then(|| build_message(state, config)))
+}
+
+// The wizard itself, independent of the terminal backend and event source.
+// Returns true when the user confirmed on the preview step, false when they quit.
+// Ctrl+R replaces `config` with a fresh load of the config files.
+pub fn run_wizard<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    config: &mut Config,
+    state: &mut AppState,
+    mut options: Option<&mut CommitOptions>,
+    git_comments: &[String],
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut notice = None;
     loop {
-        // --- DRAWING ---
-        terminal.draw(|f| {
-            let size = f.size();
-            let progress = format!(
-                "Step {}/{}",
-                step_number(&state.step),
-                total_steps
-            );
-            let progress_paragraph = Paragraph::new(progress)
-                .style(Style::default().fg(Color::Cyan));
-            let chunks_outer = Layout::default()
-                .direction(Direction::Vertical)
-                .constraints([
-                    Constraint::Length(1), // For progress indicator
-                    Constraint::Min(1),    // For the main content
-                ])
-                .split(size);
-            f.render_widget(progress_paragraph, chunks_outer[0]);
-
-            let area = chunks_outer[1]; // Main drawing area
-
-            match state.step {
-                Step::Type => {
-                    // Get types slice, defaulting to empty if config.types is None
-                    let types_slice = config.types.as_ref().map(|v| v.as_slice()).unwrap_or(&[]);
-                    let items: Vec<ListItem> = types_slice
-                        .iter()
-                        .map(|ty| ListItem::new(ty.as_str())) // ty is &String, as_str() makes &str
-                        .collect();
-                    let mut list_state = ratatui::widgets::ListState::default();
-                    list_state.select(Some(state.selected_type));
-                    let list = List::new(items)
-                        .block(Block::default().title("Select Commit Type (Enter to confirm, q/Esc/Ctrl+C to quit)").borders(Borders::ALL))
-                        .highlight_style(Style::default().bg(Color::Blue))
-                        .highlight_symbol(">> ");
-                    f.render_stateful_widget(list, area, &mut list_state);
+        match wizard_loop(terminal, events, config, state, options.as_deref_mut(), git_comments, notice.take())? {
+            Some(confirmed) => return Ok(confirmed),
+            None => {
+                // Language and colours were fixed at startup and stay as they are
+                let (reloaded, problems, _) = crate::load_config();
+                *config = reloaded;
+                state.revalidate(config);
+                notice = Some(tf("text.config_reloaded", &[("count", &problems.len().to_string())]));
+                if trace::enabled() {
+                    trace::event("reload", json!({
+                        "problems": problems.iter().map(|p| p.to_string()).collect::<Vec<_>>(),
+                        "step": state.step.name(),
+                    }));
                 }
-                Step::Scope => {
-                    // Get scopes slice, defaulting to empty if config.scopes is None
-                    let scopes_slice = config.scopes.as_ref().map(|v| v.as_slice()).unwrap_or(&[]);
-                    
-                    let chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            // Use the actual length of the slice or 0 if None
-                            Constraint::Length(scopes_slice.len() as u16 + 2), 
-                            Constraint::Length(3),
-                        ])
-                        .split(area);
-
-                    let items: Vec<ListItem> = scopes_slice
-                        .iter()
-                        .map(|s| {
-                            if s.starts_with('─') {
-                                ListItem::new(s.as_str()).style(Style::default().fg(Color::DarkGray)) // s is &String, as_str() makes &str
-                            } else {
-                                ListItem::new(s.as_str()) // s is &String, as_str() makes &str
-                            }
-                        })
-                        .collect();
-                    let mut list_state = ratatui::widgets::ListState::default();
-                    list_state.select(Some(state.selected_scope));
-                    let list = List::new(items)
-                        .block(Block::default().title("Select Scope").borders(Borders::ALL))
-                        .highlight_style(Style::default().bg(Color::Blue))
-                        .highlight_symbol(">> ");
-                    f.render_stateful_widget(list, chunks[0], &mut list_state);
-
-                    let input_block = if state.focus_input {
-                        Block::default()
-                            .title("Or type a custom scope (Tab to switch, Enter to confirm, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green))
-                    } else {
-                        Block::default()
-                            .title("Or type a custom scope (Tab to switch, Enter to confirm, b/Left to go back, q/Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                    };
-                    let paragraph = Paragraph::new(state.custom_scope.as_str())
-                        .block(input_block)
-                        .style(Style::default().fg(Color::Yellow));
-                    f.render_widget(paragraph, chunks[1]);
+            }
+        }
+    }
+}
+
+// One run of the wizard against a fixed config; None when the user asked for a reload
+fn wizard_loop<B: Backend>(
+    terminal: &mut Terminal<B>,
+    events: &mut impl EventSource,
+    config: &Config,
+    state: &mut AppState,
+    options: Option<&mut CommitOptions>,
+    git_comments: &[String],
+    notice: Option<String>,
+) -> Result<Option<bool>, Box<dyn std::error::Error>> {
+    let mut app = App::new(config, state, options, git_comments);
+    app.notice = notice;
+    if app.state.draft_restored {
+        app.state.draft_restored = false;
+        app.notice = app.notice.take().or_else(|| Some(t("text.draft_restored").to_string()));
+    }
+
+    // `idle_lock_minutes` without a key saves a draft and locks the screen;
+    // the next key unlocks it (and drops the draft again)
+    let idle_minutes = config.idle_lock_minutes.unwrap_or_else(commitui::config::default_idle_lock_minutes);
+    let idle_after = Duration::from_secs(idle_minutes.saturating_mul(60));
+    let mut last_key = Instant::now();
+    let mut locked = false;
+
+    // Only redraw when something changed: a key, a resize, or a running job's
+    // spinner. Idle poll ticks leave the screen alone (less flicker over SSH).
+    let mut dirty = true;
+    let confirmed = 'wizard: loop {
+        if terminated() {
+            let _ = remembered::save_draft(&app.state.draft());
+            break 'wizard Some(false);
+        }
+        if dirty {
+            terminal.draw(|f| {
+                app.draw(f);
+                if locked {
+                    draw_lock_screen(f, idle_minutes);
                 }
-                Step::Subject => {
-                    let block = if state.focus_input {
-                        Block::default()
-                            .title("Enter Subject (Tab to navigate, Enter to confirm, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green))
-                    } else {
-                        Block::default()
-                            .title("Subject (Tab to edit, b/Left to go back, Enter to confirm, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green))
-                    };
-                    let paragraph = Paragraph::new(state.subject.as_str())
-                        .block(block)
-                        .style(Style::default().fg(Color::Yellow));
-                    f.render_widget(paragraph, area); // Use `area` for rendering
-
-                    let validation_msg = validate_subject(&state.subject, &config); // Pass config here
-                    if let Some(ref msg) = validation_msg {
-                        let warn = Paragraph::new(msg.as_str())
-                            .block(Block::default().borders(Borders::ALL).title("Validation Error"))
-                            .style(Style::default().fg(Color::Red));
-                        let warn_area = Rect {
-                            x: area.x,
-                            y: area.y + area.height.saturating_sub(3),
-                            width: area.width,
-                            height: 3,
-                        };
-                        f.render_widget(warn, warn_area);
+                degrade(f);
+            })?;
+        }
+
+        let mut actions = Vec::new();
+        dirty = match poll_event(terminal, events)? {
+            Some(Event::Key(key)) if locked => {
+                // The key that wakes the wizard does nothing else
+                if key.kind == KeyEventKind::Press {
+                    locked = false;
+                    last_key = Instant::now();
+                    remembered::clear_draft();
+                }
+                true
+            }
+            Some(Event::Key(key)) => {
+                last_key = Instant::now();
+                let from = app.state.step.name();
+                let action = app.handle_key(key);
+                if trace::enabled() {
+                    trace::event("key", json!({
+                        "code": format!("{:?}", key.code),
+                        "modifiers": format!("{:?}", key.modifiers),
+                        "kind": format!("{:?}", key.kind),
+                        "step": from,
+                        "action": match &action {
+                            Action::OpenHistory(entries) => format!("OpenHistory({} commits)", entries.len()),
+                            action => format!("{:?}", action),
+                        },
+                    }));
+                    if app.state.step.name() != from {
+                        trace::event("step", json!({ "from": from, "to": app.state.step.name() }));
                     }
                 }
-                Step::Body => {
-                    let block = if state.focus_input {
-                        Block::default()
-                            .title("Enter Body (Tab to navigate, Enter for new line, Empty line to finish, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green))
-                    } else {
-                        Block::default()
-                            .title("Body (Tab to edit, b/Left to go back, Enter for new line, Empty line to finish, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green))
-                    };
-                    let body_text = if state.body_lines.is_empty() && state.body.is_empty() {
-                        String::from("<empty>")
-                    } else {
-                        let mut all = state.body_lines.join("\n");
-                        if !state.body.is_empty() {
-                            if !all.is_empty() {
-                                all.push('\n');
-                            }
-                            all.push_str(&state.body);
-                        }
-                        all
+                actions.push(action);
+                true
+            }
+            Some(Event::Resize(..)) => true,
+            _ => false,
+        };
+        // Background jobs finish between key presses
+        dirty |= app.job.is_some() || app.loading.is_some() || app.animating();
+        actions.extend(app.poll_job());
+        if !locked && idle_minutes > 0 && last_key.elapsed() >= idle_after {
+            let _ = remembered::save_draft(&app.state.draft());
+            locked = true;
+            dirty = true;
+        }
+
+        for action in actions {
+            match action {
+                Action::Continue => {}
+                Action::Confirm => break 'wizard Some(true),
+                Action::Quit => break 'wizard Some(false),
+                Action::ReloadConfig => break 'wizard None,
+                Action::Reword(range) => {
+                    // Edits in the review's own wizard may reload a copy of the config
+                    let mut config = config.clone();
+                    app.notice = match reword_from_wizard(terminal, events, &mut config, &range) {
+                        Ok(summary) => summary,
+                        Err(e) => Some(e.to_string()),
                     };
-                    let paragraph = Paragraph::new(body_text.as_str()) // Use as_str() here
-                        .block(block)
-                        .style(Style::default().fg(Color::Yellow))
-                        .wrap(Wrap { trim: false });
-                    f.render_widget(paragraph, area);
+                    let config = app.config.clone();
+                    app.start_job(t("jobs.violations"), move || JobResult::Violations(count_branch_violations(&config)));
                 }
-                Step::Breaking => {
-                    let block = if state.focus_input {
-                        Block::default()
-                            .title("Enter Breaking Changes (Tab to navigate, Enter to confirm, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Red))
-                    } else {
-                        Block::default()
-                            .title("Breaking Changes (Tab to edit, b/Left to go back, Enter to confirm, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Red))
+                Action::Manage(list) => {
+                    let items = match list {
+                        ManagedList::Types => config.types.clone(),
+                        ManagedList::Scopes => config.scopes.clone(),
                     };
-                    let paragraph = Paragraph::new(state.breaking.as_str())
-                        .block(block)
-                        .style(Style::default().fg(Color::Red));
-                    f.render_widget(paragraph, area);
-                }
-                Step::Preview => {
-                    let chunks = Layout::default()
-                        .direction(Direction::Vertical)
-                        .constraints([
-                            Constraint::Min(5),
-                            Constraint::Length(3),
-                        ])
-                        .split(area); // Use `area` for splitting
-
-                    let type_str = state.chosen_type.as_deref().unwrap_or("");
-                    let scope_str = state.chosen_scope.as_deref().unwrap_or("");
-                    let mut preview = String::new();
-
-                    if state.chosen_scope.is_none() || scope_str.is_empty() {
-                        preview = format!("{}: {}", type_str, state.subject);
-                    } else {
-                        preview = format!("{}({}): {}", type_str, scope_str, state.subject);
-                    }
-
-                    let mut full_preview = preview.clone();
-                    // Body
-                    if !state.body_lines.is_empty() || !state.body.is_empty() {
-                        full_preview.push_str("\n\n"); // Ensure 2 newlines after subject line
-                        full_preview.push_str(&state.body_lines.join("\n"));
-                        if !state.body.is_empty() {
-                            if !state.body_lines.is_empty() { // Add newline if there were previous body lines
-                                full_preview.push('\n');
-                            }
-                            full_preview.push_str(&state.body);
-                        }
-                    }
-                    // Breaking Change
-                    if !state.breaking.trim().is_empty() {
-                        // Ensure two newlines before if previous content
-                        if full_preview.ends_with('\n') && !full_preview.ends_with("\n\n") {
-                            full_preview.push('\n'); // Add one more to make it two
-                        } else if !full_preview.is_empty() {
-                            full_preview.push_str("\n\n");
+                    if let Some(items) = manage_screen(terminal, events, list, &items.unwrap_or_default())? {
+                        // The groups from the files only; workspace packages are added on load
+                        let groups = match list {
+                            ManagedList::Types => Vec::new(),
+                            ManagedList::Scopes => Config::load_checked(config.convention).0.scope_groups.unwrap_or_default(),
+                        };
+                        match save_list(Path::new(LOCAL_CONFIG), list.key(), &items, &groups) {
+                            Ok(()) => break 'wizard None, // Reloaded with the new list
+                            Err(e) => app.notice = Some(tf("text.save_failed", &[("path", LOCAL_CONFIG), ("error", &e.to_string())])),
                         }
-                        full_preview.push_str(&format!("BREAKING CHANGE: {}", state.breaking.trim()));
                     }
-                    // Issues
-                    if !state.issues.trim().is_empty() {
-                        // Ensure two newlines before if previous content
-                        if full_preview.ends_with('\n') && !full_preview.ends_with("\n\n") {
-                            full_preview.push('\n'); // Add one more to make it two
-                        } else if !full_preview.is_empty() {
-                            full_preview.push_str("\n\n");
-                        }
-                        full_preview.push_str(&state.issues.trim());
+                }
+                Action::OpenHistory(entries) => {
+                    if let Some(prefill) = history_screen(terminal, events, &entries)? {
+                        app.state.apply_prefill(config, prefill);
                     }
+                }
+            }
+        }
+    };
 
+    Ok(confirmed)
+}
 
-                    let paragraph = Paragraph::new(full_preview.as_str()) // Use as_str() here
-                        .block(Block::default()
-                            .title("Preview Commit Message (Tab to edit issues, y/Enter to confirm, b/Left to go back, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green)))
-                        .style(Style::default().fg(Color::Yellow))
-                        .wrap(Wrap { trim: false });
-                    f.render_widget(paragraph, chunks[0]);
-
-                    let input_block = if state.focus_issues {
-                        Block::default()
-                            .title("Issue References (Tab to switch, Enter to confirm)")
-                            .borders(Borders::ALL)
-                            .border_style(Style::default().fg(Color::Green))
-                    } else {
-                        Block::default()
-                            .title("Issue References (Tab to edit, y/Enter to confirm, b/Left to go back, Esc/Ctrl+C to quit)")
-                            .borders(Borders::ALL)
-                    };
-                    let issues_paragraph = Paragraph::new(state.issues.as_str())
-                        .block(input_block)
-                        .style(Style::default().fg(Color::Yellow));
-                    f.render_widget(issues_paragraph, chunks[1]);
-                }
+// The wizard dimmed behind a note that it's locked and the draft is saved
+fn draw_lock_screen(f: &mut Frame, minutes: u64) {
+    for cell in f.buffer_mut().content.iter_mut() {
+        cell.set_style(Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM));
+    }
+    let area = centered_rect(f.size(), 60, 5);
+    let lock = Paragraph::new(tf("text.locked", &[("minutes", &minutes.to_string())]))
+        .block(Block::default().title(t("titles.locked")).borders(Borders::ALL))
+        .style(Style::default().fg(Color::Yellow))
+        .wrap(Wrap { trim: true });
+    f.render_widget(Clear, area);
+    f.render_widget(lock, area);
+}
+
+// Build the commit message string from the wizard answers
+pub fn build_message(state: &AppState, config: &Config) -> String {
+    CommitMessage::from_state(state).format_with(config)
+}
+
+// What to do after `git commit` failed (usually a pre-commit or commit-msg hook)
+#[derive(Debug, Clone, PartialEq)]
+pub enum FailureAction {
+    Retry,
+    Edit,
+    Unsigned, // Retry with signing turned off
+    Abort,
+}
+
+// Scrollable screen showing git's captured output, so it doesn't get lost
+// behind terminal restoration.
+pub fn show_commit_failure(output: &str) -> Result<FailureAction, Box<dyn std::error::Error>> {
+    show_failure(output, t("titles.commit_failed_scroll"), t("hints.commit_failure"), false)
+}
+
+// The same for gpg/ssh signing failures (gpg-agent without a usable pinentry,
+// a wrong passphrase, ...), with what to fix and a way to commit unsigned
+pub fn show_signing_failure(output: &str) -> Result<FailureAction, Box<dyn std::error::Error>> {
+    let text = format!("{}\n\n{}", t("text.signing_failed"), output);
+    show_failure(&text, t("titles.signing_failed_scroll"), t("hints.signing_failure"), true)
+}
+
+// A git command that failed outside the wizard (starting a revert, staging a
+// plan, ...), with its full output; any of the abort keys leaves
+pub fn show_git_error(error: &GitError) -> Result<(), Box<dyn std::error::Error>> {
+    let status = error.status.map_or_else(|| "killed".to_string(), |code| code.to_string());
+    let text = format!("$ {}\n{}\n\n{}", error.command, tf("text.git_status", &[("status", &status)]), error.details());
+    show_failure(&text, t("titles.git_failed_scroll"), t("hints.git_failure"), false).map(|_| ())
+}
+
+fn show_failure(output: &str, title: &str, hints: &str, signing: bool) -> Result<FailureAction, Box<dyn std::error::Error>> {
+    let mut terminal = setup_terminal()?;
+    let line_count = output.lines().count() as u16;
+    let mut scroll: u16 = 0;
+
+    let action = loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let paragraph = Paragraph::new(Text::raw(output))
+                .wrap(Wrap { trim: false })
+                .block(Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Red)))
+                .scroll((scroll, 0));
+            f.render_widget(paragraph, chunks[0]);
+
+            let hints = Paragraph::new(hints)
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[1]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(&mut terminal, &mut CrosstermEvents)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
             }
+            match key.code {
+                KeyCode::Char('r') => break FailureAction::Retry,
+                KeyCode::Char('e') => break FailureAction::Edit,
+                KeyCode::Char('u') if signing => break FailureAction::Unsigned,
+                KeyCode::Char('a') | KeyCode::Char('q') | KeyCode::Esc => break FailureAction::Abort,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break FailureAction::Abort,
+                KeyCode::Down => scroll = (scroll + 1).min(line_count.saturating_sub(1)),
+                KeyCode::Up => scroll = scroll.saturating_sub(1),
+                KeyCode::PageDown => scroll = (scroll + 10).min(line_count.saturating_sub(1)),
+                KeyCode::PageUp => scroll = scroll.saturating_sub(10),
+                _ => {}
+            }
+        }
+    };
+
+    restore_terminal(&mut terminal)?;
+    Ok(action)
+}
+
+// Shown before the wizard when the repository is mid-merge, detached, ...
+// Returns whether the user chose to continue anyway.
+pub fn show_repo_warnings(warnings: &[RepoWarning]) -> Result<bool, Box<dyn std::error::Error>> {
+    let text = warnings.iter().map(|w| format!("• {}", w.describe())).collect::<Vec<_>>().join("\n\n");
+    show_notice(t("titles.repo_state"), &text, t("hints.repo_warnings"), true)
+}
+
+// Shown before the wizard when staged files match the content checks. Only an
+// explicit `c` goes on, so a reflexive Enter doesn't commit a key.
+pub fn show_content_warnings(warnings: &[ContentWarning]) -> Result<bool, Box<dyn std::error::Error>> {
+    let text = warnings.iter().map(|w| format!("• {}", w.describe())).collect::<Vec<_>>().join("\n");
+    show_notice(t("titles.staged_content"), &text, t("hints.staged_content"), false)
+}
+
+// Shown before the wizard when the commit would be signed with a key git can't
+// find. Returns whether to go on without signing.
+pub fn show_signing_problem(problem: &SigningProblem) -> Result<bool, Box<dyn std::error::Error>> {
+    show_notice(t("titles.signing"), &problem.describe(), t("hints.signing"), false)
+}
+
+// Shown before the wizard when an interrupted session left a draft. Returns
+// whether to resume it; declining drops it.
+pub fn show_resume_draft(draft: &remembered::Draft) -> Result<bool, Box<dyn std::error::Error>> {
+    let answers = &draft.answers;
+    let header = match (&answers.commit_type, &answers.scope) {
+        (Some(commit_type), Some(scope)) => format!("{}({}): ", commit_type, scope),
+        (Some(commit_type), None) => format!("{}: ", commit_type),
+        _ => String::new(),
+    } + answers.subject.as_deref().unwrap_or_default();
+    let minutes = (draft.age().as_secs() / 60).to_string();
+    let branch = draft.branch.as_deref().unwrap_or("a detached HEAD");
+    let text = tf("text.resume_draft", &[("branch", branch), ("minutes", &minutes), ("header", &header)]);
+    show_notice(t("titles.resume_draft"), &text, t("hints.resume_draft"), true)
+}
+
+// Shown before the wizard when nothing is staged; only an explicit `c` goes on
+// to make an empty commit. Returns whether to.
+pub fn show_empty_commit() -> Result<bool, Box<dyn std::error::Error>> {
+    show_notice(t("titles.empty_commit"), t("preflight.nothing_staged"), t("hints.empty_commit"), false)
+}
+
+// Shown before the wizard on a `protected_branches` branch; only an explicit
+// `c` goes on. Returns whether to commit to it anyway.
+pub fn show_protected_branch(branch: &str, pattern: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let text = tf("preflight.protected_branch", &[("branch", branch), ("pattern", pattern)]);
+    show_notice(t("titles.protected_branch"), &text, t("hints.protected_branch"), false)
+}
+
+// Shown outside a git repository: the message can still be written, just not
+// committed. Returns whether to go on and compose it.
+pub fn show_not_a_repo() -> Result<bool, Box<dyn std::error::Error>> {
+    show_notice(t("titles.not_a_repo"), t("text.not_a_repo"), t("hints.not_a_repo"), true)
+}
+
+// Shown at startup when a config file has mistakes (instead of a warning
+// that would vanish behind the alternate screen). Returns whether to go on.
+pub fn show_config_problems(problems: &[ConfigProblem]) -> Result<bool, Box<dyn std::error::Error>> {
+    let text = problems.iter().map(|p| format!("• {}", p)).collect::<Vec<_>>().join("\n");
+    show_notice(t("titles.config_problems"), &text, t("hints.config_problems"), true)
+}
+
+// A yellow box of text with continue/abort keys
+fn show_notice(title: &str, text: &str, hints: &str, enter_continues: bool) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut terminal = setup_terminal()?;
+
+    let proceed = loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Min(3),
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let paragraph = Paragraph::new(text)
+                .block(Block::default()
+                    .title(title)
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)))
+                .wrap(Wrap { trim: false });
+            f.render_widget(paragraph, chunks[0]);
+
+            let hints = Paragraph::new(hints)
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[1]);
+            degrade(f);
         })?;
 
-        // --- EVENT HANDLING ---
-        if event::poll(std::time::Duration::from_millis(100))? {
-            if let Event::Key(key) = event::read()? {
-                if key.kind == KeyEventKind::Press {
-                    // Global quit hotkeys (Esc or Ctrl+C) always work
-                    if (key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL))
-                        || key.code == KeyCode::Esc
-                    {
-                        break;
-                    }
+        if let Some(Event::Key(key)) = poll_event(&mut terminal, &mut CrosstermEvents)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break false,
+                KeyCode::Char('c') => break true,
+                KeyCode::Enter if enter_continues => break true,
+                KeyCode::Char('q') | KeyCode::Esc => break false,
+                _ => {}
+            }
+        }
+    };
 
-                    match state.step {
-                        Step::Type => {
-                            // Only 'q' quits here, Esc/Ctrl+C are handled globally
-                            if key.code == KeyCode::Char('q') && key.modifiers.is_empty() {
-                                break;
-                            }
-                            // Type selection doesn't have a separate "input mode"
-                            match key.code {
-                                KeyCode::Down => {
-                                    // Use .map_or(0, |v| v.len()) to get length safely from Option<Vec<String>>
-                                    let types_len = config.types.as_ref().map_or(0, |v| v.len());
-                                    state.selected_type = (state.selected_type + 1).min(types_len.saturating_sub(1));
-                                }
-                                KeyCode::Up => {
-                                    state.selected_type = state.selected_type.saturating_sub(1);
-                                }
-                                KeyCode::Enter => {
-                                    // Make sure config.types is Some before indexing
-                                    if let Some(types_vec) = config.types.as_ref() {
-                                        state.chosen_type = Some(types_vec[state.selected_type].clone());
-                                    }
-                                    state.step = Step::Scope;
-                                    state.focus_input = false; // Start scope list focused
-                                }
-                                _ => {}
-                            }
-                        }
-                        Step::Scope => {
-                            let scopes_slice = config.scopes.as_ref().map(|v| v.as_slice()).unwrap_or(&[]);
-
-                            if state.focus_input { // Custom scope input focused
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = false; // Switch to list
-                                    }
-                                    KeyCode::Enter => {
-                                        if !state.custom_scope.trim().is_empty() {
-                                            state.chosen_scope = Some(state.custom_scope.trim().to_string());
-                                        } else {
-                                            state.chosen_scope = None; // If custom input is empty, clear scope
-                                        }
-                                        state.step = Step::Subject;
-                                        state.focus_input = true; // Start subject input focused
-                                    }
-                                    KeyCode::Char(c) => {
-                                        state.custom_scope.push(c);
-                                    }
-                                    KeyCode::Backspace => {
-                                        state.custom_scope.pop();
-                                    }
-                                    _ => {}
-                                }
-                            } else { // Scope list focused
-                                // Only 'q' quits here, Esc/Ctrl+C are handled globally
-                                if key.code == KeyCode::Char('q') && key.modifiers.is_empty() {
-                                    break;
-                                }
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = true; // Switch to custom input
-                                    }
-                                    KeyCode::Down => {
-                                        state.selected_scope = next_selectable_scope(scopes_slice, state.selected_scope, 1);
-                                    }
-                                    KeyCode::Up => {
-                                        state.selected_scope = next_selectable_scope(scopes_slice, state.selected_scope, -1);
-                                    }
-                                    KeyCode::Enter => {
-                                        if is_scope_selectable(scopes_slice, state.selected_scope) {
-                                            if state.selected_scope == 0 { // "no scope" selected (always at index 0 in default)
-                                                state.chosen_scope = None;
-                                            } else {
-                                                state.chosen_scope = Some(scopes_slice[state.selected_scope].clone());
-                                            }
-                                            state.step = Step::Subject;
-                                            state.focus_input = true; // Start subject input focused
-                                        }
-                                    }
-                                    KeyCode::Char('b') | KeyCode::Left => {
-                                        state.step = Step::Type;
-                                        // Restore selected_type based on chosen_type for back nav
-                                        state.selected_type = config.types.as_ref()
-                                            .and_then(|types_vec| types_vec.iter().position(|t| Some(t) == state.chosen_type.as_ref()))
-                                            .unwrap_or(0);
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                        Step::Subject => {
-                            // `q` for quit is handled globally
-                            if state.focus_input { // Subject input focused
-                                let validation_msg = validate_subject(&state.subject, &config); // Pass config here
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = false; // Switch to navigation mode for subject
-                                    }
-                                    KeyCode::Enter => {
-                                        if validation_msg.is_none() {
-                                            state.step = Step::Body;
-                                            state.focus_input = true; // Start body input focused
-                                            state.in_body = false; // Reset multi-line body state
-                                        }
-                                    }
-                                    KeyCode::Char(c) => {
-                                        state.subject.push(c);
-                                    }
-                                    KeyCode::Backspace => {
-                                        state.subject.pop();
-                                    }
-                                    _ => {}
-                                }
-                            } else { // Navigation mode for subject
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = true; // Switch to subject input
-                                    }
-                                    KeyCode::Char('b') | KeyCode::Left => {
-                                        state.step = Step::Scope;
-                                        // Restore state for scope
-                                        let scopes_vec = config.scopes.as_ref().map(|v| v.as_slice()).unwrap_or(&[]);
-                                        state.focus_input = state.chosen_scope.is_some() && !scopes_vec.contains(state.chosen_scope.as_ref().unwrap_or(&String::new()));
-                                        state.selected_scope = scopes_vec.iter().position(|s| Some(s) == state.chosen_scope.as_ref()).unwrap_or(0);
-                                        state.custom_scope = state.chosen_scope.clone().unwrap_or_default();
-                                    }
-                                    KeyCode::Enter => {
-                                        // If enter is pressed in nav mode, it should still move forward if valid.
-                                        if validate_subject(&state.subject, &config).is_none() { // Pass config here
-                                            state.step = Step::Body;
-                                            state.focus_input = true;
-                                            state.in_body = false;
-                                        }
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                        Step::Body => {
-                            // `q` for quit is handled globally
-                            if state.focus_input { // Body input focused
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = false; // Switch to navigation mode for body
-                                    }
-                                    KeyCode::Enter => {
-                                        if state.body.is_empty() {
-                                            state.step = Step::Breaking;
-                                            state.focus_input = true; // Start breaking changes input focused
-                                        } else {
-                                            state.body_lines.push(state.body.clone());
-                                            state.body.clear();
-                                        }
-                                    }
-                                    KeyCode::Char(c) => {
-                                        state.body.push(c);
-                                    }
-                                    KeyCode::Backspace => {
-                                        state.body.pop();
-                                    }
-                                    _ => {}
-                                }
-                            } else { // Navigation mode for body
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = true; // Switch to body input
-                                    }
-                                    KeyCode::Char('b') | KeyCode::Left => {
-                                        state.step = Step::Subject;
-                                        state.focus_input = true; // Return to subject input focus
-                                    }
-                                    KeyCode::Enter => {
-                                        // If enter is pressed in nav mode, it should still move forward.
-                                        state.step = Step::Breaking;
-                                        state.focus_input = true;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                        Step::Breaking => {
-                            // `q` for quit is handled globally
-                            if state.focus_input { // Breaking changes input focused
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = false; // Switch to navigation mode for breaking
-                                    }
-                                    KeyCode::Enter => {
-                                        state.step = Step::Preview;
-                                        state.focus_issues = false; // Start preview with issues not focused
-                                    }
-                                    KeyCode::Char(c) => {
-                                        state.breaking.push(c);
-                                    }
-                                    KeyCode::Backspace => {
-                                        state.breaking.pop();
-                                    }
-                                    _ => {}
-                                }
-                            } else { // Navigation mode for breaking
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_input = true; // Switch to breaking changes input
-                                    }
-                                    KeyCode::Char('b') | KeyCode::Left => {
-                                        state.step = Step::Body;
-                                        state.focus_input = true; // Return to body input focus
-                                    }
-                                    KeyCode::Enter => {
-                                        state.step = Step::Preview;
-                                        state.focus_issues = false;
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                        Step::Preview => {
-                            // `q` for quit is handled globally
-                            if state.focus_issues { // Issues input focused
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_issues = false; // Switch to preview navigation
-                                    }
-                                    KeyCode::Enter => {
-                                        // Confirm and exit
-                                        break;
-                                    }
-                                    KeyCode::Char(c) => {
-                                        state.issues.push(c);
-                                    }
-                                    KeyCode::Backspace => {
-                                        state.issues.pop();
-                                    }
-                                    KeyCode::Char('b') | KeyCode::Left => {
-                                        state.focus_issues = false; // Leave issue input
-                                        state.step = Step::Breaking; // Go back
-                                        state.focus_input = true; // Return to breaking input focus
-                                    }
-                                    _ => {}
-                                }
-                            } else { // Preview navigation
-                                match key.code {
-                                    KeyCode::Tab => {
-                                        state.focus_issues = true; // Switch to issues input
-                                    }
-                                    KeyCode::Char('y') | KeyCode::Enter => {
-                                        // Confirm and exit
-                                        break;
-                                    }
-                                    KeyCode::Char('b') | KeyCode::Left => {
-                                        state.step = Step::Breaking; // Go back
-                                        state.focus_input = true; // Return to breaking input focus
-                                    }
-                                    _ => {}
-                                }
-                            }
-                        }
-                    }
+    restore_terminal(&mut terminal)?;
+    Ok(proceed)
+}
+
+// Offered after a release commit: the tag name (editable) and whether to push it.
+// None if the user skipped tagging.
+pub fn show_tag_prompt(suggested: &str) -> Result<Option<(String, bool)>, Box<dyn std::error::Error>> {
+    let mut terminal = setup_terminal()?;
+    let mut name = suggested.to_string();
+    let mut push = false;
+
+    let result = loop {
+        terminal.draw(|f| {
+            let chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([
+                    Constraint::Length(3), // Tag name
+                    Constraint::Length(1), // Push toggle
+                    Constraint::Min(0),
+                    Constraint::Length(1), // Key hints
+                ])
+                .split(f.size());
+
+            let input = Paragraph::new(name.as_str())
+                .block(Block::default()
+                    .title(t("titles.tag_name"))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)));
+            f.render_widget(input, chunks[0]);
+            f.set_cursor(chunks[0].x + 1 + name.chars().count() as u16, chunks[0].y + 1);
+
+            let toggle = Paragraph::new(tf("text.push_tag", &[("mark", if push { "x" } else { " " })]));
+            f.render_widget(toggle, chunks[1]);
+
+            let hints = Paragraph::new(t("hints.tag"))
+                .style(Style::default().fg(Color::Cyan));
+            f.render_widget(hints, chunks[3]);
+            degrade(f);
+        })?;
+
+        if let Some(Event::Key(key)) = poll_event(&mut terminal, &mut CrosstermEvents)? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match key.code {
+                KeyCode::Esc => break None,
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break None,
+                KeyCode::Enter if !name.trim().is_empty() => break Some((name.trim().to_string(), push)),
+                KeyCode::Tab => push = !push,
+                KeyCode::Backspace => {
+                    name.pop();
                 }
+                KeyCode::Char(c) if !c.is_whitespace() => name.push(c), // Tag names can't contain spaces
+                _ => {}
             }
         }
-        // Special handling for multi-line body input state
-        if matches!(state.step, Step::Body) && !state.in_body {
-            state.body.clear(); // Clear current line when entering body step for first time
-            state.in_body = true;
-            state.focus_input = true; // Ensure body input starts focused
-        }
-        if !matches!(state.step, Step::Body) {
-            state.in_body = false;
+    };
+
+    restore_terminal(&mut terminal)?;
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reword::reword_screen;
+    use crate::review::review_screen;
+    use crate::finder::search_screen;
+    use crate::help::help_lines;
+    use crate::manage::{manage_screen, ManagedList};
+    use crate::fixup::{fixup_screen, FixupChoice};
+    use crate::squash::{pruned, prune_screen};
+    use crate::staging::hunk_screen;
+    use commitui::hunks::{build_patch, parse_diff};
+    use commitui::git::LogEntry;
+    use commitui::state::{Prefill, Step};
+    use crossterm::event::KeyEvent;
+    use ratatui::backend::TestBackend;
+    use std::collections::VecDeque;
+
+    // Replays a fixed list of events; running out means the script was wrong
+    struct ScriptedEvents(VecDeque<Event>);
+
+    impl EventSource for ScriptedEvents {
+        fn next_event(&mut self) -> io::Result<Option<Event>> {
+            self.0
+                .pop_front()
+                .map(Some)
+                .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "script ran out of events"))
         }
     }
 
-    // Restore terminal before returning
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    terminal.show_cursor()?;
+    fn key(code: KeyCode) -> Event {
+        Event::Key(KeyEvent::new(code, KeyModifiers::NONE))
+    }
 
-    // Build the commit message string to return
-    let mut result = String::new();
-    if let Some(ty) = state.chosen_type {
-        if state.chosen_scope.is_none() || state.chosen_scope.as_deref().unwrap_or("").is_empty() {
-            result = format!("{}: {}", ty, state.subject);
-        } else {
-            result = format!("{}({}): {}", ty, state.chosen_scope.as_deref().unwrap(), state.subject);
-        }
+    fn ctrl(c: char) -> Event {
+        Event::Key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL))
     }
-    
-    // Append body if not empty
-    if !state.body_lines.is_empty() || !state.body.is_empty() {
-        if !result.is_empty() && !result.ends_with('\n') { // Ensure newline after subject if not already
-            result.push('\n'); 
-        }
-        // Ensure two newlines after subject/header for body
-        if !result.ends_with("\n\n") {
-             result.push_str("\n\n");
-        }
 
-        for (i, line) in state.body_lines.iter().enumerate() {
-            if i > 0 { // Don't add newline before the very first line if already starting on one
-                result.push('\n');
-            }
-            result.push_str(line);
-        }
-        if !state.body.is_empty() {
-            if !state.body_lines.is_empty() { // Only add newline if there were previous body lines
-                result.push('\n');
-            }
-            result.push_str(&state.body);
-        }
+    fn text(s: &str) -> Vec<Event> {
+        s.chars().map(|c| key(KeyCode::Char(c))).collect()
     }
-    
-    // Append footers (breaking changes, issues)
-    // Check if there was any content (subject + optional body) before footers
-    let has_previous_content = !result.trim().is_empty(); // Trim to account for leading newlines
 
-    if !state.breaking.trim().is_empty() {
-        if has_previous_content {
-            if !result.ends_with("\n\n") { result.push_str("\n\n"); }
-        } else {
-             // If breaking change is the first non-subject content, ensure 2 newlines from subject
-             if !result.ends_with("\n\n") { result.push_str("\n\n"); }
+    // Drive the wizard with `script`; returns whether it was confirmed, the final
+    // state, and the last frame drawn (the screen the final event was pressed on)
+    fn run_script(script: Vec<Event>) -> (bool, AppState, String) {
+        let mut config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut events = ScriptedEvents(script.into());
+
+        let confirmed = run_wizard(&mut terminal, &mut events, &mut config, &mut state, None, &[]).unwrap();
+        (confirmed, state, frame_text(&terminal))
+    }
+
+    fn frame_text(terminal: &Terminal<TestBackend>) -> String {
+        let buffer = terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let line: String = (0..buffer.area.width).map(|x| buffer.get(x, y).symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn escape_on_first_step_aborts() {
+        let (confirmed, state, frame) = run_script(vec![key(KeyCode::Esc)]);
+        assert!(!confirmed);
+        assert_eq!(state.step, Step::Type);
+        insta::assert_snapshot!(frame);
+    }
+
+    #[test]
+    fn walkthrough_builds_message() {
+        let mut script = vec![
+            key(KeyCode::Down),  // fix
+            key(KeyCode::Enter), // type
+            key(KeyCode::Down),  // core
+            key(KeyCode::Enter), // scope
+        ];
+        script.extend(text("handle empty input"));
+        script.extend([
+            key(KeyCode::Enter), // subject
+            key(KeyCode::Enter), // empty body
+            key(KeyCode::Enter), // no breaking change
+            key(KeyCode::Char('y')),
+        ]);
+
+        let (confirmed, state, frame) = run_script(script);
+        assert!(confirmed);
+        assert_eq!(build_message(&state, &Config::default()), "fix(core): handle empty input\n");
+        insta::assert_snapshot!(frame);
+    }
+
+    #[test]
+    fn invalid_subject_blocks_enter() {
+        let mut script = vec![key(KeyCode::Enter), key(KeyCode::Enter)];
+        script.extend(text("Add thing."));
+        script.extend([key(KeyCode::Enter), key(KeyCode::Esc)]);
+
+        let (confirmed, state, frame) = run_script(script);
+        assert!(!confirmed);
+        assert_eq!(state.step, Step::Subject);
+        insta::assert_snapshot!(frame);
+    }
+
+    #[test]
+    fn live_preview_pane_follows_typing() {
+        let mut script = vec![key(KeyCode::Enter), key(KeyCode::Enter), ctrl('p')];
+        script.extend(text("add x"));
+        script.push(key(KeyCode::Esc));
+
+        let (_, state, frame) = run_script(script);
+        assert!(state.live_preview);
+        insta::assert_snapshot!(frame);
+    }
+
+    #[test]
+    fn preview_shows_the_trailers_as_committed() {
+        let mut config = Config { interpret_trailers: Some(false), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("feat".into());
+        state.subject = "drop the v1 api".into();
+        state.body_lines = vec!["- callers move to v2".into()];
+        state.breaking = "v1 endpoints are gone".into();
+        state.issues = "Refs: #12\nRefs: #12\nCloses: #7".into();
+        state.step = Step::Preview;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut events = ScriptedEvents(vec![key(KeyCode::Esc)].into());
+        run_wizard(&mut terminal, &mut events, &mut config, &mut state, None, &[]).unwrap();
+
+        let frame = frame_text(&terminal);
+        let preview = frame.split('└').next().unwrap();
+        assert_eq!(preview.matches("Refs: #12").count(), 1);
+        insta::assert_snapshot!(frame);
+    }
+
+    #[test]
+    fn preview_colours_the_header_parts() {
+        let mut config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".into());
+        state.chosen_scope = Some("core".into());
+        state.subject = "handle empty input".into();
+        state.step = Step::Preview;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let mut events = ScriptedEvents(vec![key(KeyCode::Esc)].into());
+        run_wizard(&mut terminal, &mut events, &mut config, &mut state, None, &[]).unwrap();
+
+        let frame = frame_text(&terminal);
+        let (y, line) = frame.lines().enumerate().find(|(_, line)| line.contains("fix(core): ")).unwrap();
+        let (x, y) = (line[..line.find("fix(core)").unwrap()].chars().count() as u16, y as u16);
+        let buffer = terminal.backend().buffer();
+        let fg = |offset: u16| buffer.get(x + offset, y).fg;
+        assert_eq!([fg(0), fg(3), fg(4), fg(8), fg(11)], [Color::Cyan, Color::DarkGray, Color::Magenta, Color::DarkGray, Color::Yellow]);
+        assert!(buffer.get(x, y).modifier.contains(ratatui::style::Modifier::BOLD));
+    }
+
+    #[test]
+    fn message_template_replaces_the_conventional_layout() {
+        let config = Config { message_template: Some("[{TYPE}] {subject}\n\n{body}\n\n{footers}".into()), ..Config::default() };
+        let mut state = AppState::new(&config, Prefill::default());
+        state.chosen_type = Some("fix".into());
+        state.subject = "handle empty input".into();
+        state.breaking = "drops the old flag".into();
+
+        // The empty body leaves no extra blank lines behind
+        assert_eq!(build_message(&state, &config), "[FIX] handle empty input\n\nBREAKING CHANGE: drops the old flag\n");
+    }
+
+    #[test]
+    fn reword_fixes_a_commit_in_the_wizard() {
+        let mut config = Config::default();
+        let commits = [LogEntry { sha: "0123456789abcdef".into(), message: "wip".into() }];
+        let mut messages = vec!["wip".to_string()];
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let script = vec![
+            key(KeyCode::Char('e')),
+            key(KeyCode::Enter), // feat
+            key(KeyCode::Enter), // no scope
+            key(KeyCode::Enter), // subject kept
+            key(KeyCode::Enter), // empty body
+            key(KeyCode::Enter), // no breaking change
+            key(KeyCode::Char('y')),
+            key(KeyCode::Enter), // approve the last commit
+        ];
+        let mut events = ScriptedEvents(script.into());
+
+        let approved = reword_screen(&mut terminal, &mut events, &mut config, &commits, &[String::new()], &mut messages).unwrap();
+        assert!(approved);
+        assert_eq!(messages, ["feat: wip"]);
+    }
+
+    #[test]
+    fn review_screen_is_read_only_and_lists_the_rules_broken() {
+        let config = Config::default();
+        let mut terminal = Terminal::new(TestBackend::new(100, 30)).unwrap();
+        // Editing keys do nothing; q leaves
+        let mut events = ScriptedEvents(vec![key(KeyCode::Char('1')), key(KeyCode::Char('y')), key(KeyCode::Char('q'))].into());
+
+        review_screen(&mut terminal, &mut events, &config, "0123456789abcdef", "Fixed the login bug.").unwrap();
+        let frame = frame_text(&terminal);
+        assert!(frame.contains("Reviewing 0123456"));
+        assert!(frame.contains("Fixed the login bug."));
+        assert!(frame.contains("type:"), "{}", frame);
+
+        let mut events = ScriptedEvents(vec![key(KeyCode::Esc)].into());
+        review_screen(&mut terminal, &mut events, &config, "0123456", "fix(core): handle empty input\n\nRefs: #12").unwrap();
+        let frame = frame_text(&terminal);
+        assert!(frame.contains("Follows the commit rules"));
+        // The message as written, without the wizard's input rows
+        assert_eq!(frame.matches("Refs: #12").count(), 1);
+        assert!(!frame.contains(t("titles.issues")), "{}", frame);
+    }
+
+    #[test]
+    fn search_screen_narrows_by_type_scope_and_fuzzy_text() {
+        use commitui::parser::parse_message;
+        use commitui::search::{search, Query};
+        let entry = |sha: &str, message: &str| LogEntry { sha: sha.into(), message: message.into() };
+        let entries = vec![
+            entry("aaaaaaa1", "feat(api): add rate limiting"),
+            entry("bbbbbbb2", "fix(auth): handle expired login tokens"),
+            entry("ccccccc3", "fix(api): log in with SSO\n\nRefs: #42"),
+            entry("ddddddd4", "Update README"),
+        ];
+        let parsed: Vec<_> = entries.iter().map(|e| parse_message(&e.message)).collect();
+        assert_eq!(search(&Query::parse("type:fix"), &parsed), [1, 2]);
+        assert_eq!(search(&Query::parse("t:fix s:api"), &parsed), [2]);
+        // A whole word in the header beats a scattered match
+        assert_eq!(search(&Query::parse("login"), &parsed), [1, 2]);
+        assert_eq!(search(&Query::parse("#42"), &parsed), [2]);
+        assert_eq!(search(&Query::parse("type:docs"), &parsed), Vec::<usize>::new());
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let script = text(" scope:auth")
+            .into_iter()
+            .chain([key(KeyCode::Enter)])
+            .chain([key(KeyCode::Esc), key(KeyCode::Esc)])
+            .collect::<Vec<_>>();
+        let mut events = ScriptedEvents(script.into());
+        let mut opened = Vec::new();
+        let stat = |sha: &str| {
+            opened.push(sha.to_string());
+            " auth.rs | 2 +-".to_string()
+        };
+        search_screen(&mut terminal, &mut events, &entries, "fix", stat).unwrap();
+        assert_eq!(opened, ["bbbbbbb2"]);
+        assert!(frame_text(&terminal).contains("(1 of 4 commits)"));
+    }
+
+    #[test]
+    fn hunk_screen_stages_only_the_picked_hunk() {
+        let diff = "diff --git a/f b/f\nindex 1..2 100644\n--- a/f\n+++ b/f\n\
+            @@ -1,3 +1,3 @@\n 1\n-2\n+two\n 3\n\
+            @@ -27,3 +27,3 @@\n 27\n-28\n+twentyeight\n 29\n";
+        let files = parse_diff(diff);
+        assert_eq!(files[0].hunks.len(), 2);
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut events = ScriptedEvents(vec![key(KeyCode::Down), key(KeyCode::Char(' ')), key(KeyCode::Enter)].into());
+
+        let selected = hunk_screen(&mut terminal, &mut events, &files).unwrap().unwrap();
+        assert_eq!(selected, [[false, true]]);
+        assert_eq!(
+            build_patch(&files, &selected),
+            "diff --git a/f b/f\nindex 1..2 100644\n--- a/f\n+++ b/f\n@@ -27,3 +27,3 @@\n 27\n-28\n+twentyeight\n 29\n"
+        );
+    }
+
+    #[test]
+    fn squash_prune_drops_lines_and_their_gaps() {
+        let lines = ["feat(api): add rate limiting", "", "* feat(api): add rate limiting", "* docs: typo", "", "Refs: #12"];
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut script = vec![key(KeyCode::Char(' '))]; // On the header: nothing
+        script.extend([key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Char(' '))]);
+        script.extend([key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Char('d')), key(KeyCode::Enter)]);
+        let mut events = ScriptedEvents(script.into());
+
+        // The header can't be dropped; the docs bullet and the footer are
+        let kept = prune_screen(&mut terminal, &mut events, &lines).unwrap().unwrap();
+        assert_eq!(kept, [true, true, true, false, true, false]);
+        assert_eq!(pruned(&lines, &kept), "feat(api): add rate limiting\n\n* feat(api): add rate limiting\n");
+    }
+
+    #[test]
+    fn help_lists_every_config_key_with_its_comment() {
+        let text: Vec<String> = help_lines().iter().map(|line| line.spans.iter().map(|s| s.content.to_string()).collect()).collect();
+        for (key, _) in commitui::config::documented_keys() {
+            assert!(text.iter().any(|line| line.starts_with(&format!("   {}: ", key))), "{} is missing", key);
         }
-        result.push_str(&format!("BREAKING CHANGE: {}", state.breaking.trim()));
-    }
-    
-    if !state.issues.trim().is_empty() {
-        // If issues is the first non-subject content, ensure 2 newlines from subject
-        // Or if there was breaking change, ensure 2 newlines.
-        if !result.is_empty() && !result.ends_with("\n\n") {
-            result.push_str("\n\n");
-        } else if result.is_empty() { // This means the message is entirely empty until issues
-             // Do nothing special, issues will be the first line
+        for key in ["types", "auto_fix", "base_branch", "jira"] {
+            assert!(text.iter().any(|line| line.starts_with(&format!("   {}: ", key)) && !line.contains(t("help.undocumented"))), "{}", key);
         }
-        result.push_str(&state.issues.trim());
+        assert!(text.iter().any(|line| line.contains("Ctrl+F") && line.contains("auto_fix")));
     }
-    
-    // Ensure final newline for git to pick it up correctly
-    if !result.ends_with('\n') {
-        result.push('\n');
+
+    #[test]
+    fn manage_screen_edits_the_scope_list() {
+        let scopes: Vec<String> = ["no scope", "api", "ui"].iter().map(|s| s.to_string()).collect();
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let mut script = vec![key(KeyCode::Char('K'))]; // "api" can't go above "no scope"
+        script.push(key(KeyCode::Char('a')));
+        script.extend(text("db"));
+        script.extend([key(KeyCode::Enter), key(KeyCode::Char('K'))]);
+        script.extend([key(KeyCode::Down), key(KeyCode::Down), key(KeyCode::Char('r'))]);
+        script.extend([key(KeyCode::Backspace), key(KeyCode::Backspace)]);
+        script.extend(text("web"));
+        script.push(key(KeyCode::Enter)); // Renamed "ui"
+        script.extend([key(KeyCode::Up), key(KeyCode::Char('d')), key(KeyCode::Char('s'))]); // Deleted "api"
+        let mut events = ScriptedEvents(script.into());
+
+        let saved = manage_screen(&mut terminal, &mut events, ManagedList::Scopes, &scopes).unwrap().unwrap();
+        assert_eq!(saved, ["no scope", "db", "web"]);
     }
 
-    Ok(result)
-}
\ No newline at end of file
+    #[test]
+    fn short_terminal_drops_borders_and_filters_the_type_list() {
+        let mut config = Config::default();
+        let mut state = AppState::new(&config, Prefill::default());
+        let mut terminal = Terminal::new(TestBackend::new(60, 10)).unwrap();
+        let mut script = vec![key(KeyCode::Char('/'))];
+        script.extend(text("ref"));
+        script.extend([key(KeyCode::Enter), key(KeyCode::Esc)]);
+        let mut events = ScriptedEvents(script.into());
+
+        run_wizard(&mut terminal, &mut events, &mut config, &mut state, None, &[]).unwrap();
+        assert_eq!(state.chosen_type.as_deref(), Some("refactor"));
+        assert!(state.list_filter.is_none());
+
+        // The filter as it was typed, before Enter
+        let mut state = AppState::new(&config, Prefill::default());
+        let mut script = vec![key(KeyCode::Char('/'))];
+        script.extend(text("f"));
+        script.push(key(KeyCode::Esc));
+        let mut events = ScriptedEvents(script.into());
+        run_wizard(&mut terminal, &mut events, &mut config, &mut state, None, &[]).unwrap();
+        let frame = frame_text(&terminal);
+        assert!(!frame.contains('┌'));
+        assert!(frame.contains("/f"));
+        assert!(frame.contains(">> feat") && frame.contains("fix") && frame.contains("perf"));
+        assert!(!frame.contains("docs"));
+    }
+
+    #[test]
+    fn fixup_screen_picks_a_target_and_the_rebase() {
+        let commits = [
+            LogEntry { sha: "aaaaaaaaaa".into(), message: "fix: tidy".into() },
+            LogEntry { sha: "bbbbbbbbbb".into(), message: "feat: add it".into() },
+        ];
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let script = vec![key(KeyCode::Down), key(KeyCode::Char('r')), key(KeyCode::Char('s'))];
+        let mut events = ScriptedEvents(script.into());
+
+        let choice = fixup_screen(&mut terminal, &mut events, &commits, false).unwrap().unwrap();
+        assert_eq!(choice, FixupChoice { sha: "bbbbbbbbbb".into(), squash: true, rebase: true });
+    }
+}
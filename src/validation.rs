@@ -1,24 +1,330 @@
-use crate::config::Config; // Import Config
+use crate::config::{Config, MoodCheck}; // Import Config
+use crate::message::CommitMessage;
+use crate::parser::parse_message;
+use crate::state::SmartCommit;
+use crate::i18n::{t, tf};
+use regex::Regex;
+use std::sync::OnceLock;
 
-pub fn validate_subject(subject: &str, config: &Config) -> Option<String> {
+// Scope policy: custom scopes (`allow_custom_scope`, `scope_pattern`) and
+// per-type rules (`scope_required_for` / `scope_forbidden_for`)
+pub fn validate_scope(commit_type: Option<&str>, scope: Option<&str>, config: &Config) -> Option<String> {
+    if let Some(scope) = scope {
+        if let Some(msg) = validate_custom_scope(scope, config) {
+            return Some(msg);
+        }
+    }
+
+    let commit_type = commit_type?;
+    let listed = |types: &Option<Vec<String>>| types.as_ref().is_some_and(|t| t.iter().any(|t| t == commit_type));
+
+    match scope {
+        None if listed(&config.scope_required_for) => Some(tf("validation.scope_required", &[("type", commit_type)])),
+        Some(_) if listed(&config.scope_forbidden_for) => Some(tf("validation.scope_forbidden", &[("type", commit_type)])),
+        _ => None,
+    }
+}
+
+// Scopes from the configured list are always fine; anything else is a custom scope
+fn validate_custom_scope(scope: &str, config: &Config) -> Option<String> {
+    if config.is_listed_scope(scope) {
+        return None;
+    }
+    if !config.allow_custom_scope.unwrap_or_else(crate::config::default_allow_custom_scope) {
+        return Some(tf("validation.scope_not_listed", &[("scope", scope)]));
+    }
+    let pattern = config.scope_pattern.as_deref()?;
+    match Regex::new(pattern) {
+        Ok(re) if re.is_match(scope) => None,
+        Ok(_) => Some(tf("validation.scope_pattern", &[("scope", scope), ("pattern", pattern)])),
+        Err(e) => Some(tf("validation.scope_pattern_invalid", &[("error", &e.to_string())])),
+    }
+}
+
+// `[type_rules]` for `commit_type` win over the top-level subject rules
+pub fn validate_subject(commit_type: Option<&str>, subject: &str, config: &Config) -> Option<String> {
     // Get validation rules from config, unwrapping Options to their effective default if None.
     // This uses the defaults defined in the `default_subject_*` functions if the field
     // was not set in *any* config file (local or global).
-    let max_length = config.subject_max_length.unwrap_or_else(crate::config::default_subject_max_length);
-    let start_lowercase = config.subject_start_lowercase.unwrap_or_else(crate::config::default_subject_start_lowercase);
-    let no_ending_period = config.subject_no_ending_period.unwrap_or_else(crate::config::default_subject_no_ending_period);
+    let rules = config.type_rules(commit_type).cloned().unwrap_or_default();
+    let min_length = rules.subject_min_length.or(config.subject_min_length).unwrap_or_else(crate::config::default_subject_min_length);
+    let max_length = rules.subject_max_length.or(config.subject_max_length).unwrap_or_else(crate::config::default_subject_max_length);
+    let start_lowercase = rules.subject_start_lowercase.or(config.subject_start_lowercase).unwrap_or_else(crate::config::default_subject_start_lowercase);
+    let no_ending_period = rules.subject_no_ending_period.or(config.subject_no_ending_period).unwrap_or_else(crate::config::default_subject_no_ending_period);
+    let mood_check = rules.imperative_mood.or(config.imperative_mood).unwrap_or_else(crate::config::default_imperative_mood);
 
-    if subject.trim().is_empty() {
-        return Some("Subject must not be empty.".to_string());
+    let length = subject.trim().chars().count();
+    if length < min_length {
+        if length == 0 {
+            return Some(t("validation.subject_empty").to_string());
+        }
+        return Some(tf("validation.subject_too_short", &[("min", &min_length.to_string()), ("length", &length.to_string())]));
     }
     if subject.len() > max_length {
-        return Some(format!("Subject should be {} characters or less (currently {}).", max_length, subject.len()));
+        return Some(tf("validation.subject_too_long", &[("max", &max_length.to_string()), ("length", &subject.len().to_string())]));
     }
     if no_ending_period && subject.ends_with('.') {
-        return Some("Subject should not end with a period.".to_string());
+        return Some(t("validation.subject_period").to_string());
     }
     if start_lowercase && subject.chars().next().map(|c| c.is_uppercase()).unwrap_or(false) {
-        return Some("Subject should start with a lowercase letter.".to_string());
+        return Some(t("validation.subject_lowercase").to_string());
+    }
+    if mood_check == MoodCheck::Strict {
+        return imperative_mood_hint(subject, config);
+    }
+    None
+}
+
+// The subject with what can be fixed mechanically fixed: surrounding
+// whitespace, trailing periods and a capital first letter (unless the first
+// word is an acronym like "API"). None when there's nothing to change.
+pub fn fix_subject(commit_type: Option<&str>, subject: &str, config: &Config) -> Option<String> {
+    let rules = config.type_rules(commit_type).cloned().unwrap_or_default();
+    let start_lowercase = rules.subject_start_lowercase.or(config.subject_start_lowercase).unwrap_or_else(crate::config::default_subject_start_lowercase);
+    let no_ending_period = rules.subject_no_ending_period.or(config.subject_no_ending_period).unwrap_or_else(crate::config::default_subject_no_ending_period);
+
+    let mut fixed = subject.split_whitespace().collect::<Vec<_>>().join(" ");
+    if no_ending_period {
+        fixed = fixed.trim_end_matches('.').trim_end().to_string();
+    }
+    let first_word = fixed.split(' ').next().unwrap_or_default();
+    let acronym = first_word.chars().filter(|c| c.is_alphabetic()).count() > 1 && !first_word.chars().any(char::is_lowercase);
+    if start_lowercase && !acronym {
+        let mut chars = fixed.chars();
+        if let Some(first) = chars.next() {
+            fixed = first.to_lowercase().chain(chars).collect();
+        }
+    }
+    (fixed != subject).then_some(fixed)
+}
+
+// An overlong subject cut after its first clause: the rest becomes a body
+// paragraph. Connecting words ("because ...") stay with the rest, punctuation
+// doesn't. None when the subject fits or has no clause short enough to keep.
+pub fn split_subject(commit_type: Option<&str>, subject: &str, config: &Config) -> Option<(String, String)> {
+    const BREAKS: &[(&str, bool)] = &[
+        (", ", false), ("; ", false), (": ", false), (" - ", false), (" — ", false),
+        (" because ", true), (" so that ", true), (" since ", true), (" which ", true),
+    ];
+    let rules = config.type_rules(commit_type).cloned().unwrap_or_default();
+    let min_length = rules.subject_min_length.or(config.subject_min_length).unwrap_or_else(crate::config::default_subject_min_length);
+    let max_length = rules.subject_max_length.or(config.subject_max_length).unwrap_or_else(crate::config::default_subject_max_length);
+
+    let subject = fix_subject(commit_type, subject, config).unwrap_or_else(|| subject.to_string());
+    if subject.len() <= max_length {
+        return None;
+    }
+    let mut breaks: Vec<(usize, &str, bool)> = BREAKS
+        .iter()
+        .flat_map(|&(separator, keep)| subject.match_indices(separator).map(move |(i, _)| (i, separator, keep)))
+        .collect();
+    breaks.sort();
+    let (at, separator, keep) = breaks
+        .into_iter()
+        .find(|&(at, _, _)| subject[..at].trim_end().chars().count() >= min_length)
+        .filter(|&(at, _, _)| subject[..at].trim_end().len() <= max_length)?;
+
+    let head = subject[..at].trim_end();
+    let head = fix_subject(commit_type, head, config).unwrap_or_else(|| head.to_string());
+    let rest = if keep { &subject[at + 1..] } else { &subject[at + separator.len()..] };
+    let mut chars = rest.trim().chars();
+    let mut paragraph: String = chars.next()?.to_uppercase().chain(chars).collect();
+    if !paragraph.ends_with(['.', '!', '?']) {
+        paragraph.push('.');
+    }
+    Some((head, paragraph))
+}
+
+// The BREAKING CHANGE description, once the change is marked breaking. The
+// formatter writes the "BREAKING CHANGE:" token itself, so a pasted one would
+// end up doubled.
+pub fn validate_breaking(description: &str, config: &Config) -> Option<String> {
+    static PREFIX: OnceLock<Regex> = OnceLock::new();
+    let min_length = config.breaking_min_length.unwrap_or_else(crate::config::default_breaking_min_length);
+    let description = description.trim();
+    if description.is_empty() {
+        return Some(t("validation.breaking_required").to_string());
+    }
+    let prefix = PREFIX.get_or_init(|| Regex::new(r"(?i)^BREAKING[ -]CHANGES?\s*:").unwrap());
+    if let Some(found) = prefix.find(description) {
+        return Some(tf("validation.breaking_prefix", &[("prefix", found.as_str())]));
+    }
+    let length = description.chars().count();
+    if length < min_length {
+        return Some(tf("validation.breaking_too_short", &[("min", &min_length.to_string()), ("length", &length.to_string())]));
+    }
+    None
+}
+
+// A `!` in the header without a BREAKING CHANGE description, when
+// `bang_requires_footer` asks for both
+pub fn validate_bang(bang: bool, breaking: Option<&str>, config: &Config) -> Option<String> {
+    let required = config.bang_requires_footer.unwrap_or_else(crate::config::default_bang_requires_footer);
+    let described = breaking.is_some_and(|b| !b.trim().is_empty());
+    (required && bang && !described).then(|| t("validation.bang_requires_footer").to_string())
+}
+
+// Non-blocking advice shown under the subject (only used in `warn` mode)
+pub fn subject_warning(commit_type: Option<&str>, subject: &str, config: &Config) -> Option<String> {
+    let mood_check = config
+        .type_rules(commit_type)
+        .and_then(|rules| rules.imperative_mood)
+        .or(config.imperative_mood)
+        .unwrap_or_else(crate::config::default_imperative_mood);
+    if mood_check == MoodCheck::Warn {
+        return imperative_mood_hint(subject, config);
+    }
+    None
+}
+
+fn imperative_mood_hint(subject: &str, config: &Config) -> Option<String> {
+    let first_word = subject.split_whitespace().next()?.to_lowercase();
+    let imperative = config.imperative_words.as_ref()?.get(&first_word)?;
+    Some(tf("validation.imperative_mood", &[("imperative", imperative), ("word", &first_word)]))
+}
+
+// Footer lines led by one of `issue_keywords` ("Closes: #12, #14" or "Closes #12")
+// need at least one reference, each matching `issue_pattern` when set. Every
+// line must be a `Key: value` trailer, the only form `git interpret-trailers`
+// reads ("Closes #12" and "BREAKING CHANGE: x" aren't).
+pub fn validate_issues(issues: &str, config: &Config) -> Option<String> {
+    static TRAILER: OnceLock<Regex> = OnceLock::new();
+    let trailer = TRAILER.get_or_init(|| Regex::new(r"^[A-Za-z0-9-]+:\s*\S").unwrap());
+    let keywords = config.issue_keywords.clone().unwrap_or_else(crate::config::default_issue_keywords);
+    let pattern = match config.issue_pattern.as_deref().map(|p| (p, Regex::new(p))) {
+        Some((_, Err(e))) => return Some(tf("validation.issue_pattern_invalid", &[("error", &e.to_string())])),
+        Some((pattern, Ok(re))) => Some((pattern, re)),
+        None => None,
+    };
+
+    for line in issues.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (keyword, references) = line.split_once(':')
+            .filter(|(keyword, _)| !keyword.contains(' '))
+            .or_else(|| line.split_once(' '))
+            .unwrap_or((line, ""));
+        if let Some(keyword) = keywords.iter().find(|k| k.eq_ignore_ascii_case(keyword)) {
+            let references: Vec<&str> = references
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|r| !r.is_empty())
+                .collect();
+            if references.is_empty() {
+                return Some(tf("validation.issue_missing", &[("keyword", keyword)]));
+            }
+            if let Some((pattern, re)) = &pattern {
+                if let Some(reference) = references.iter().find(|r| !re.is_match(r)) {
+                    return Some(tf("validation.issue_pattern", &[("reference", reference), ("pattern", pattern)]));
+                }
+            }
+        }
+        if !trailer.is_match(line) {
+            return Some(tf("validation.trailer_syntax", &[("line", line)]));
+        }
+    }
+    None
+}
+
+// Smart-commit inputs: a `PROJ-123` key (from `[jira] projects` when listed)
+// whenever anything is filled in, and a Jira duration like "1d 2h 30m" for time
+pub fn validate_smart_commit(smart: &SmartCommit, config: &Config) -> Option<String> {
+    static TICKET: OnceLock<Regex> = OnceLock::new();
+    static TIME: OnceLock<Regex> = OnceLock::new();
+    let ticket = smart.ticket.trim();
+    let time = smart.time.trim();
+    if ticket.is_empty() {
+        let filled = !time.is_empty() || !smart.comment.trim().is_empty();
+        return filled.then(|| t("validation.jira_ticket_missing").to_string());
+    }
+
+    let ticket_re = TICKET.get_or_init(|| Regex::new(r"^([A-Z][A-Z0-9_]+)-[0-9]+$").unwrap());
+    let Some(captures) = ticket_re.captures(ticket) else {
+        return Some(tf("validation.jira_ticket", &[("ticket", ticket)]));
+    };
+    let projects = config.jira.as_ref().and_then(|jira| jira.projects.as_ref());
+    if let Some(projects) = projects.filter(|p| !p.is_empty()) {
+        if !projects.iter().any(|p| p == &captures[1]) {
+            return Some(tf("validation.jira_project", &[("project", &captures[1]), ("projects", &projects.join(", "))]));
+        }
+    }
+    let time_re = TIME.get_or_init(|| Regex::new(r"^([0-9]+(\.[0-9]+)?[wdhm]\s*)+$").unwrap());
+    if !time.is_empty() && !time_re.is_match(time) {
+        return Some(tf("validation.jira_time", &[("time", time)]));
     }
     None
-}
\ No newline at end of file
+}
+
+// Server-side limits on the final text: the header and each footer line
+// (`header_max_length`, `footer_max_length`)
+pub fn validate_lengths(text: &str, config: &Config) -> Option<String> {
+    let header_max = config.header_max_length.unwrap_or_else(crate::config::default_header_max_length);
+    let header = text.lines().next().unwrap_or_default();
+    let length = header.chars().count();
+    if length > header_max {
+        return Some(tf("validation.header_too_long", &[("max", &header_max.to_string()), ("length", &length.to_string())]));
+    }
+    let footer_max = config.footer_max_length?;
+    let parsed = parse_message(text);
+    let breaking = parsed.breaking.map(|b| format!("BREAKING CHANGE: {}", b));
+    let footer = parsed.footers.into_iter().chain(breaking).find(|f| f.chars().count() > footer_max)?;
+    Some(tf("validation.footer_too_long", &[
+        ("max", &footer_max.to_string()),
+        ("footer", &footer.chars().take(20).collect::<String>()),
+    ]))
+}
+
+// Only a warning: the size at which some servers start rejecting messages
+pub fn message_size_warning(text: &str, config: &Config) -> Option<String> {
+    let max = config.message_max_bytes.unwrap_or_else(crate::config::default_message_max_bytes);
+    (text.len() > max).then(|| tf("validation.message_too_big", &[("max", &max.to_string()), ("size", &text.len().to_string())]))
+}
+
+// Only a warning: the first body line over `body_max_line_length` (numbered from 1)
+pub fn body_line_warning(body: &str, config: &Config) -> Option<String> {
+    let max = config.body_max_line_length.unwrap_or_else(crate::config::default_body_max_line_length);
+    let (number, length) = body.lines()
+        .map(|line| line.chars().count())
+        .enumerate()
+        .find(|(_, length)| *length > max)?;
+    Some(tf("validation.body_line_too_long", &[
+        ("line", &(number + 1).to_string()),
+        ("length", &length.to_string()),
+        ("max", &max.to_string()),
+    ]))
+}
+
+// A rule violation tied to the message part it concerns
+#[derive(Debug, Clone, PartialEq)]
+pub struct ValidationError {
+    pub field: &'static str,
+    pub message: String,
+}
+
+// Everything the wizard would refuse, for callers without a TUI
+pub fn validate_message(message: &CommitMessage, config: &Config) -> Vec<ValidationError> {
+    let mut errors = Vec::new();
+    let types = config.types.as_deref().unwrap_or(&[]);
+
+    match &message.commit_type {
+        None => errors.push(ValidationError { field: "type", message: t("validation.type_required").to_string() }),
+        Some(ty) if !types.contains(ty) => errors.push(ValidationError {
+            field: "type",
+            message: tf("validation.type_unknown", &[("type", ty), ("types", &types.join(", "))]),
+        }),
+        Some(_) => {}
+    }
+    if let Some(msg) = validate_scope(message.commit_type.as_deref(), message.scope.as_deref(), config) {
+        errors.push(ValidationError { field: "scope", message: msg });
+    }
+    if let Some(msg) = validate_subject(message.commit_type.as_deref(), &message.subject, config) {
+        errors.push(ValidationError { field: "subject", message: msg });
+    }
+    if let Some(msg) = message.breaking.as_deref().and_then(|breaking| validate_breaking(breaking, config)) {
+        errors.push(ValidationError { field: "breaking", message: msg });
+    }
+    if let Some(msg) = validate_issues(&message.footers.join("\n"), config) {
+        errors.push(ValidationError { field: "issues", message: msg });
+    }
+    if let Some(msg) = validate_lengths(&message.format_with(config), config) {
+        errors.push(ValidationError { field: "length", message: msg });
+    }
+    errors
+}